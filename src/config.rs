@@ -4,8 +4,15 @@ use serde::Deserialize;
 
 use crate::autoscalers::cluster_autoscaler::cluster_autoscaler::ClusterAutoscalerConfig;
 use crate::autoscalers::horizontal_pod_autoscaler::horizontal_pod_autoscaler::HorizontalPodAutoscalerConfig;
+use crate::controllers::node_lifecycle_controller::NodeLifecycleControllerConfig;
+use crate::descheduler::descheduler::DeschedulerConfig;
+use crate::failure_injector::failure_injector::FailureInjectorConfig;
 
 use crate::core::node::Node;
+use crate::core::node_component::{CpuContentionConfig, NodePressureConfig, ResourceLimitsConfig};
+use crate::core::scheduler::extender::SchedulerExtenderConfig;
+use crate::core::scheduler::kube_scheduler::KubeSchedulerConfig;
+use crate::core::scheduler::model::PodSchedulingTimeModelConfig;
 
 use crate::metrics::printer::MetricsPrinterConfig;
 
@@ -20,11 +27,32 @@ pub struct SimulationConfig {
     pub cluster_autoscaler: ClusterAutoscalerConfig,
     #[serde(default)]
     pub horizontal_pod_autoscaler: HorizontalPodAutoscalerConfig,
+    #[serde(default)]
+    pub descheduler: DeschedulerConfig,
+    #[serde(default)]
+    pub failure_injector: FailureInjectorConfig,
+    #[serde(default)]
+    pub node_lifecycle_controller: NodeLifecycleControllerConfig,
+    #[serde(default)]
+    pub node_pressure: NodePressureConfig,
+    #[serde(default)]
+    pub resource_limits: ResourceLimitsConfig,
+    #[serde(default)]
+    pub cpu_contention: CpuContentionConfig,
     pub metrics_printer: Option<MetricsPrinterConfig>,
     pub default_cluster: Option<Vec<NodeGroup>>,
-    // TODO: In SchedulerConfig struct two fields below:
+    /// Scheduling profiles of kube-scheduler. If not set the default profile is used.
+    #[serde(default)]
+    pub scheduler: KubeSchedulerConfig,
+    /// Model of simulated duration of the scheduling algorithm run for a pod. If not set, constant
+    /// time per node is used.
+    #[serde(default)]
+    pub scheduling_time_model: PodSchedulingTimeModelConfig,
+    /// If set, the default scheduler delegates scheduling decisions to the external extender
+    /// instead of scheduling profiles.
+    pub scheduler_extender: Option<SchedulerExtenderConfig>,
+    // TODO: In SchedulerConfig struct field below:
     pub scheduling_cycle_interval: f64, // in seconds
-    pub enable_unscheduled_pods_conditional_move: bool,
     // Simulated network delays, as = api server, ps = persistent storage, ca = cluster autoscaler,
     // hpa = horizontal pod autoscaler.
     // All delays are in seconds with fractional part. Assuming all delays are bidirectional.
@@ -34,6 +62,79 @@ pub struct SimulationConfig {
     pub as_to_node_network_delay: f64,
     pub as_to_ca_network_delay: f64,
     pub as_to_hpa_network_delay: f64,
+    pub as_to_descheduler_network_delay: f64,
+    pub as_to_node_lifecycle_controller_network_delay: f64,
+    /// Scheduler components running alongside the default one, each with its own cache and
+    /// scheduling cycle. Pods are routed to them by the `scheduler_name` label.
+    #[serde(default)]
+    pub additional_schedulers: Vec<AdditionalSchedulerConfig>,
+}
+
+/// Config of a scheduler component besides the default one. Fields which are not set are taken
+/// from the top level of simulation config.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct AdditionalSchedulerConfig {
+    /// Name of the simulation component, should be unique.
+    pub name: String,
+    /// Pods with `scheduler_name` equal to one of the profile names are scheduled by this
+    /// component instead of the default one.
+    pub scheduler: KubeSchedulerConfig,
+    /// If set, decisions are delegated to the external extender, profiles are used only for routing.
+    pub extender: Option<SchedulerExtenderConfig>,
+    pub scheduling_time_model: Option<PodSchedulingTimeModelConfig>,
+    pub scheduling_cycle_interval: Option<f64>,
+    pub ps_to_sched_network_delay: Option<f64>,
+    pub sched_to_as_network_delay: Option<f64>,
+}
+
+/// Settings of a single scheduler component with defaults resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchedulerComponentConfig {
+    pub name: String,
+    pub scheduler: KubeSchedulerConfig,
+    pub extender: Option<SchedulerExtenderConfig>,
+    pub scheduling_time_model: PodSchedulingTimeModelConfig,
+    pub scheduling_cycle_interval: f64,
+    pub ps_to_sched_network_delay: f64,
+    pub sched_to_as_network_delay: f64,
+}
+
+impl SimulationConfig {
+    pub fn default_scheduler_component(&self) -> SchedulerComponentConfig {
+        SchedulerComponentConfig {
+            name: "scheduler".to_string(),
+            scheduler: self.scheduler.clone(),
+            extender: self.scheduler_extender.clone(),
+            scheduling_time_model: self.scheduling_time_model.clone(),
+            scheduling_cycle_interval: self.scheduling_cycle_interval,
+            ps_to_sched_network_delay: self.ps_to_sched_network_delay,
+            sched_to_as_network_delay: self.sched_to_as_network_delay,
+        }
+    }
+
+    pub fn additional_scheduler_components(&self) -> Vec<SchedulerComponentConfig> {
+        self.additional_schedulers
+            .iter()
+            .map(|config| SchedulerComponentConfig {
+                name: config.name.clone(),
+                scheduler: config.scheduler.clone(),
+                extender: config.extender.clone(),
+                scheduling_time_model: config
+                    .scheduling_time_model
+                    .clone()
+                    .unwrap_or_else(|| self.scheduling_time_model.clone()),
+                scheduling_cycle_interval: config
+                    .scheduling_cycle_interval
+                    .unwrap_or(self.scheduling_cycle_interval),
+                ps_to_sched_network_delay: config
+                    .ps_to_sched_network_delay
+                    .unwrap_or(self.ps_to_sched_network_delay),
+                sched_to_as_network_delay: config
+                    .sched_to_as_network_delay
+                    .unwrap_or(self.sched_to_as_network_delay),
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Default, Debug, Deserialize, PartialEq)]
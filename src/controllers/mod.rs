@@ -0,0 +1 @@
+pub mod node_lifecycle_controller;
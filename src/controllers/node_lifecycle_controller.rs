@@ -0,0 +1,271 @@
+//! Implements node lifecycle controller which watches node heartbeats and taints nodes that have
+//! stopped sending them, so that their pods are evicted after the toleration timeout.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use dslab_core::event::EventData;
+use dslab_core::{cast, log_debug, log_info, Event, EventHandler, SimulationContext};
+use serde::Deserialize;
+
+use crate::config::SimulationConfig;
+use crate::core::common::SimComponentId;
+use crate::core::events::{
+    NodeHeartbeat, NodeRemovedFromCluster, RunNodeLifecycleControllerCycle, TaintNodeRequest,
+    UntaintNodeRequest, UpdateNodeConditionRequest,
+};
+use crate::core::node::{
+    NodeConditionType, Taint, TaintEffect, NODE_NOT_READY_TAINT_KEY, NODE_UNREACHABLE_TAINT_KEY,
+};
+use crate::core::pod::{Pod, Toleration, TolerationOperator};
+
+/// Every `node_monitor_period` seconds controller checks the last heartbeats of nodes. If a node
+/// has not sent a heartbeat for `node_monitor_grace_period` seconds, its `NodeReady` condition
+/// is set to `Unknown` and it gets NoSchedule and NoExecute `node.kubernetes.io/unreachable`
+/// taints. Node component evicts running pods when their toleration of the NoExecute taint runs
+/// out. As soon as the node sends a heartbeat again, the condition is set back to `True` and
+/// the taints are removed.
+///
+/// Nodes are watched since their first heartbeat until they are removed from the cluster.
+pub struct NodeLifecycleController {
+    api_server: SimComponentId,
+
+    /// Map from node name to the time of its last heartbeat and whether it is reachable.
+    nodes: BTreeMap<String, (f64, bool)>,
+
+    ctx: SimulationContext,
+    config: Rc<SimulationConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct NodeLifecycleControllerConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// Interval of heartbeats sent by nodes, like `nodeStatusUpdateFrequency` of kubelet.
+    #[serde(default = "node_status_update_frequency_default")]
+    pub node_status_update_frequency: f64,
+    #[serde(default = "node_monitor_period_default")]
+    pub node_monitor_period: f64,
+    #[serde(default = "node_monitor_grace_period_default")]
+    pub node_monitor_grace_period: f64,
+    /// Toleration seconds of not-ready and unreachable NoExecute taints which are added to pods
+    /// not tolerating them on creation, like DefaultTolerationSeconds admission plugin does.
+    #[serde(default = "default_toleration_seconds_default")]
+    pub default_toleration_seconds: f64,
+}
+
+impl Default for NodeLifecycleControllerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+            node_status_update_frequency: node_status_update_frequency_default(),
+            node_monitor_period: node_monitor_period_default(),
+            node_monitor_grace_period: node_monitor_grace_period_default(),
+            default_toleration_seconds: default_toleration_seconds_default(),
+        }
+    }
+}
+
+fn enabled_default() -> bool {
+    false // disabled by default
+}
+fn node_status_update_frequency_default() -> f64 {
+    10.0 // 10 seconds
+}
+fn node_monitor_period_default() -> f64 {
+    5.0 // 5 seconds
+}
+fn node_monitor_grace_period_default() -> f64 {
+    40.0 // 40 seconds
+}
+fn default_toleration_seconds_default() -> f64 {
+    300.0 // 5 minutes
+}
+
+/// Lets the pod tolerate NoExecute taints of not ready and unreachable nodes for limited time,
+/// unless it already tolerates them.
+pub fn add_default_tolerations(pod: &mut Pod, toleration_seconds: f64) {
+    for key in [NODE_NOT_READY_TAINT_KEY, NODE_UNREACHABLE_TAINT_KEY] {
+        let taint = Taint {
+            key: key.to_string(),
+            value: Default::default(),
+            effect: TaintEffect::NoExecute,
+        };
+        if taint.is_tolerated_by(&pod.spec.tolerations) {
+            continue;
+        }
+        pod.spec.tolerations.push(Toleration {
+            key: key.to_string(),
+            operator: TolerationOperator::Exists,
+            value: Default::default(),
+            effect: Some(TaintEffect::NoExecute),
+            toleration_seconds: Some(toleration_seconds),
+        });
+    }
+}
+
+impl NodeLifecycleController {
+    pub fn new(
+        api_server: SimComponentId,
+        ctx: SimulationContext,
+        config: Rc<SimulationConfig>,
+    ) -> Self {
+        Self {
+            api_server,
+            nodes: Default::default(),
+            ctx,
+            config,
+        }
+    }
+
+    pub fn start(&mut self) {
+        log_info!(
+            self.ctx,
+            "Node lifecycle controller started checking nodes every {} seconds",
+            self.config.node_lifecycle_controller.node_monitor_period
+        );
+        self.ctx.emit_self_now(RunNodeLifecycleControllerCycle {});
+    }
+
+    fn emit_to_api_server<T: EventData>(&self, data: T) {
+        self.ctx.emit(
+            data,
+            self.api_server,
+            self.config.as_to_node_lifecycle_controller_network_delay,
+        );
+    }
+
+    fn unreachable_taint(effect: TaintEffect) -> Taint {
+        Taint {
+            key: NODE_UNREACHABLE_TAINT_KEY.to_string(),
+            value: Default::default(),
+            effect,
+        }
+    }
+
+    fn mark_node_unreachable(&self, node_name: &str, event_time: f64) {
+        log_debug!(
+            self.ctx,
+            "Node {:?} has not sent heartbeats since {}",
+            node_name,
+            self.nodes[node_name].0
+        );
+        self.emit_to_api_server(UpdateNodeConditionRequest {
+            node_name: node_name.to_string(),
+            condition_type: NodeConditionType::NodeReady,
+            status: "Unknown".to_string(),
+            transition_time: event_time,
+        });
+        for effect in [TaintEffect::NoSchedule, TaintEffect::NoExecute] {
+            self.emit_to_api_server(TaintNodeRequest {
+                node_name: node_name.to_string(),
+                taint: Self::unreachable_taint(effect),
+            });
+        }
+    }
+
+    fn mark_node_reachable(&self, node_name: &str, event_time: f64) {
+        log_debug!(self.ctx, "Node {:?} is reachable again", node_name);
+        self.emit_to_api_server(UpdateNodeConditionRequest {
+            node_name: node_name.to_string(),
+            condition_type: NodeConditionType::NodeReady,
+            status: "True".to_string(),
+            transition_time: event_time,
+        });
+        for effect in [TaintEffect::NoSchedule, TaintEffect::NoExecute] {
+            self.emit_to_api_server(UntaintNodeRequest {
+                node_name: node_name.to_string(),
+                key: NODE_UNREACHABLE_TAINT_KEY.to_string(),
+                effect,
+            });
+        }
+    }
+
+    fn monitor_node_health(&mut self, event_time: f64) {
+        let grace_period = self
+            .config
+            .node_lifecycle_controller
+            .node_monitor_grace_period;
+        let unreachable_nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|(_, &(last_heartbeat_time, reachable))| {
+                reachable && event_time - last_heartbeat_time > grace_period
+            })
+            .map(|(node_name, _)| node_name.clone())
+            .collect();
+        for node_name in unreachable_nodes {
+            self.mark_node_unreachable(&node_name, event_time);
+            self.nodes.get_mut(&node_name).unwrap().1 = false;
+        }
+
+        self.ctx.emit_self(
+            RunNodeLifecycleControllerCycle {},
+            self.config.node_lifecycle_controller.node_monitor_period,
+        );
+    }
+}
+
+impl EventHandler for NodeLifecycleController {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            RunNodeLifecycleControllerCycle {} => {
+                self.monitor_node_health(event.time);
+            }
+            NodeHeartbeat {
+                node_name,
+                heartbeat_time,
+            } => {
+                let (_, reachable) = self
+                    .nodes
+                    .insert(node_name.clone(), (heartbeat_time, true))
+                    .unwrap_or((heartbeat_time, true));
+                if !reachable {
+                    self.mark_node_reachable(&node_name, event.time);
+                }
+            }
+            NodeRemovedFromCluster { node_name, .. } => {
+                self.nodes.remove(&node_name);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::node::{Taint, TaintEffect, NODE_UNREACHABLE_TAINT_KEY};
+    use crate::core::pod::{Pod, Toleration, TolerationOperator};
+
+    use super::add_default_tolerations;
+
+    #[test]
+    fn test_default_tolerations_are_added_once() {
+        let mut pod = Pod::new("pod".to_string(), 1000, 1073741824, None);
+        add_default_tolerations(&mut pod, 300.0);
+        assert_eq!(2, pod.spec.tolerations.len());
+        add_default_tolerations(&mut pod, 300.0);
+        assert_eq!(2, pod.spec.tolerations.len());
+
+        // Pod which tolerates unreachable nodes forever keeps its toleration.
+        let mut tolerating_pod = Pod::new("tolerating_pod".to_string(), 1000, 1073741824, None);
+        tolerating_pod.spec.tolerations.push(Toleration {
+            key: NODE_UNREACHABLE_TAINT_KEY.to_string(),
+            operator: TolerationOperator::Exists,
+            ..Default::default()
+        });
+        add_default_tolerations(&mut tolerating_pod, 300.0);
+        let unreachable_taint = Taint {
+            key: NODE_UNREACHABLE_TAINT_KEY.to_string(),
+            value: Default::default(),
+            effect: TaintEffect::NoExecute,
+        };
+        let tolerations: Vec<&Toleration> = tolerating_pod
+            .spec
+            .tolerations
+            .iter()
+            .filter(|toleration| toleration.tolerates(&unreachable_taint))
+            .collect();
+        assert_eq!(1, tolerations.len());
+        assert_eq!(None, tolerations[0].toleration_seconds);
+    }
+}
@@ -0,0 +1,314 @@
+//! Type definitions for pod scheduling constraints which refer to labels of other objects.
+//! https://kubernetes.io/docs/concepts/scheduling-eviction/assign-pod-node/#affinity-and-anti-affinity
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Affinity {
+    /// Scheduling rules for a pod based on node labels.
+    #[serde(default)]
+    pub node_affinity: Option<NodeAffinity>,
+    /// Rules to co-locate a pod in the same topology domain with other pods.
+    #[serde(default)]
+    pub pod_affinity: Option<PodAffinity>,
+    /// Rules to avoid placing a pod in the same topology domain with other pods.
+    #[serde(default)]
+    pub pod_anti_affinity: Option<PodAffinity>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct NodeAffinity {
+    /// Terms of `requiredDuringSchedulingIgnoredDuringExecution`. Node must match at least one of
+    /// them to be feasible for a pod. Empty list means no requirements.
+    #[serde(default)]
+    pub required: Vec<NodeSelectorTerm>,
+    /// Terms of `preferredDuringSchedulingIgnoredDuringExecution`. Scheduler prefers nodes matching
+    /// terms with the greatest sum of weights.
+    #[serde(default)]
+    pub preferred: Vec<PreferredSchedulingTerm>,
+}
+
+/// Requirements of a term are ANDed.
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct NodeSelectorTerm {
+    #[serde(default)]
+    pub match_expressions: Vec<NodeSelectorRequirement>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct PreferredSchedulingTerm {
+    /// Weight in range 1-100.
+    pub weight: u32,
+    pub preference: NodeSelectorTerm,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct NodeSelectorRequirement {
+    /// Label key that the requirement applies to.
+    pub key: String,
+    pub operator: NodeSelectorOperator,
+    /// Must be non-empty for In and NotIn, empty for Exists and DoesNotExist and contain a single
+    /// integer for Gt and Lt.
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum NodeSelectorOperator {
+    In,
+    NotIn,
+    Exists,
+    DoesNotExist,
+    Gt,
+    Lt,
+}
+
+/// Pod affinity or anti-affinity depending on the field of `Affinity` it is set to.
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct PodAffinity {
+    /// All terms must be satisfied for a node to be feasible.
+    #[serde(default)]
+    pub required: Vec<PodAffinityTerm>,
+    #[serde(default)]
+    pub preferred: Vec<WeightedPodAffinityTerm>,
+}
+
+/// Defines a set of pods selected by labels and a topology domain which is a set of nodes with the
+/// same value of `topology_key` label. Pod (anti-)affinity is satisfied on a node if selected pods
+/// are (not) running in the topology domain of the node.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct PodAffinityTerm {
+    #[serde(default)]
+    pub label_selector: LabelSelector,
+    /// Node label key, e.g. zone or hostname.
+    pub topology_key: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct WeightedPodAffinityTerm {
+    /// Weight in range 1-100.
+    pub weight: u32,
+    pub pod_affinity_term: PodAffinityTerm,
+}
+
+/// Requirements of match labels and match expressions are ANDed. Empty selector matches all
+/// objects.
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct LabelSelector {
+    #[serde(default)]
+    pub match_labels: HashMap<String, String>,
+    /// Expressions have the same format as for nodes but Gt and Lt operators are not expected.
+    #[serde(default)]
+    pub match_expressions: Vec<NodeSelectorRequirement>,
+}
+
+/// Describes how pods matching the label selector should be spread among topology domains.
+/// https://kubernetes.io/docs/concepts/scheduling-eviction/topology-spread-constraints/
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TopologySpreadConstraint {
+    /// Maximal permitted difference between the number of matching pods in any two eligible
+    /// topology domains. Must be greater than zero.
+    pub max_skew: u32,
+    /// Node label key, nodes with the same value of the label belong to the same domain.
+    pub topology_key: String,
+    #[serde(default)]
+    pub when_unsatisfiable: UnsatisfiableConstraintAction,
+    #[serde(default)]
+    pub label_selector: LabelSelector,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum UnsatisfiableConstraintAction {
+    /// Constraint is checked by the filter, nodes which would violate max skew are infeasible.
+    #[default]
+    DoNotSchedule,
+    /// Constraint only affects scoring, nodes which reduce skew are preferred.
+    ScheduleAnyway,
+}
+
+impl LabelSelector {
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        self.match_labels
+            .iter()
+            .all(|(key, value)| labels.get(key) == Some(value))
+            && self
+                .match_expressions
+                .iter()
+                .all(|requirement| requirement.matches(labels))
+    }
+}
+
+impl NodeSelectorRequirement {
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        let label = labels.get(&self.key);
+        match self.operator {
+            NodeSelectorOperator::In => label.is_some_and(|value| self.values.contains(value)),
+            NodeSelectorOperator::NotIn => !label.is_some_and(|value| self.values.contains(value)),
+            NodeSelectorOperator::Exists => label.is_some(),
+            NodeSelectorOperator::DoesNotExist => label.is_none(),
+            NodeSelectorOperator::Gt | NodeSelectorOperator::Lt => {
+                let (label_value, requirement_value) = match (
+                    label.and_then(|value| value.parse::<i64>().ok()),
+                    self.values
+                        .first()
+                        .and_then(|value| value.parse::<i64>().ok()),
+                ) {
+                    (Some(label_value), Some(requirement_value)) => {
+                        (label_value, requirement_value)
+                    }
+                    _ => return false,
+                };
+                if self.operator == NodeSelectorOperator::Gt {
+                    label_value > requirement_value
+                } else {
+                    label_value < requirement_value
+                }
+            }
+        }
+    }
+}
+
+impl NodeSelectorTerm {
+    /// Term without requirements matches no objects like in k8s.
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        !self.match_expressions.is_empty()
+            && self
+                .match_expressions
+                .iter()
+                .all(|requirement| requirement.matches(labels))
+    }
+}
+
+impl NodeAffinity {
+    pub fn required_terms_match(&self, labels: &HashMap<String, String>) -> bool {
+        self.required.is_empty() || self.required.iter().any(|term| term.matches(labels))
+    }
+
+    /// Sum of weights of preferred terms which match the labels.
+    pub fn preferred_terms_weight(&self, labels: &HashMap<String, String>) -> u32 {
+        self.preferred
+            .iter()
+            .filter(|term| term.preference.matches(labels))
+            .map(|term| term.weight)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::core::affinity::{
+        LabelSelector, NodeAffinity, NodeSelectorOperator, NodeSelectorRequirement,
+        NodeSelectorTerm, PreferredSchedulingTerm,
+    };
+
+    fn requirement(
+        key: &str,
+        operator: NodeSelectorOperator,
+        values: &[&str],
+    ) -> NodeSelectorRequirement {
+        NodeSelectorRequirement {
+            key: key.to_string(),
+            operator,
+            values: values.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    fn labels() -> HashMap<String, String> {
+        HashMap::from([
+            ("zone".to_string(), "zone_a".to_string()),
+            ("cpu_count".to_string(), "16".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_node_selector_operators() {
+        let labels = labels();
+
+        assert!(
+            requirement("zone", NodeSelectorOperator::In, &["zone_a", "zone_b"]).matches(&labels)
+        );
+        assert!(!requirement("zone", NodeSelectorOperator::In, &["zone_b"]).matches(&labels));
+        assert!(!requirement("disk", NodeSelectorOperator::In, &["ssd"]).matches(&labels));
+
+        assert!(requirement("zone", NodeSelectorOperator::NotIn, &["zone_b"]).matches(&labels));
+        assert!(!requirement("zone", NodeSelectorOperator::NotIn, &["zone_a"]).matches(&labels));
+        assert!(requirement("disk", NodeSelectorOperator::NotIn, &["ssd"]).matches(&labels));
+
+        assert!(requirement("zone", NodeSelectorOperator::Exists, &[]).matches(&labels));
+        assert!(!requirement("disk", NodeSelectorOperator::Exists, &[]).matches(&labels));
+        assert!(requirement("disk", NodeSelectorOperator::DoesNotExist, &[]).matches(&labels));
+        assert!(!requirement("zone", NodeSelectorOperator::DoesNotExist, &[]).matches(&labels));
+
+        assert!(requirement("cpu_count", NodeSelectorOperator::Gt, &["8"]).matches(&labels));
+        assert!(!requirement("cpu_count", NodeSelectorOperator::Gt, &["16"]).matches(&labels));
+        assert!(requirement("cpu_count", NodeSelectorOperator::Lt, &["32"]).matches(&labels));
+        assert!(!requirement("cpu_count", NodeSelectorOperator::Lt, &["16"]).matches(&labels));
+        // not integers
+        assert!(!requirement("zone", NodeSelectorOperator::Gt, &["8"]).matches(&labels));
+        assert!(!requirement("cpu_count", NodeSelectorOperator::Lt, &["many"]).matches(&labels));
+    }
+
+    #[test]
+    fn test_node_affinity_terms() {
+        let labels = labels();
+        let matching_term = NodeSelectorTerm {
+            match_expressions: vec![
+                requirement("zone", NodeSelectorOperator::In, &["zone_a"]),
+                requirement("cpu_count", NodeSelectorOperator::Gt, &["4"]),
+            ],
+        };
+        let not_matching_term = NodeSelectorTerm {
+            match_expressions: vec![
+                requirement("zone", NodeSelectorOperator::In, &["zone_a"]),
+                requirement("disk", NodeSelectorOperator::Exists, &[]),
+            ],
+        };
+
+        assert!(!NodeSelectorTerm::default().matches(&labels));
+        assert!(NodeAffinity::default().required_terms_match(&labels));
+
+        let mut node_affinity = NodeAffinity {
+            required: vec![not_matching_term.clone()],
+            preferred: vec![
+                PreferredSchedulingTerm {
+                    weight: 10,
+                    preference: matching_term.clone(),
+                },
+                PreferredSchedulingTerm {
+                    weight: 50,
+                    preference: not_matching_term.clone(),
+                },
+                PreferredSchedulingTerm {
+                    weight: 5,
+                    preference: matching_term.clone(),
+                },
+            ],
+        };
+        assert!(!node_affinity.required_terms_match(&labels));
+        assert_eq!(15, node_affinity.preferred_terms_weight(&labels));
+
+        // required terms are ORed
+        node_affinity.required.push(matching_term);
+        assert!(node_affinity.required_terms_match(&labels));
+    }
+
+    #[test]
+    fn test_label_selector() {
+        let labels = labels();
+
+        assert!(LabelSelector::default().matches(&labels));
+
+        let mut selector = LabelSelector {
+            match_labels: HashMap::from([("zone".to_string(), "zone_a".to_string())]),
+            match_expressions: vec![],
+        };
+        assert!(selector.matches(&labels));
+
+        selector.match_expressions = vec![requirement("disk", NodeSelectorOperator::Exists, &[])];
+        assert!(!selector.matches(&labels));
+    }
+}
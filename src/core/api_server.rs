@@ -6,21 +6,29 @@ use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-use dslab_core::{Event, EventHandler, SimulationContext};
+use dslab_core::{log_debug, Event, EventHandler, SimulationContext};
 
 use crate::autoscalers::horizontal_pod_autoscaler::interface::PodGroupInfo;
 use crate::cast_box;
+use crate::controllers::node_lifecycle_controller::add_default_tolerations;
 use crate::core::common::SimComponentId;
 use crate::core::events::{
     AssignPodToNodeRequest, AssignPodToNodeResponse, BindPodToNodeRequest,
-    ClusterAutoscalerRequest, ClusterAutoscalerResponse, CreateNodeRequest, CreateNodeResponse,
-    CreatePodGroupRequest, CreatePodRequest, NodeAddedToCluster, NodeRemovedFromCluster,
-    PodFinishedRunning, PodNotScheduled, PodRemovedFromNode, PodStartedRunning, RegisterPodGroup,
-    RemoveNodeRequest, RemoveNodeResponse, RemovePodRequest, RemovePodResponse,
+    ClusterAutoscalerRequest, ClusterAutoscalerResponse, CordonNodeRequest, CordonNodeResponse,
+    CreateNodeRequest, CreateNodeResponse, CreatePodGroupRequest, CreatePodRequest,
+    DeschedulerRequest, DeschedulerResponse, DrainNodeRequest, EvictPodRequest, EvictPodResponse,
+    FailNodeRequest, NodeAddedToCluster, NodeDrained, NodeFailed, NodeHeartbeat, NodeRecovered,
+    NodeRemovedFromCluster, PodEvictedFromNode, PodFinishedRunning, PodNotScheduled,
+    PodRemovedFromNode, PodRestarted, PodStartedRunning, RecoverNodeRequest,
+    RegisterNodeForFailures, RegisterPodGroup, RemoveNodeRequest, RemoveNodeResponse,
+    RemovePodRequest, RemovePodResponse, TaintNodeRequest, TaintNodeResponse, UncordonNodeRequest,
+    UncordonNodeResponse, UnregisterNodeForFailures, UntaintNodeRequest, UntaintNodeResponse,
+    UpdateNodeConditionRequest,
 };
 use crate::core::node::Node;
 use crate::core::node_component::NodeComponent;
 use crate::core::node_component_pool::NodeComponentPool;
+use crate::core::pod::{EvictionReason, Pod, PodConditionType, PodFailureReason};
 use crate::metrics::collector::MetricsCollector;
 
 use crate::config::SimulationConfig;
@@ -29,6 +37,9 @@ pub struct KubeApiServer {
     persistent_storage: SimComponentId,
     cluster_autoscaler: Option<SimComponentId>,
     horizontal_pod_autoscaler: Option<SimComponentId>,
+    descheduler: Option<SimComponentId>,
+    failure_injector: Option<SimComponentId>,
+    node_lifecycle_controller: Option<SimComponentId>,
 
     pub ctx: SimulationContext,
     config: Rc<SimulationConfig>,
@@ -37,6 +48,9 @@ pub struct KubeApiServer {
     pending_node_creation_requests: HashMap<String, Node>,
     pending_node_removal_requests: HashSet<String>,
     pending_pod_removal_requests: HashSet<String>,
+    /// Map from name of the node being drained to the grace period of its pods and the flag whether
+    /// to remove the node after it is drained.
+    pending_drains: HashMap<String, (f64, bool)>,
     // Mapping from node name to it's component
     created_nodes: HashMap<String, Rc<RefCell<NodeComponent>>>,
 
@@ -48,6 +62,7 @@ impl KubeApiServer {
         persistent_storage_id: SimComponentId,
         cluster_autoscaler_id: Option<SimComponentId>,
         horizontal_pod_autoscaler_id: Option<SimComponentId>,
+        descheduler_id: Option<SimComponentId>,
         ctx: SimulationContext,
         config: Rc<SimulationConfig>,
         metrics_collector: Rc<RefCell<MetricsCollector>>,
@@ -56,12 +71,16 @@ impl KubeApiServer {
             persistent_storage: persistent_storage_id,
             cluster_autoscaler: cluster_autoscaler_id,
             horizontal_pod_autoscaler: horizontal_pod_autoscaler_id,
+            descheduler: descheduler_id,
+            failure_injector: None,
+            node_lifecycle_controller: None,
             ctx,
             config,
             node_pool: Default::default(),
             pending_node_creation_requests: Default::default(),
             pending_node_removal_requests: Default::default(),
             pending_pod_removal_requests: Default::default(),
+            pending_drains: Default::default(),
             created_nodes: Default::default(),
             metrics_collector,
         }
@@ -99,9 +118,18 @@ impl KubeApiServer {
             .pending_node_creation_requests
             .remove(node_name)
             .unwrap();
+        if let Some(failure_injector) = self.failure_injector {
+            self.ctx.emit_now(
+                RegisterNodeForFailures { node: node.clone() },
+                failure_injector,
+            );
+        }
         let node_component =
             self.node_pool
                 .allocate_component(node, self.ctx.id(), self.config.clone());
+        node_component.borrow_mut().start_heartbeats();
+        node_component.borrow_mut().start_pressure_checks();
+        node_component.borrow_mut().start_limits_checks();
         self.add_node_component(node_component);
 
         self.ctx.emit(
@@ -114,14 +142,50 @@ impl KubeApiServer {
         );
     }
 
+    /// Sets default tolerations of the pod and redirects its creation to persistent storage.
+    fn handle_create_pod(&mut self, mut pod: Pod) {
+        if self.config.node_lifecycle_controller.enabled {
+            add_default_tolerations(
+                &mut pod,
+                self.config
+                    .node_lifecycle_controller
+                    .default_toleration_seconds,
+            );
+        }
+        self.metrics_collector
+            .borrow_mut()
+            .gauge_metrics
+            .current_pods += 1;
+        self.ctx.emit(
+            CreatePodRequest { pod },
+            self.persistent_storage,
+            self.config.as_to_ps_network_delay,
+        );
+    }
+
     fn handle_node_removal(&mut self, node_name: &String) {
         let node_component = self.created_nodes.remove(node_name).unwrap();
         self.node_pool.reclaim_component(node_component);
     }
 
+    /// Failure injector is told about nodes added to and removed from the cluster.
+    pub fn set_failure_injector(&mut self, failure_injector_id: SimComponentId) {
+        self.failure_injector = Some(failure_injector_id);
+    }
+
+    /// Node lifecycle controller gets heartbeats of nodes and is told about removed nodes.
+    pub fn set_node_lifecycle_controller(&mut self, node_lifecycle_controller_id: SimComponentId) {
+        self.node_lifecycle_controller = Some(node_lifecycle_controller_id);
+    }
+
     pub fn set_node_pool(&mut self, node_pool: NodeComponentPool) {
         self.node_pool = node_pool
     }
+
+    /// Number of running pods in each pod group, shared by all node components.
+    pub fn pod_group_sizes(&self) -> Rc<RefCell<HashMap<String, usize>>> {
+        self.node_pool.pod_group_sizes()
+    }
 }
 
 impl EventHandler for KubeApiServer {
@@ -149,16 +213,7 @@ impl EventHandler for KubeApiServer {
                 self.handle_create_node(&node_name, event.time);
             }
             CreatePodRequest { pod } => {
-                self.metrics_collector
-                    .borrow_mut()
-                    .gauge_metrics
-                    .current_pods += 1;
-                // Redirects to persistent storage
-                self.ctx.emit(
-                    CreatePodRequest { pod },
-                    self.persistent_storage,
-                    self.config.as_to_ps_network_delay,
-                );
+                self.handle_create_pod(pod);
             }
             AssignPodToNodeRequest {
                 assign_time,
@@ -194,10 +249,16 @@ impl EventHandler for KubeApiServer {
             AssignPodToNodeResponse {
                 pod_name,
                 pod_requests,
+                pod_limits,
+                pod_qos_class,
+                pod_tolerations,
                 pod_group,
                 pod_group_creation_time,
                 node_name,
                 pod_duration,
+                pod_restart_policy,
+                pod_failure_probability,
+                pod_failure_time,
                 resources_usage_model_config,
             } => {
                 // Info about assignment is persisted - make bind request to node component
@@ -206,10 +267,16 @@ impl EventHandler for KubeApiServer {
                     BindPodToNodeRequest {
                         pod_name,
                         pod_requests,
+                        pod_limits,
+                        pod_qos_class,
+                        pod_tolerations,
                         pod_group,
                         pod_group_creation_time,
                         node_name,
                         pod_duration,
+                        pod_restart_policy,
+                        pod_failure_probability,
+                        pod_failure_time,
                         resources_usage_model_config,
                     },
                     node_component.borrow().id(),
@@ -244,21 +311,66 @@ impl EventHandler for KubeApiServer {
                     self.config.as_to_ps_network_delay,
                 );
             }
+            PodRestarted {
+                pod_name,
+                node_name,
+                restart_time,
+            } => {
+                self.metrics_collector
+                    .borrow_mut()
+                    .accumulated_metrics
+                    .pod_restarts += 1;
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    PodRestarted {
+                        pod_name,
+                        node_name,
+                        restart_time,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
             PodFinishedRunning {
                 pod_name,
                 node_name,
                 finish_time,
                 finish_result,
+                failure_reason,
+                qos_class,
             } => {
                 self.metrics_collector
                     .borrow_mut()
                     .accumulated_metrics
                     .internal
                     .terminated_pods += 1;
-                self.metrics_collector
-                    .borrow_mut()
-                    .accumulated_metrics
-                    .pods_succeeded += 1;
+                if finish_result == PodConditionType::PodFailed {
+                    self.metrics_collector
+                        .borrow_mut()
+                        .accumulated_metrics
+                        .pods_failed += 1;
+                    self.metrics_collector
+                        .borrow_mut()
+                        .accumulated_metrics
+                        .qos_class_metrics(&qos_class)
+                        .pods_failed += 1;
+                    if failure_reason == Some(PodFailureReason::OOMKilled) {
+                        self.metrics_collector
+                            .borrow_mut()
+                            .accumulated_metrics
+                            .pods_oom_killed += 1;
+                    }
+                } else {
+                    self.metrics_collector
+                        .borrow_mut()
+                        .accumulated_metrics
+                        .pods_succeeded += 1;
+                    self.metrics_collector
+                        .borrow_mut()
+                        .accumulated_metrics
+                        .qos_class_metrics(&qos_class)
+                        .pods_succeeded += 1;
+                }
                 self.metrics_collector
                     .borrow_mut()
                     .gauge_metrics
@@ -270,6 +382,8 @@ impl EventHandler for KubeApiServer {
                         node_name,
                         finish_time,
                         finish_result,
+                        failure_reason,
+                        qos_class,
                     },
                     self.persistent_storage,
                     self.config.as_to_ps_network_delay,
@@ -306,6 +420,25 @@ impl EventHandler for KubeApiServer {
                 // Event from node component about completed removal.
                 self.handle_node_removal(&node_name);
                 self.pending_node_removal_requests.remove(&node_name);
+                self.pending_drains.remove(&node_name);
+                if let Some(failure_injector) = self.failure_injector {
+                    self.ctx.emit_now(
+                        UnregisterNodeForFailures {
+                            node_name: node_name.clone(),
+                        },
+                        failure_injector,
+                    );
+                }
+                if let Some(node_lifecycle_controller) = self.node_lifecycle_controller {
+                    self.ctx.emit(
+                        NodeRemovedFromCluster {
+                            removal_time,
+                            node_name: node_name.clone(),
+                        },
+                        node_lifecycle_controller,
+                        self.config.as_to_node_lifecycle_controller_network_delay,
+                    );
+                }
 
                 // Redirect to persistent storage
                 self.ctx.emit(
@@ -317,6 +450,258 @@ impl EventHandler for KubeApiServer {
                     self.config.as_to_ps_network_delay,
                 );
             }
+            NodeHeartbeat {
+                node_name,
+                heartbeat_time,
+            } => {
+                if let Some(node_lifecycle_controller) = self.node_lifecycle_controller {
+                    self.ctx.emit(
+                        NodeHeartbeat {
+                            node_name,
+                            heartbeat_time,
+                        },
+                        node_lifecycle_controller,
+                        self.config.as_to_node_lifecycle_controller_network_delay,
+                    );
+                }
+            }
+            UpdateNodeConditionRequest {
+                node_name,
+                condition_type,
+                status,
+                transition_time,
+            } => {
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    UpdateNodeConditionRequest {
+                        node_name,
+                        condition_type,
+                        status,
+                        transition_time,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            FailNodeRequest {
+                node_name,
+                failure_type,
+            } => {
+                // Node is being removed or already removed - nothing to fail.
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                // Failure happens on the node itself, so the request goes to the node component
+                // first and persistent storage finds out about it from the node.
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        FailNodeRequest {
+                            node_name,
+                            failure_type,
+                        },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            NodeFailed {
+                node_name,
+                failure_type,
+                failure_time,
+            } => {
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    NodeFailed {
+                        node_name,
+                        failure_type,
+                        failure_time,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            RecoverNodeRequest { node_name } => {
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        RecoverNodeRequest { node_name },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            NodeRecovered {
+                node_name,
+                recovery_time,
+            } => {
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    NodeRecovered {
+                        node_name,
+                        recovery_time,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            TaintNodeRequest { node_name, taint } => {
+                // Redirect to persistent storage first to persist new taint
+                self.ctx.emit(
+                    TaintNodeRequest { node_name, taint },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            TaintNodeResponse { node_name, taint } => {
+                // Node is being removed or already removed - no need to evict its pods.
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        TaintNodeRequest { node_name, taint },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            UntaintNodeRequest {
+                node_name,
+                key,
+                effect,
+            } => {
+                // Redirect to persistent storage first to persist taint removal
+                self.ctx.emit(
+                    UntaintNodeRequest {
+                        node_name,
+                        key,
+                        effect,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            UntaintNodeResponse {
+                node_name,
+                key,
+                effect,
+            } => {
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        UntaintNodeRequest {
+                            node_name,
+                            key,
+                            effect,
+                        },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            CordonNodeRequest { node_name } => {
+                // Redirect to persistent storage first to persist the node as unschedulable
+                self.ctx.emit(
+                    CordonNodeRequest { node_name },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            CordonNodeResponse { node_name } => {
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        CordonNodeRequest {
+                            node_name: node_name.clone(),
+                        },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                    // Node is cordoned as the first step of the drain, so its pods may be evicted.
+                    let pending_drain = self.pending_drains.get(&node_name).cloned();
+                    if let Some((grace_period, remove_node)) = pending_drain {
+                        self.ctx.emit(
+                            DrainNodeRequest {
+                                node_name,
+                                grace_period,
+                                remove_node,
+                            },
+                            node_component.borrow().id(),
+                            self.config.as_to_node_network_delay,
+                        );
+                    }
+                }
+            }
+            UncordonNodeRequest { node_name } => {
+                // Redirect to persistent storage first to persist the node as schedulable
+                self.ctx.emit(
+                    UncordonNodeRequest { node_name },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            UncordonNodeResponse { node_name } => {
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        UncordonNodeRequest { node_name },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            DrainNodeRequest {
+                node_name,
+                grace_period,
+                remove_node,
+            } => {
+                if self.pending_node_removal_requests.contains(&node_name)
+                    || !self.created_nodes.contains_key(&node_name)
+                {
+                    return;
+                }
+                // Node is cordoned first so that evicted pods are not placed back to it.
+                self.pending_drains
+                    .insert(node_name.clone(), (grace_period, remove_node));
+                self.ctx.emit(
+                    CordonNodeRequest { node_name },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            NodeDrained {
+                node_name,
+                drain_time,
+            } => {
+                let remove_node = match self.pending_drains.remove(&node_name) {
+                    Some((_, remove_node)) => remove_node,
+                    None => return,
+                };
+                log_debug!(
+                    self.ctx,
+                    "Node {} drained at time: {}",
+                    node_name,
+                    drain_time
+                );
+                if !remove_node || self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                // Remove the drained node the same way as on client request.
+                self.pending_node_removal_requests.insert(node_name.clone());
+                self.ctx.emit(
+                    RemoveNodeRequest { node_name },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
             ClusterAutoscalerRequest { request_type } => {
                 // Redirect to persistent storage
                 self.ctx.emit(
@@ -339,6 +724,28 @@ impl EventHandler for KubeApiServer {
                     self.config.as_to_ca_network_delay,
                 );
             }
+            DeschedulerRequest {} => {
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    DeschedulerRequest {},
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            DeschedulerResponse {
+                nodes,
+                assigned_pods,
+            } => {
+                // Redirect to descheduler
+                self.ctx.emit(
+                    DeschedulerResponse {
+                        nodes,
+                        assigned_pods,
+                    },
+                    self.descheduler.unwrap(),
+                    self.config.as_to_descheduler_network_delay,
+                );
+            }
             RemovePodRequest { pod_name } => {
                 self.pending_node_removal_requests.insert(pod_name.clone());
                 // Redirect to persistent storage
@@ -402,6 +809,94 @@ impl EventHandler for KubeApiServer {
                     self.config.as_to_ps_network_delay,
                 );
             }
+            EvictPodRequest { pod_name, reason } => {
+                // Redirect to persistent storage to find out the node the pod is assigned to
+                self.ctx.emit(
+                    EvictPodRequest { pod_name, reason },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            EvictPodResponse {
+                assigned_node,
+                pod_name,
+                reason,
+            } => {
+                // Pod is not running on any node - nothing to evict.
+                if assigned_node.is_none() {
+                    return;
+                }
+                let node_name = assigned_node.unwrap();
+                // Node is being removed or already removed - its pods are rescheduled anyway.
+                if self.pending_node_removal_requests.contains(&node_name) {
+                    return;
+                }
+                if let Some(node_component) = self.created_nodes.get(&node_name) {
+                    self.ctx.emit(
+                        EvictPodRequest { pod_name, reason },
+                        node_component.borrow().id(),
+                        self.config.as_to_node_network_delay,
+                    );
+                }
+            }
+            PodEvictedFromNode {
+                evicted,
+                eviction_time,
+                pod_name,
+                node_name,
+                reason,
+            } => {
+                if evicted {
+                    match reason {
+                        EvictionReason::Preemption => {
+                            self.metrics_collector
+                                .borrow_mut()
+                                .accumulated_metrics
+                                .pods_preempted += 1;
+                        }
+                        EvictionReason::NoExecuteTaint => {
+                            self.metrics_collector
+                                .borrow_mut()
+                                .accumulated_metrics
+                                .pods_evicted_by_taints += 1;
+                        }
+                        EvictionReason::NodeDrain => {
+                            self.metrics_collector
+                                .borrow_mut()
+                                .accumulated_metrics
+                                .pods_evicted_by_drain += 1;
+                        }
+                        EvictionReason::NodePressure => {
+                            self.metrics_collector
+                                .borrow_mut()
+                                .accumulated_metrics
+                                .pods_evicted_by_node_pressure += 1;
+                        }
+                        EvictionReason::Descheduling { ref strategy } => {
+                            *self
+                                .metrics_collector
+                                .borrow_mut()
+                                .accumulated_metrics
+                                .pods_evicted_by_descheduler
+                                .entry(strategy.clone())
+                                .or_default() += 1;
+                        }
+                    }
+                }
+
+                // Redirect to persistent storage
+                self.ctx.emit(
+                    PodEvictedFromNode {
+                        evicted,
+                        eviction_time,
+                        pod_name,
+                        node_name,
+                        reason,
+                    },
+                    self.persistent_storage,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
             CreatePodGroupRequest { pod_group } => {
                 assert!(
                     pod_group.pod_template.spec.running_duration.is_none(),
@@ -430,21 +925,12 @@ impl EventHandler for KubeApiServer {
                     pod.spec.resources.usage_model_config =
                         Some(info.pod_group.resources_usage_model_config.clone());
 
-                    self.ctx.emit(
-                        CreatePodRequest { pod },
-                        self.persistent_storage,
-                        self.config.as_to_ps_network_delay,
-                    );
+                    self.handle_create_pod(pod);
 
                     info.created_pods.insert(pod_name);
                     info.total_created += 1;
                 }
 
-                self.metrics_collector
-                    .borrow_mut()
-                    .gauge_metrics
-                    .current_pods += info.pod_group.initial_pod_count as u64;
-
                 if self.horizontal_pod_autoscaler.is_some() {
                     self.ctx.emit(
                         RegisterPodGroup { info },
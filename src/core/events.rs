@@ -11,8 +11,10 @@ use crate::autoscalers::cluster_autoscaler::interface::{
     AutoscaleInfoRequestType, ScaleDownInfo, ScaleUpInfo,
 };
 use crate::autoscalers::horizontal_pod_autoscaler::interface::{PodGroup, PodGroupInfo};
-use crate::core::node::Node;
-use crate::core::pod::{Pod, PodConditionType};
+use crate::core::node::{Node, NodeConditionType, NodeFailureType, Taint, TaintEffect};
+use crate::core::pod::{
+    EvictionReason, Pod, PodConditionType, PodFailureReason, QoSClass, RestartPolicy, Toleration,
+};
 
 use crate::core::common::{RuntimeResources, RuntimeResourcesUsageModelConfig};
 
@@ -40,7 +42,7 @@ pub struct NodeAddedToCluster {
 /// Event from client or cluster autoscaler to api server to inform that node should be removed from
 /// a cluster due to general reasons such as scaling down or maintenance. Api server redirects this
 /// event to persistent storage
-/// Does not reflect node failures.
+/// Does not reflect node failures, see `FailNodeRequest` for them.
 #[derive(Serialize, Clone, IsSimulationEvent)]
 pub struct RemoveNodeRequest {
     pub node_name: String,
@@ -68,6 +70,170 @@ pub struct RemoveNodeFromCache {
     pub node_name: String,
 }
 
+/// Event from client to api server with request to fail a node. Api server redirects this request
+/// to the node component, which fails its running pods on hard failure.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct FailNodeRequest {
+    pub node_name: String,
+    pub failure_type: NodeFailureType,
+}
+
+/// Event from node component to api server and from api server to persistent storage to inform
+/// that node has failed. Persistent storage marks the node as not ready and taints it.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct NodeFailed {
+    pub node_name: String,
+    pub failure_type: NodeFailureType,
+    pub failure_time: f64,
+}
+
+/// Event from client to api server with request to recover a failed node. Passes the same way as
+/// `FailNodeRequest`.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RecoverNodeRequest {
+    pub node_name: String,
+}
+
+/// Event from node component to api server and from api server to persistent storage to inform
+/// that failed node has recovered and accepts pods again.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct NodeRecovered {
+    pub node_name: String,
+    pub recovery_time: f64,
+}
+
+/// Event from node component to api server and from api server to node lifecycle controller to
+/// inform that node is alive. Node components do not send heartbeats while they are failed.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct NodeHeartbeat {
+    pub node_name: String,
+    pub heartbeat_time: f64,
+}
+
+/// Event from node lifecycle controller or node component to api server and from api server to
+/// persistent storage with request to set the status of node condition.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UpdateNodeConditionRequest {
+    pub node_name: String,
+    pub condition_type: NodeConditionType,
+    // True, False or Unknown
+    pub status: String,
+    pub transition_time: f64,
+}
+
+/// Event from api server to failure injector to inform that node is added to the cluster and may
+/// fail from now on.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RegisterNodeForFailures {
+    pub node: Node,
+}
+
+/// Event from api server to failure injector to inform that node is removed from the cluster and
+/// cannot fail anymore.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UnregisterNodeForFailures {
+    pub node_name: String,
+}
+
+/// Event which failure injector sends to itself when the time of the node to fail comes.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct InjectNodeFailure {
+    pub node_name: String,
+}
+
+/// Event which failure injector sends to itself when the failed node is repaired.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct InjectNodeRecovery {
+    pub node_name: String,
+}
+
+/// Event from client to api server with request to add a taint to a node. Api server redirects this
+/// request to persistent storage and on response to the node component, which evicts running pods
+/// not tolerating NoExecute taint.
+/// Taint replaces existing one with the same key and effect.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct TaintNodeRequest {
+    pub node_name: String,
+    pub taint: Taint,
+}
+
+/// Event from persistent storage to api server telling that the new taint of a node has been
+/// persisted.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct TaintNodeResponse {
+    pub node_name: String,
+    pub taint: Taint,
+}
+
+/// Event from client to api server with request to remove taint with given key and effect from
+/// a node. Passes the same way as `TaintNodeRequest`.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UntaintNodeRequest {
+    pub node_name: String,
+    pub key: String,
+    pub effect: TaintEffect,
+}
+
+/// Event from persistent storage to api server telling that taint removal has been persisted.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UntaintNodeResponse {
+    pub node_name: String,
+    pub key: String,
+    pub effect: TaintEffect,
+}
+
+/// Event from client to api server with request to cordon a node, so that the scheduler does not
+/// place new pods to it. Api server redirects this request to persistent storage and on response to
+/// the node component.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct CordonNodeRequest {
+    pub node_name: String,
+}
+
+/// Event from persistent storage to api server telling that the node is persisted as unschedulable.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct CordonNodeResponse {
+    pub node_name: String,
+}
+
+/// Event from client to api server with request to make cordoned node schedulable again. Passes the
+/// same way as `CordonNodeRequest`.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UncordonNodeRequest {
+    pub node_name: String,
+}
+
+/// Event from persistent storage to api server telling that the node is persisted as schedulable.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UncordonNodeResponse {
+    pub node_name: String,
+}
+
+/// Event from client to api server with request to drain a node. Api server cordons the node first
+/// and then redirects this request to the node component, which evicts every running pod after
+/// `grace_period` unless it finishes earlier. The node is removed from the cluster when it has no
+/// pods left if `remove_node` is set.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct DrainNodeRequest {
+    pub node_name: String,
+    pub grace_period: f64,
+    pub remove_node: bool,
+}
+
+/// Event from node component to api server to inform that the node being drained has no pods left.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct NodeDrained {
+    pub node_name: String,
+    pub drain_time: f64,
+}
+
+/// Event from persistent storage to scheduler to inform that node spec or metadata has changed
+/// (e.g. taints) and scheduler should update its cache.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct UpdateNodeInCache {
+    pub node: Node,
+}
+
 /// Event from client or pod autoscaler to api server with request to create a pod. Api server
 /// redirects this request to persistent storage and persistent storage sends schedule request
 /// to scheduler.
@@ -111,6 +277,46 @@ pub struct RemovePodFromCache {
     pub pod_name: String,
 }
 
+/// Event from persistent storage to scheduler to inform that a pod scheduled by another scheduler
+/// is assigned to a node, so the node resources taken by it should be reserved in scheduler cache.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct AddPodToCache {
+    pub pod: Pod,
+}
+
+/// Event from scheduler to api server with request to evict a running pod from its node, e.g.
+/// to free place for a pod with higher priority. Api server redirects this request to persistent
+/// storage to find out the node the pod is assigned to and then to the node component.
+/// Unlike pod removal, evicted pod is not terminated but goes back to the scheduling queues.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct EvictPodRequest {
+    pub pod_name: String,
+    pub reason: EvictionReason,
+}
+
+/// Event from persistent storage to api server with the node which evicted pod is assigned to.
+/// `assigned_node` is None if the pod has already finished, removed or has not been assigned yet,
+/// so there is nothing to evict.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct EvictPodResponse {
+    pub assigned_node: Option<String>,
+    pub pod_name: String,
+    pub reason: EvictionReason,
+}
+
+/// Event from node component->api server->persistent storage->scheduler to inform that pod is
+/// evicted from a node. Persistent storage and scheduler release pod's resources on the node and
+/// the scheduler puts the pod back to the active queue.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct PodEvictedFromNode {
+    /// Flag that is false if pod was not evicted as it is not running on the node anymore.
+    pub evicted: bool,
+    pub eviction_time: f64,
+    pub pod_name: String,
+    pub node_name: String,
+    pub reason: EvictionReason,
+}
+
 // Event from persistent storage to scheduler to inform that new pod is created and ready for scheduling.
 #[derive(Serialize, Clone, IsSimulationEvent)]
 pub struct PodScheduleRequest {
@@ -139,13 +345,29 @@ pub struct AssignPodToNodeRequest {
 pub struct AssignPodToNodeResponse {
     pub pod_name: String,
     pub pod_requests: RuntimeResources,
+    pub pod_limits: RuntimeResources,
+    pub pod_qos_class: QoSClass,
+    pub pod_tolerations: Vec<Toleration>,
     pub pod_group: Option<String>,
     pub pod_group_creation_time: Option<String>,
     pub node_name: String,
     pub pod_duration: Option<f64>,
+    pub pod_restart_policy: RestartPolicy,
+    pub pod_failure_probability: Option<f64>,
+    pub pod_failure_time: Option<f64>,
     pub resources_usage_model_config: RuntimeResourcesUsageModelConfig,
 }
 
+/// Event from persistent storage to scheduler to inform that the assignment of the pod is rejected,
+/// because the node has not enough resources left. It happens when several schedulers bind pods to
+/// the same node resources based on their own caches. The scheduler should roll back the
+/// assignment and retry the pod.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct PodAssignmentConflict {
+    pub pod_name: String,
+    pub node_name: String,
+}
+
 /// Event from scheduler -> api server -> persistent storage to inform that pod cannot be scheduled
 /// temporary.
 #[derive(Serialize, Clone, IsSimulationEvent)]
@@ -159,10 +381,16 @@ pub struct PodNotScheduled {
 pub struct BindPodToNodeRequest {
     pub pod_name: String,
     pub pod_requests: RuntimeResources,
+    pub pod_limits: RuntimeResources,
+    pub pod_qos_class: QoSClass,
+    pub pod_tolerations: Vec<Toleration>,
     pub pod_group: Option<String>,
     pub pod_group_creation_time: Option<String>,
     pub node_name: String,
     pub pod_duration: Option<f64>,
+    pub pod_restart_policy: RestartPolicy,
+    pub pod_failure_probability: Option<f64>,
+    pub pod_failure_time: Option<f64>,
     pub resources_usage_model_config: RuntimeResourcesUsageModelConfig,
 }
 
@@ -189,6 +417,24 @@ pub struct PodFinishedRunning {
     pub node_name: String,
     pub finish_time: f64,
     pub finish_result: PodConditionType, // either PodSucceeded or PodFailed
+    pub failure_reason: Option<PodFailureReason>, // set if pod failed
+    pub qos_class: QoSClass,
+}
+
+/// Event from node component to itself when CrashLoopBackOff delay of a pod which finished its run
+/// is over and the pod should run again on the same node.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RestartPod {
+    pub pod_name: String,
+}
+
+/// Event from node component->api server->persistent storage to inform that pod finished its run
+/// and started running again on the same node according to its restart policy.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct PodRestarted {
+    pub pod_name: String,
+    pub node_name: String,
+    pub restart_time: f64,
 }
 
 /// Event from node client->api server to inform that new group of long running
@@ -217,6 +463,27 @@ pub struct RunClusterAutoscalerCycle {}
 #[derive(Serialize, Clone, IsSimulationEvent)]
 pub struct RunHorizontalPodAutoscalerCycle {}
 
+/// Event from descheduler to itself to simulate working interval.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RunDeschedulerCycle {}
+
+/// Event from node lifecycle controller to itself to check node heartbeats in working interval.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RunNodeLifecycleControllerCycle {}
+
+/// Event from node component to itself to send heartbeat in working interval.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RunNodeHeartbeatCycle {}
+
+/// Event from node component to itself to check resource usage of its pods in working interval.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RunNodePressureCheckCycle {}
+
+/// Event from node component to itself to check usage of its pods against their limits in working
+/// interval.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct RunPodLimitsCheckCycle {}
+
 /// Event from metrics collector to itself to collect pod metrics in working interval.
 #[derive(Serialize, Clone, IsSimulationEvent)]
 pub struct RunPodMetricsCollectionCycle {}
@@ -239,10 +506,28 @@ pub struct ClusterAutoscalerResponse {
     pub scale_down: Option<ScaleDownInfo>,
 }
 
+/// Event from descheduler->api server->persistent storage to find out the current placement of
+/// pods.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct DeschedulerRequest {}
+
+/// Event from persistent storage->api server->descheduler with all nodes and pods assigned to them.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct DeschedulerResponse {
+    pub nodes: Vec<Node>,
+    pub assigned_pods: Vec<Pod>,
+}
+
 /// Event from scheduler to itself to flush unschedulable queue leftover.
 #[derive(Serialize, Clone, IsSimulationEvent)]
 pub struct FlushUnschedulableQueueLeftover {}
 
+/// Event from scheduler to itself to reject a pod which is not permitted to bind in time.
+#[derive(Serialize, Clone, IsSimulationEvent)]
+pub struct PodPermitWaitTimeout {
+    pub pod_name: String,
+}
+
 // Expect event.data to be Box<Box<dyn SimulationEvent>> to downcast it first and then extract a real type.
 #[macro_export]
 macro_rules! cast_box {
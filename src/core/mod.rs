@@ -1,3 +1,4 @@
+pub mod affinity;
 pub mod api_server;
 pub mod common;
 pub mod events;
@@ -3,10 +3,70 @@
 use serde::{Deserialize, Serialize};
 
 use crate::core::common::{ObjectMeta, RuntimeResources};
+use crate::core::pod::Toleration;
 
 #[derive(Default, Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct NodeSpec {
-    // placeholder for future fields
+    /// Taints repel pods which do not tolerate them from a node.
+    #[serde(default)]
+    pub taints: Vec<Taint>,
+    /// Cordoned node does not accept new pods, but pods running on it keep running.
+    #[serde(default)]
+    pub unschedulable: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum TaintEffect {
+    // Pods that do not tolerate the taint are not scheduled to the node.
+    NoSchedule,
+    // Scheduler tries to avoid placing pods that do not tolerate the taint to the node.
+    PreferNoSchedule,
+    // Pods that do not tolerate the taint are not scheduled to the node and running ones are
+    // evicted from it.
+    NoExecute,
+}
+
+// https://kubernetes.io/docs/concepts/scheduling-eviction/taint-and-toleration/
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Taint {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    pub effect: TaintEffect,
+}
+
+impl Taint {
+    /// Whether at least one of the tolerations tolerates the taint.
+    pub fn is_tolerated_by(&self, tolerations: &[Toleration]) -> bool {
+        tolerations
+            .iter()
+            .any(|toleration| toleration.tolerates(self))
+    }
+}
+
+/// Key of NoSchedule taint which is put on a failed node until it recovers, so that pods are not
+/// placed to it unless they tolerate the failure.
+pub const NODE_NOT_READY_TAINT_KEY: &str = "node.kubernetes.io/not-ready";
+
+/// Key of NoSchedule and NoExecute taints which node lifecycle controller puts on a node that has
+/// stopped sending heartbeats.
+pub const NODE_UNREACHABLE_TAINT_KEY: &str = "node.kubernetes.io/unreachable";
+
+/// Key of NoSchedule taint which pods tolerate to be scheduled to cordoned nodes.
+pub const NODE_UNSCHEDULABLE_TAINT_KEY: &str = "node.kubernetes.io/unschedulable";
+
+/// Keys of NoSchedule taints which are put on a node while it is under memory or cpu pressure, so
+/// that the scheduler does not place more pods to it.
+pub const NODE_MEMORY_PRESSURE_TAINT_KEY: &str = "node.kubernetes.io/memory-pressure";
+pub const NODE_CPU_PRESSURE_TAINT_KEY: &str = "node.kubernetes.io/cpu-pressure";
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum NodeFailureType {
+    // Node is temporarily unavailable due to software failures, pods running on it keep running
+    // but new ones are not placed to it.
+    Soft,
+    // Node is unavailable due to hardware failures, pods running on it fail.
+    Hard,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -19,6 +79,19 @@ pub enum NodeConditionType {
     DiskPressure,
     MemoryPressure,
     PIDPressure,
+    // Not in k8s: pods use more cpu than the node has, so they are slowed down.
+    CPUPressure,
+}
+
+impl NodeConditionType {
+    /// Key of NoSchedule taint which is put on a node while the condition is true.
+    pub fn pressure_taint_key(&self) -> Option<&'static str> {
+        match self {
+            NodeConditionType::MemoryPressure => Some(NODE_MEMORY_PRESSURE_TAINT_KEY),
+            NodeConditionType::CPUPressure => Some(NODE_CPU_PRESSURE_TAINT_KEY),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -67,6 +140,26 @@ impl Node {
         }
     }
 
+    /// Adds taint to the node replacing the existing one with the same key and effect.
+    pub fn add_taint(&mut self, taint: Taint) {
+        self.remove_taint(&taint.key, &taint.effect);
+        self.spec.taints.push(taint);
+    }
+
+    pub fn remove_taint(&mut self, key: &str, effect: &TaintEffect) {
+        self.spec
+            .taints
+            .retain(|taint| taint.key != key || &taint.effect != effect);
+    }
+
+    // Ref to condition if it exists else None.
+    pub fn get_condition(&self, condition_type: NodeConditionType) -> Option<&NodeCondition> {
+        self.status
+            .conditions
+            .iter()
+            .find(|c| c.condition_type == condition_type)
+    }
+
     pub fn update_condition(
         &mut self,
         status: String,
@@ -1,18 +1,26 @@
 //! Node component simulates a real node running pods.
 
-use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 
 use dslab_core::event::EventId;
 use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use serde::Deserialize;
 
 use crate::core::common::{RuntimeResourcesUsageModelConfig, SimComponentId};
 use crate::core::events::{
-    BindPodToNodeRequest, NodeRemovedFromCluster, PodFinishedRunning, PodRemovedFromNode,
-    PodStartedRunning, RemoveNodeRequest, RemovePodRequest,
+    BindPodToNodeRequest, CordonNodeRequest, DrainNodeRequest, EvictPodRequest, FailNodeRequest,
+    NodeDrained, NodeFailed, NodeHeartbeat, NodeRecovered, NodeRemovedFromCluster,
+    PodEvictedFromNode, PodFinishedRunning, PodRemovedFromNode, PodRestarted, PodStartedRunning,
+    RecoverNodeRequest, RemoveNodeRequest, RemovePodRequest, RestartPod, RunNodeHeartbeatCycle,
+    RunNodePressureCheckCycle, RunPodLimitsCheckCycle, TaintNodeRequest, UncordonNodeRequest,
+    UntaintNodeRequest, UpdateNodeConditionRequest,
+};
+use crate::core::node::{Node, NodeConditionType, NodeFailureType, TaintEffect};
+use crate::core::pod::{
+    EvictionReason, PodConditionType, PodFailureReason, QoSClass, RestartPolicy, Toleration,
 };
-use crate::core::node::Node;
-use crate::core::pod::PodConditionType;
 
 use crate::core::resource_usage::helpers::resource_usage_model_from_config;
 use crate::core::resource_usage::interface::ResourceUsageModel;
@@ -21,13 +29,210 @@ use crate::config::SimulationConfig;
 
 use crate::core::common::RuntimeResources;
 
+// CrashLoopBackOff delays of kubelet: the delay before restart doubles with every restart up to the
+// maximum and is reset after the pod runs long enough without finishing.
+pub const CRASH_LOOP_BACKOFF_INITIAL_DELAY: f64 = 10.0;
+pub const CRASH_LOOP_BACKOFF_MAX_DELAY: f64 = 300.0;
+pub const CRASH_LOOP_BACKOFF_RESET_TIME: f64 = 600.0;
+
+/// Config of node-pressure eviction of kubelet. Nodes compute actual usage of their pods with the
+/// usage models and report pressure conditions when the usage exceeds the thresholds.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct NodePressureConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// Interval of usage checks, like `housekeepingInterval` of kubelet.
+    #[serde(default = "housekeeping_interval_default")]
+    pub housekeeping_interval: f64,
+    /// Fraction of node ram capacity used by pods above which the node is under memory pressure.
+    /// Such node evicts one pod per check until the usage falls below the threshold.
+    #[serde(default = "memory_pressure_threshold_default")]
+    pub memory_pressure_threshold: f64,
+    /// Fraction of node cpu capacity used by pods above which the node is under cpu pressure. Cpu
+    /// is compressible, so pods are not evicted, but new ones are not placed to the node.
+    #[serde(default = "cpu_pressure_threshold_default")]
+    pub cpu_pressure_threshold: f64,
+}
+
+impl Default for NodePressureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+            housekeeping_interval: housekeeping_interval_default(),
+            memory_pressure_threshold: memory_pressure_threshold_default(),
+            cpu_pressure_threshold: cpu_pressure_threshold_default(),
+        }
+    }
+}
+
+/// Config of pod resource limits enforcement. Pods using more memory than their limits are killed,
+/// pods using more cpu than their limits are throttled and run slower. Zero limit means no limit.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct ResourceLimitsConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// Interval of checks of pod usage against limits. Usage is also checked when a run of a pod
+    /// starts.
+    #[serde(default = "usage_check_interval_default")]
+    pub usage_check_interval: f64,
+}
+
+impl Default for ResourceLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+            usage_check_interval: usage_check_interval_default(),
+        }
+    }
+}
+
+fn enabled_default() -> bool {
+    false // disabled by default
+}
+fn housekeeping_interval_default() -> f64 {
+    10.0 // 10 seconds
+}
+fn memory_pressure_threshold_default() -> f64 {
+    0.95
+}
+fn cpu_pressure_threshold_default() -> f64 {
+    0.95
+}
+fn usage_check_interval_default() -> f64 {
+    10.0 // 10 seconds
+}
+
+/// Config of cpu contention model. Pods on a node share its cpu fairly when they need more cpu than
+/// the node has, so their runs go slower.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct CpuContentionConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+impl Default for CpuContentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+        }
+    }
+}
+
+/// Current run of a pod on a node. Run goes slower than real time when the pod does not get all
+/// the cpu it needs.
+pub struct PodRun {
+    /// Time the run needs at full speed to end counted from `update_time` and the result of the
+    /// run. None if the run never ends.
+    pub remaining: Option<(f64, PodConditionType)>,
+    /// Time of the last change of the run speed.
+    pub update_time: f64,
+    /// Fraction of needed cpu the pod gets, defines how much slower than real time the run goes.
+    pub speed: f64,
+}
+
+impl PodRun {
+    /// Time the run needs at full speed to end counted from the time. None if the run never ends.
+    pub fn remaining_time(&self, time: f64) -> Option<f64> {
+        self.remaining
+            .as_ref()
+            .map(|(remaining_time, _)| remaining_time - (time - self.update_time) * self.speed)
+    }
+
+    /// Whether the run has ended by the time, but the node has not handled its end yet.
+    pub fn has_ended(&self, time: f64) -> bool {
+        self.remaining_time(time)
+            .map_or(false, |remaining_time| remaining_time <= 0.0)
+    }
+}
+
+/// Shares capacity between demands with max-min fairness: demands are satisfied starting from the
+/// smallest one while they are below the equal share of capacity left.
+fn fair_share(demands: &BTreeMap<String, f64>, capacity: f64) -> BTreeMap<String, f64> {
+    let mut sorted_demands: Vec<(&String, f64)> = demands
+        .iter()
+        .map(|(name, demand)| (name, *demand))
+        .collect();
+    sorted_demands.sort_by(|lhs, rhs| lhs.1.total_cmp(&rhs.1));
+
+    let mut capacity_left = capacity;
+    let demand_count = sorted_demands.len();
+    let mut shares: BTreeMap<String, f64> = Default::default();
+    for (idx, (name, demand)) in sorted_demands.into_iter().enumerate() {
+        let share = demand.min(capacity_left / (demand_count - idx) as f64);
+        capacity_left -= share;
+        shares.insert(name.clone(), share);
+    }
+    shares
+}
+
 pub struct RunningPodInfo {
+    /// Id of the event sent to self which ends the current run of a pod (`PodFinishedRunning`) or
+    /// restarts it after the backoff (`RestartPod`).
     pub event_id: Option<EventId>,
     /// Name of pod group a pod belongs to. None if a pod is not in group.
     pub pod_group: Option<String>,
     pub pod_requests: RuntimeResources,
+    pub pod_limits: RuntimeResources,
+    pub qos_class: QoSClass,
+    pub pod_tolerations: Vec<Toleration>,
+    /// Id of delayed `EvictPodRequest` which is sent to self when pod tolerates NoExecute taints
+    /// of the node for limited time, the time pod started tolerating the taints and the time of
+    /// the eviction.
+    pub taint_eviction: Option<(EventId, f64, f64)>,
+    /// Id of delayed `EvictPodRequest` which is sent to self when the node is being drained.
+    pub drain_eviction: Option<EventId>,
     pub cpu_usage_model: Option<Box<dyn ResourceUsageModel>>,
     pub ram_usage_model: Option<Box<dyn ResourceUsageModel>>,
+    pub pod_duration: Option<f64>,
+    pub restart_policy: RestartPolicy,
+    pub failure_probability: Option<f64>,
+    pub failure_time: Option<f64>,
+    /// Start time of the current run of a pod.
+    pub run_start_time: f64,
+    /// The number of restarts since the backoff was reset, defines the next backoff delay.
+    pub backoff_restarts: u32,
+    /// None if a pod is waiting for restart or is being killed.
+    pub current_run: Option<PodRun>,
+}
+
+impl RunningPodInfo {
+    /// Actual cpu (in millicores) and ram (in bytes) usage of a pod according to its usage models.
+    /// Pods without usage model of a resource use as much as they request. `pod_group_size` is the
+    /// number of running pods in the pod group of a pod.
+    pub fn current_usage(&mut self, time: f64, pod_group_size: Option<usize>) -> (f64, f64) {
+        let cpu_usage = resource_usage(
+            &mut self.cpu_usage_model,
+            self.pod_requests.cpu as f64,
+            time,
+            pod_group_size,
+        );
+        let ram_usage = resource_usage(
+            &mut self.ram_usage_model,
+            self.pod_requests.ram as f64,
+            time,
+            pod_group_size,
+        );
+        (cpu_usage, ram_usage)
+    }
+}
+
+fn resource_usage(
+    model: &mut Option<Box<dyn ResourceUsageModel>>,
+    request: f64,
+    time: f64,
+    pod_group_size: Option<usize>,
+) -> f64 {
+    match model {
+        Some(model) => {
+            let usage = model.current_usage(time, pod_group_size);
+            if model.is_utilization() {
+                usage * request
+            } else {
+                usage
+            }
+        }
+        None => request,
+    }
 }
 
 pub struct NodeComponent {
@@ -40,16 +245,36 @@ pub struct NodeComponent {
     pub running_pods: HashMap<String, RunningPodInfo>,
     /// Set of canceled running nodes which did not finish due to node removal.
     pub canceled_pods: HashSet<String>,
+    /// Map from evicted pod name to its eviction time. Remove request may come to a node after
+    /// eviction, so such pods are considered as removed at the time of eviction.
+    pub evicted_pods: HashMap<String, f64>,
 
     /// Flag to check that node is being removed so cannot accept any pod occasionally.
     pub removed: bool,
     pub removal_time: f64,
+
+    /// Failure of the node if it has failed and not recovered yet. Pods bound to a node with hard
+    /// failure fail right away.
+    pub failure: Option<NodeFailureType>,
+
+    /// Id of the next `RunNodeHeartbeatCycle` sent to self, if heartbeats are sent.
+    pub heartbeat_event: Option<EventId>,
+    /// Id of the next `RunNodePressureCheckCycle` sent to self, if node pressure is checked.
+    pub pressure_check_event: Option<EventId>,
+    /// Id of the next `RunPodLimitsCheckCycle` sent to self, if pod limits are enforced.
+    pub limits_check_event: Option<EventId>,
+
+    /// Termination grace period of pods if the node is being drained.
+    pub drain_grace_period: Option<f64>,
 }
 
 pub struct NodeRuntime {
     pub api_server: SimComponentId,
     pub node: Node,
     pub config: Rc<SimulationConfig>,
+    /// Number of running pods in each pod group on all nodes, which is needed for pod group usage
+    /// models.
+    pub pod_group_sizes: Rc<RefCell<HashMap<String, usize>>>,
 }
 
 impl NodeComponent {
@@ -59,8 +284,14 @@ impl NodeComponent {
             runtime: None,
             running_pods: Default::default(),
             canceled_pods: Default::default(),
+            evicted_pods: Default::default(),
             removed: false,
             removal_time: 0.0,
+            failure: None,
+            heartbeat_event: None,
+            pressure_check_event: None,
+            limits_check_event: None,
+            drain_grace_period: None,
         }
     }
 
@@ -90,6 +321,48 @@ impl NodeComponent {
         self.runtime.as_mut().unwrap().node.status.allocatable.ram += requests.ram;
     }
 
+    fn add_running_pod(&mut self, pod_name: String, info: RunningPodInfo) {
+        if let Some(pod_group) = info.pod_group.as_ref() {
+            *self
+                .runtime
+                .as_ref()
+                .unwrap()
+                .pod_group_sizes
+                .borrow_mut()
+                .entry(pod_group.clone())
+                .or_default() += 1;
+        }
+        self.running_pods.insert(pod_name, info);
+    }
+
+    fn remove_running_pod(&mut self, pod_name: &str) -> Option<RunningPodInfo> {
+        let info = self.running_pods.remove(pod_name)?;
+        if let Some(pod_group) = info.pod_group.as_ref() {
+            *self
+                .runtime
+                .as_ref()
+                .unwrap()
+                .pod_group_sizes
+                .borrow_mut()
+                .get_mut(pod_group)
+                .unwrap() -= 1;
+        }
+        Some(info)
+    }
+
+    /// Cancels pending events of a running pod sent to self: its finish and delayed eviction.
+    fn cancel_pod_events(&self, info: &RunningPodInfo) {
+        if let Some(event_id) = info.event_id {
+            self.ctx.cancel_event(event_id);
+        }
+        if let Some((event_id, _, _)) = info.taint_eviction {
+            self.ctx.cancel_event(event_id);
+        }
+        if let Some(event_id) = info.drain_eviction {
+            self.ctx.cancel_event(event_id);
+        }
+    }
+
     /// This method cancels events `PodFinishedRunning` of a current node which were submitted to
     /// the simulation queue and which delay is >= current cancellation time.
     fn cancel_all_running_pods(&mut self) {
@@ -99,16 +372,17 @@ impl NodeComponent {
         for (pod_name, info) in self.running_pods.iter() {
             freed_resources.push(info.pod_requests.clone());
             self.canceled_pods.insert(pod_name.to_string());
-            if let Some(event_id) = info.event_id {
-                self.ctx.cancel_event(event_id);
-            }
+            self.cancel_pod_events(info);
         }
 
         for requests in freed_resources.into_iter() {
             self.free_pod_requests(&requests);
         }
 
-        self.running_pods.clear();
+        let pod_names: Vec<String> = self.running_pods.keys().cloned().collect();
+        for pod_name in pod_names.iter() {
+            self.remove_running_pod(pod_name);
+        }
     }
 
     pub fn simulate_pod_runtime(
@@ -116,35 +390,17 @@ impl NodeComponent {
         event_time: f64,
         pod_name: String,
         pod_requests: RuntimeResources,
+        pod_limits: RuntimeResources,
+        qos_class: QoSClass,
+        pod_tolerations: Vec<Toleration>,
         pod_group: Option<String>,
         pod_group_creation_time: Option<String>,
         pod_duration: Option<f64>,
+        restart_policy: RestartPolicy,
+        failure_probability: Option<f64>,
+        failure_time: Option<f64>,
         usage_config: RuntimeResourcesUsageModelConfig,
     ) {
-        let mut event_id: Option<EventId> = None;
-
-        if pod_duration.is_some() {
-            let duration = pod_duration.unwrap();
-            // not long running service, schedule its finish in pod duration
-            let delay = duration
-                + self
-                    .runtime
-                    .as_ref()
-                    .unwrap()
-                    .config
-                    .as_to_node_network_delay;
-
-            event_id = Some(self.ctx.emit_self(
-                PodFinishedRunning {
-                    pod_name: pod_name.clone(),
-                    node_name: self.runtime.as_ref().unwrap().node.metadata.name.clone(),
-                    finish_time: event_time + duration,
-                    finish_result: PodConditionType::PodSucceeded,
-                },
-                delay,
-            ));
-        }
-
         let mut cpu_usage_model = None;
         let mut ram_usage_model = None;
 
@@ -164,14 +420,625 @@ impl NodeComponent {
         self.allocate_pod_requests(&pod_requests);
 
         let running_pod_info = RunningPodInfo {
-            event_id,
+            event_id: None,
             pod_group,
             pod_requests,
+            pod_limits,
+            qos_class,
+            pod_tolerations,
+            taint_eviction: None,
+            drain_eviction: None,
             cpu_usage_model,
             ram_usage_model,
+            pod_duration,
+            restart_policy,
+            failure_probability,
+            failure_time,
+            run_start_time: event_time,
+            backoff_restarts: 0,
+            current_run: None,
         };
 
-        self.running_pods.insert(pod_name, running_pod_info);
+        self.add_running_pod(pod_name.clone(), running_pod_info);
+        self.start_pod_run(&pod_name, event_time);
+    }
+
+    /// Samples the end of the pod run: its duration since the start and the result. Returns None
+    /// if the run never ends.
+    /// With failure probability set, a run fails with this probability, otherwise it fails only if
+    /// failure time is set. Failing run stops at failure time or at uniformly random time within
+    /// the pod duration if failure time is not set.
+    fn sample_pod_run(&self, info: &RunningPodInfo) -> Option<(f64, PodConditionType)> {
+        let fails = match info.failure_probability {
+            Some(probability) => self.ctx.rand() < probability,
+            None => info.failure_time.is_some(),
+        };
+        if fails {
+            let failure_time = match (info.failure_time, info.pod_duration) {
+                (Some(failure_time), _) => Some(failure_time),
+                (None, Some(duration)) => Some(duration * self.ctx.rand()),
+                // Long running pod without failure time has no time to fail.
+                (None, None) => None,
+            };
+            if let Some(failure_time) = failure_time {
+                if info
+                    .pod_duration
+                    .map_or(true, |duration| failure_time < duration)
+                {
+                    return Some((failure_time, PodConditionType::PodFailed));
+                }
+            }
+        }
+        info.pod_duration
+            .map(|duration| (duration, PodConditionType::PodSucceeded))
+    }
+
+    /// Starts a new run of the pod and schedules its finish if the run is not infinite.
+    fn start_pod_run(&mut self, pod_name: &str, event_time: f64) {
+        let remaining = self.sample_pod_run(self.running_pods.get(pod_name).unwrap());
+
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        info.event_id = None;
+        info.run_start_time = event_time;
+        info.current_run = Some(PodRun {
+            remaining,
+            update_time: event_time,
+            speed: 1.0,
+        });
+        self.schedule_pod_run_end(pod_name);
+        self.update_pod_run_speeds(event_time);
+    }
+
+    /// Schedules the end of the current run of the pod instead of the previously scheduled one.
+    /// The run speed is expected to be updated at the current time.
+    fn schedule_pod_run_end(&mut self, pod_name: &str) {
+        let runtime = self.runtime.as_ref().unwrap();
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        if let Some(event_id) = info.event_id.take() {
+            self.ctx.cancel_event(event_id);
+        }
+
+        let run = info.current_run.as_ref().unwrap();
+        if let Some((remaining_time, finish_result)) = run.remaining.as_ref() {
+            let duration = remaining_time / run.speed;
+            let failure_reason = match finish_result {
+                PodConditionType::PodFailed => Some(PodFailureReason::Error),
+                _ => None,
+            };
+            info.event_id = Some(self.ctx.emit_self(
+                PodFinishedRunning {
+                    pod_name: pod_name.to_string(),
+                    node_name: runtime.node.metadata.name.clone(),
+                    finish_time: run.update_time + duration,
+                    finish_result: finish_result.clone(),
+                    failure_reason,
+                    qos_class: info.qos_class.clone(),
+                },
+                duration + runtime.config.as_to_node_network_delay,
+            ));
+        }
+    }
+
+    /// Changes speed of the current run of the pod from the time and reschedules its end.
+    fn set_pod_run_speed(&mut self, pod_name: &str, speed: f64, time: f64) {
+        let run = match self
+            .running_pods
+            .get_mut(pod_name)
+            .unwrap()
+            .current_run
+            .as_mut()
+        {
+            Some(run) if run.speed != speed => run,
+            _ => return,
+        };
+        let remaining_time = run.remaining_time(time);
+        if let Some((remaining, _)) = run.remaining.as_mut() {
+            *remaining = remaining_time.unwrap().max(0.0);
+        }
+        run.update_time = time;
+        run.speed = speed;
+        self.schedule_pod_run_end(pod_name);
+    }
+
+    /// Kills the current run of the pod, so that it fails now with the reason.
+    fn kill_pod_run(&mut self, pod_name: &str, failure_reason: PodFailureReason, time: f64) {
+        let runtime = self.runtime.as_ref().unwrap();
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        if let Some(event_id) = info.event_id.take() {
+            self.ctx.cancel_event(event_id);
+        }
+        info.current_run = None;
+        info.event_id = Some(self.ctx.emit_self(
+            PodFinishedRunning {
+                pod_name: pod_name.to_string(),
+                node_name: runtime.node.metadata.name.clone(),
+                finish_time: time,
+                finish_result: PodConditionType::PodFailed,
+                failure_reason: Some(failure_reason),
+                qos_class: info.qos_class.clone(),
+            },
+            runtime.config.as_to_node_network_delay,
+        ));
+    }
+
+    /// Recomputes speeds of the running pods from their actual cpu usage and reschedules the ends
+    /// of their runs. If limits are enforced, pods using more memory than their limits are killed
+    /// and pods using more cpu than their limits are throttled. If cpu contention is modelled, pods
+    /// share node cpu fairly when they need more than the node has. Zero limit means no limit.
+    fn update_pod_run_speeds(&mut self, time: f64) {
+        let config = self.runtime.as_ref().unwrap().config.clone();
+        if !config.resource_limits.enabled && !config.cpu_contention.enabled {
+            return;
+        }
+
+        let mut usage = self.current_pods_usage(time);
+        usage.retain(|pod_name, _| {
+            self.running_pods[pod_name]
+                .current_run
+                .as_ref()
+                .map_or(false, |run| !run.has_ended(time))
+        });
+
+        if config.resource_limits.enabled {
+            let oom_killed_pods: Vec<String> = usage
+                .iter()
+                .filter(|(pod_name, (_, ram_usage))| {
+                    let ram_limit = self.running_pods[pod_name.as_str()].pod_limits.ram;
+                    ram_limit > 0 && *ram_usage > ram_limit as f64
+                })
+                .map(|(pod_name, _)| pod_name.clone())
+                .collect();
+            for pod_name in oom_killed_pods.iter() {
+                usage.remove(pod_name);
+                self.kill_pod_run(pod_name, PodFailureReason::OOMKilled, time);
+            }
+        }
+
+        // Cpu pods may use, which is their usage throttled by limits.
+        let demands: BTreeMap<String, f64> = usage
+            .iter()
+            .map(|(pod_name, (cpu_usage, _))| {
+                let cpu_limit = self.running_pods[pod_name].pod_limits.cpu;
+                if config.resource_limits.enabled && cpu_limit > 0 {
+                    (pod_name.clone(), cpu_usage.min(cpu_limit as f64))
+                } else {
+                    (pod_name.clone(), *cpu_usage)
+                }
+            })
+            .collect();
+        let shares = if config.cpu_contention.enabled {
+            let capacity = self.get_node().status.capacity.cpu as f64;
+            fair_share(&demands, capacity)
+        } else {
+            demands
+        };
+
+        for (pod_name, (cpu_usage, _)) in usage.iter() {
+            let speed = if *cpu_usage > 0.0 {
+                (shares[pod_name] / cpu_usage).min(1.0)
+            } else {
+                1.0
+            };
+            self.set_pod_run_speed(pod_name, speed, time);
+        }
+    }
+
+    /// Returns true if the pod should run again on the node after its run finished with the result.
+    fn should_restart(&self, pod_name: &str, finish_result: &PodConditionType) -> bool {
+        match self.running_pods.get(pod_name).unwrap().restart_policy {
+            RestartPolicy::Always => true,
+            RestartPolicy::OnFailure => *finish_result == PodConditionType::PodFailed,
+            RestartPolicy::Never => false,
+        }
+    }
+
+    /// Schedules restart of the pod after CrashLoopBackOff delay counted from its finish time.
+    /// The pod keeps its resources on the node while waiting for restart.
+    fn schedule_pod_restart(&mut self, pod_name: &str, finish_time: f64, event_time: f64) {
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        if finish_time - info.run_start_time >= CRASH_LOOP_BACKOFF_RESET_TIME {
+            info.backoff_restarts = 0;
+        }
+        let backoff = (CRASH_LOOP_BACKOFF_INITIAL_DELAY * 2f64.powi(info.backoff_restarts as i32))
+            .min(CRASH_LOOP_BACKOFF_MAX_DELAY);
+        info.backoff_restarts += 1;
+        info.current_run = None;
+
+        // Finish event comes to self with network delay, so it is subtracted from the backoff.
+        let delay = (finish_time + backoff - event_time).max(0.0);
+        info.event_id = Some(self.ctx.emit_self(
+            RestartPod {
+                pod_name: pod_name.to_string(),
+            },
+            delay,
+        ));
+    }
+
+    /// Evicts running pod from the node. Pod could finish running or be canceled due to node
+    /// removal earlier than eviction request comes, then it is not evicted.
+    fn evict_pod(&mut self, pod_name: String, reason: EvictionReason, eviction_time: f64) {
+        let mut evicted = false;
+        if let Some(info) = self.remove_running_pod(&pod_name) {
+            self.free_pod_requests(&info.pod_requests);
+            self.cancel_pod_events(&info);
+            self.evicted_pods.insert(pod_name.clone(), eviction_time);
+            self.update_pod_run_speeds(eviction_time);
+            evicted = true;
+        }
+
+        self.ctx.emit(
+            PodEvictedFromNode {
+                evicted,
+                eviction_time,
+                pod_name,
+                node_name: self.node_name().to_string(),
+                reason,
+            },
+            self.runtime.as_ref().unwrap().api_server,
+            self.runtime
+                .as_ref()
+                .unwrap()
+                .config
+                .as_to_node_network_delay,
+        );
+        self.check_drained(eviction_time);
+    }
+
+    /// Starts draining the node: every running pod is evicted after the grace period unless it
+    /// finishes earlier. Pods bound to the node during the drain are evicted the same way.
+    fn drain(&mut self, grace_period: f64, event_time: f64) {
+        self.drain_grace_period = Some(grace_period);
+        let mut pod_names: Vec<String> = self.running_pods.keys().cloned().collect();
+        pod_names.sort();
+        for pod_name in pod_names.iter() {
+            self.schedule_drain_eviction(pod_name);
+        }
+        self.check_drained(event_time);
+    }
+
+    fn schedule_drain_eviction(&mut self, pod_name: &str) {
+        let grace_period = self.drain_grace_period.unwrap();
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        if info.drain_eviction.is_some() {
+            return;
+        }
+        info.drain_eviction = Some(self.ctx.emit_self(
+            EvictPodRequest {
+                pod_name: pod_name.to_string(),
+                reason: EvictionReason::NodeDrain,
+            },
+            grace_period,
+        ));
+    }
+
+    /// Tells api server that the node being drained has no pods left. Drain is over after that.
+    fn check_drained(&mut self, event_time: f64) {
+        if self.drain_grace_period.is_none() || !self.running_pods.is_empty() || self.removed {
+            return;
+        }
+        self.drain_grace_period = None;
+        self.ctx.emit(
+            NodeDrained {
+                node_name: self.node_name().to_string(),
+                drain_time: event_time,
+            },
+            self.runtime.as_ref().unwrap().api_server,
+            self.runtime
+                .as_ref()
+                .unwrap()
+                .config
+                .as_to_node_network_delay,
+        );
+    }
+
+    /// Starts sending heartbeats to api server if node lifecycle controller watches them.
+    pub fn start_heartbeats(&mut self) {
+        if self
+            .runtime
+            .as_ref()
+            .unwrap()
+            .config
+            .node_lifecycle_controller
+            .enabled
+        {
+            self.heartbeat_event = Some(self.ctx.emit_self_now(RunNodeHeartbeatCycle {}));
+        }
+    }
+
+    /// Failed node does not send heartbeats but keeps the cycle to resume them upon recovery.
+    fn run_heartbeat_cycle(&mut self, event_time: f64) {
+        let runtime = self.runtime.as_ref().unwrap();
+        if self.failure.is_none() {
+            self.ctx.emit(
+                NodeHeartbeat {
+                    node_name: runtime.node.metadata.name.clone(),
+                    heartbeat_time: event_time,
+                },
+                runtime.api_server,
+                runtime.config.as_to_node_network_delay,
+            );
+        }
+        self.heartbeat_event = Some(
+            self.ctx.emit_self(
+                RunNodeHeartbeatCycle {},
+                runtime
+                    .config
+                    .node_lifecycle_controller
+                    .node_status_update_frequency,
+            ),
+        );
+    }
+
+    /// Starts checking resource usage of running pods if node-pressure eviction is enabled.
+    pub fn start_pressure_checks(&mut self) {
+        if self.runtime.as_ref().unwrap().config.node_pressure.enabled {
+            self.pressure_check_event = Some(self.ctx.emit_self_now(RunNodePressureCheckCycle {}));
+        }
+    }
+
+    /// Returns actual cpu and ram usage of the running pod.
+    fn pod_usage(&mut self, pod_name: &str, time: f64) -> (f64, f64) {
+        let pod_group_sizes = self.runtime.as_ref().unwrap().pod_group_sizes.clone();
+        let info = self.running_pods.get_mut(pod_name).unwrap();
+        let pod_group_size = info
+            .pod_group
+            .as_ref()
+            .map(|pod_group| pod_group_sizes.borrow()[pod_group]);
+        info.current_usage(time, pod_group_size)
+    }
+
+    /// Returns map from running pod name to its actual cpu and ram usage.
+    fn current_pods_usage(&mut self, time: f64) -> BTreeMap<String, (f64, f64)> {
+        let pod_names: Vec<String> = self.running_pods.keys().cloned().collect();
+        pod_names
+            .into_iter()
+            .map(|pod_name| {
+                let usage = self.pod_usage(&pod_name, time);
+                (pod_name, usage)
+            })
+            .collect()
+    }
+
+    /// Picks the pod to evict under memory pressure: pods of the least protected QoS class go
+    /// first, then the ones with the largest memory usage above requests.
+    fn pod_to_evict_under_memory_pressure(&self, usage: &BTreeMap<String, (f64, f64)>) -> String {
+        let qos_class = |pod_name: &str| &self.running_pods[pod_name].qos_class;
+        let excess = |pod_name: &str, ram_usage: f64| {
+            ram_usage - self.running_pods[pod_name].pod_requests.ram as f64
+        };
+        usage
+            .iter()
+            .min_by(|(lhs_name, lhs), (rhs_name, rhs)| {
+                qos_class(lhs_name)
+                    .cmp(qos_class(rhs_name))
+                    .then(excess(rhs_name, rhs.1).total_cmp(&excess(lhs_name, lhs.1)))
+            })
+            .map(|(pod_name, _)| pod_name.clone())
+            .unwrap()
+    }
+
+    /// Tells api server about the change of pressure condition of the node.
+    fn update_pressure_condition(
+        &mut self,
+        condition_type: NodeConditionType,
+        under_pressure: bool,
+        event_time: f64,
+    ) {
+        let runtime = self.runtime.as_mut().unwrap();
+        let was_under_pressure = runtime
+            .node
+            .get_condition(condition_type.clone())
+            .map_or(false, |condition| condition.status == "True");
+        if was_under_pressure == under_pressure {
+            return;
+        }
+        let status = if under_pressure { "True" } else { "False" }.to_string();
+        runtime
+            .node
+            .update_condition(status.clone(), condition_type.clone(), event_time);
+        self.ctx.emit(
+            UpdateNodeConditionRequest {
+                node_name: runtime.node.metadata.name.clone(),
+                condition_type,
+                status,
+                transition_time: event_time,
+            },
+            runtime.api_server,
+            runtime.config.as_to_node_network_delay,
+        );
+    }
+
+    /// Compares actual usage of running pods with node capacity. Node under memory pressure evicts
+    /// one pod per check like kubelet does.
+    fn run_pressure_check_cycle(&mut self, event_time: f64) {
+        let usage = self.current_pods_usage(event_time);
+        let cpu_usage: f64 = usage.values().map(|(cpu, _)| cpu).sum();
+        let ram_usage: f64 = usage.values().map(|(_, ram)| ram).sum();
+
+        let runtime = self.runtime.as_ref().unwrap();
+        let capacity = &runtime.node.status.capacity;
+        let config = &runtime.config.node_pressure;
+        let memory_pressure = ram_usage > config.memory_pressure_threshold * capacity.ram as f64;
+        let cpu_pressure = cpu_usage > config.cpu_pressure_threshold * capacity.cpu as f64;
+        let housekeeping_interval = config.housekeeping_interval;
+
+        self.update_pressure_condition(
+            NodeConditionType::MemoryPressure,
+            memory_pressure,
+            event_time,
+        );
+        self.update_pressure_condition(NodeConditionType::CPUPressure, cpu_pressure, event_time);
+
+        if memory_pressure {
+            let pod_name = self.pod_to_evict_under_memory_pressure(&usage);
+            self.evict_pod(pod_name, EvictionReason::NodePressure, event_time);
+        }
+
+        self.pressure_check_event = Some(
+            self.ctx
+                .emit_self(RunNodePressureCheckCycle {}, housekeeping_interval),
+        );
+    }
+
+    /// Starts checking usage of running pods against their limits if limits are enforced.
+    pub fn start_limits_checks(&mut self) {
+        if self
+            .runtime
+            .as_ref()
+            .unwrap()
+            .config
+            .resource_limits
+            .enabled
+        {
+            self.limits_check_event = Some(self.ctx.emit_self_now(RunPodLimitsCheckCycle {}));
+        }
+    }
+
+    fn run_limits_check_cycle(&mut self, event_time: f64) {
+        self.update_pod_run_speeds(event_time);
+
+        let usage_check_interval = self
+            .runtime
+            .as_ref()
+            .unwrap()
+            .config
+            .resource_limits
+            .usage_check_interval;
+        self.limits_check_event = Some(
+            self.ctx
+                .emit_self(RunPodLimitsCheckCycle {}, usage_check_interval),
+        );
+    }
+
+    fn send_pod_failed(&self, pod_name: String, qos_class: QoSClass, failure_time: f64) {
+        self.ctx.emit(
+            PodFinishedRunning {
+                pod_name,
+                node_name: self.node_name().to_string(),
+                finish_time: failure_time,
+                finish_result: PodConditionType::PodFailed,
+                failure_reason: Some(PodFailureReason::NodeFailure),
+                qos_class,
+            },
+            self.runtime.as_ref().unwrap().api_server,
+            self.runtime
+                .as_ref()
+                .unwrap()
+                .config
+                .as_to_node_network_delay,
+        );
+    }
+
+    /// Soft failure only makes the node not ready, while hard failure also fails all running pods.
+    /// Soft failure of a node with hard failure changes nothing.
+    fn fail(&mut self, failure_type: NodeFailureType, failure_time: f64) {
+        if self.failure == Some(NodeFailureType::Hard) {
+            return;
+        }
+        if failure_type == NodeFailureType::Hard {
+            let mut pod_names: Vec<String> = self.running_pods.keys().cloned().collect();
+            pod_names.sort();
+            for pod_name in pod_names {
+                let info = self.remove_running_pod(&pod_name).unwrap();
+                self.free_pod_requests(&info.pod_requests);
+                self.cancel_pod_events(&info);
+                self.send_pod_failed(pod_name, info.qos_class, failure_time);
+            }
+            self.check_drained(failure_time);
+        }
+        self.failure = Some(failure_type.clone());
+
+        self.ctx.emit(
+            NodeFailed {
+                node_name: self.node_name().to_string(),
+                failure_type,
+                failure_time,
+            },
+            self.runtime.as_ref().unwrap().api_server,
+            self.runtime
+                .as_ref()
+                .unwrap()
+                .config
+                .as_to_node_network_delay,
+        );
+    }
+
+    /// Applies NoExecute taints of the node to the running pod like taint manager does. Pod is
+    /// evicted immediately if it does not tolerate some of the taints. If tolerations are limited
+    /// in time, the eviction is delayed by the minimal `toleration_seconds`, otherwise the delayed
+    /// eviction is canceled if any. Already delayed eviction is brought forward if a new taint is
+    /// tolerated for shorter time, the delay is still counted from the original start time.
+    fn apply_no_execute_taints(&mut self, pod_name: &str, event_time: f64) {
+        let taints = self.get_node().spec.taints.clone();
+        let info = self.running_pods.get(pod_name).unwrap();
+
+        let mut eviction_delay: Option<f64> = None;
+        for taint in taints
+            .iter()
+            .filter(|taint| taint.effect == TaintEffect::NoExecute)
+        {
+            let mut tolerated = false;
+            for toleration in info.pod_tolerations.iter() {
+                if !toleration.tolerates(taint) {
+                    continue;
+                }
+                tolerated = true;
+                if let Some(seconds) = toleration.toleration_seconds {
+                    eviction_delay = Some(eviction_delay.map_or(seconds, |d| d.min(seconds)));
+                }
+            }
+            if !tolerated {
+                self.evict_pod(
+                    pod_name.to_string(),
+                    EvictionReason::NoExecuteTaint,
+                    event_time,
+                );
+                return;
+            }
+        }
+
+        match (eviction_delay, info.taint_eviction) {
+            (Some(delay), None) => {
+                let event_id = self.ctx.emit_self(
+                    EvictPodRequest {
+                        pod_name: pod_name.to_string(),
+                        reason: EvictionReason::NoExecuteTaint,
+                    },
+                    delay,
+                );
+                self.running_pods.get_mut(pod_name).unwrap().taint_eviction =
+                    Some((event_id, event_time, event_time + delay));
+            }
+            (Some(delay), Some((event_id, start_time, eviction_time)))
+                if start_time + delay < eviction_time =>
+            {
+                self.ctx.cancel_event(event_id);
+                let eviction_time = start_time + delay;
+                let event_id = self.ctx.emit_self(
+                    EvictPodRequest {
+                        pod_name: pod_name.to_string(),
+                        reason: EvictionReason::NoExecuteTaint,
+                    },
+                    (eviction_time - event_time).max(0.0),
+                );
+                self.running_pods.get_mut(pod_name).unwrap().taint_eviction =
+                    Some((event_id, start_time, eviction_time));
+            }
+            (None, Some((event_id, _, _))) => {
+                self.ctx.cancel_event(event_id);
+                self.running_pods.get_mut(pod_name).unwrap().taint_eviction = None;
+            }
+            // Already scheduled eviction is kept as the pod started tolerating taints earlier.
+            _ => {}
+        }
+    }
+
+    fn apply_no_execute_taints_to_all_pods(&mut self, event_time: f64) {
+        let mut pod_names: Vec<String> = self.running_pods.keys().cloned().collect();
+        pod_names.sort();
+        for pod_name in pod_names.iter() {
+            self.apply_no_execute_taints(pod_name, event_time);
+        }
     }
 }
 
@@ -181,10 +1048,16 @@ impl EventHandler for NodeComponent {
             BindPodToNodeRequest {
                 pod_name,
                 pod_requests,
+                pod_limits,
+                pod_qos_class,
+                pod_tolerations,
                 pod_group,
                 pod_group_creation_time,
                 node_name,
                 pod_duration,
+                pod_restart_policy,
+                pod_failure_probability,
+                pod_failure_time,
                 resources_usage_model_config,
             } => {
                 assert!(
@@ -201,19 +1074,33 @@ impl EventHandler for NodeComponent {
                     node_name
                 );
 
+                self.evicted_pods.remove(&pod_name);
+
+                // Pod could be assigned while the node was failing, it cannot run there.
+                if self.failure == Some(NodeFailureType::Hard) {
+                    self.send_pod_failed(pod_name, pod_qos_class, event.time);
+                    return;
+                }
+
                 self.simulate_pod_runtime(
                     event.time,
                     pod_name.clone(),
                     pod_requests,
+                    pod_limits,
+                    pod_qos_class,
+                    pod_tolerations,
                     pod_group,
                     pod_group_creation_time,
                     pod_duration,
+                    pod_restart_policy,
+                    pod_failure_probability,
+                    pod_failure_time,
                     resources_usage_model_config,
                 );
 
                 self.ctx.emit(
                     PodStartedRunning {
-                        pod_name,
+                        pod_name: pod_name.clone(),
                         start_time: event.time,
                     },
                     self.runtime.as_ref().unwrap().api_server,
@@ -223,16 +1110,34 @@ impl EventHandler for NodeComponent {
                         .config
                         .as_to_node_network_delay,
                 );
+
+                // Node could start draining while the pod was being assigned to it.
+                if self.drain_grace_period.is_some() {
+                    self.schedule_drain_eviction(&pod_name);
+                }
+
+                // Node could be tainted while the pod was being assigned to it.
+                self.apply_no_execute_taints(&pod_name, event.time);
             }
             PodFinishedRunning {
                 pod_name,
                 node_name,
                 finish_time,
                 finish_result,
+                failure_reason,
+                qos_class,
             } => {
-                let info = self.running_pods.remove(&pod_name).unwrap();
+                if self.should_restart(&pod_name, &finish_result) {
+                    self.schedule_pod_restart(&pod_name, finish_time, event.time);
+                    self.update_pod_run_speeds(event.time);
+                    return;
+                }
+
+                let info = self.remove_running_pod(&pod_name).unwrap();
 
                 self.free_pod_requests(&info.pod_requests);
+                self.cancel_pod_events(&info);
+                self.update_pod_run_speeds(event.time);
 
                 self.ctx.emit_now(
                     PodFinishedRunning {
@@ -240,9 +1145,12 @@ impl EventHandler for NodeComponent {
                         node_name,
                         finish_time,
                         finish_result,
+                        failure_reason,
+                        qos_class,
                     },
                     self.runtime.as_ref().unwrap().api_server,
                 );
+                self.check_drained(event.time);
             }
             RemoveNodeRequest { node_name } => {
                 assert_eq!(
@@ -255,6 +1163,15 @@ impl EventHandler for NodeComponent {
                 // Here we should cancel all events which have been already submitted to simulation
                 // queue as running events as we terminate.
                 self.cancel_all_running_pods();
+                if let Some(event_id) = self.heartbeat_event.take() {
+                    self.ctx.cancel_event(event_id);
+                }
+                if let Some(event_id) = self.pressure_check_event.take() {
+                    self.ctx.cancel_event(event_id);
+                }
+                if let Some(event_id) = self.limits_check_event.take() {
+                    self.ctx.cancel_event(event_id);
+                }
 
                 self.ctx.emit(
                     NodeRemovedFromCluster {
@@ -275,11 +1192,10 @@ impl EventHandler for NodeComponent {
             RemovePodRequest { pod_name } => {
                 if self.running_pods.contains_key(&pod_name) {
                     // pod is still running - cancel it and send response to api server about removal
-                    let info = self.running_pods.remove(&pod_name).unwrap();
+                    let info = self.remove_running_pod(&pod_name).unwrap();
                     self.free_pod_requests(&info.pod_requests);
-                    if let Some(event_id) = info.event_id {
-                        self.ctx.cancel_event(event_id);
-                    }
+                    self.cancel_pod_events(&info);
+                    self.update_pod_run_speeds(event.time);
                     self.ctx.emit(
                         PodRemovedFromNode {
                             removed: true,
@@ -293,6 +1209,26 @@ impl EventHandler for NodeComponent {
                             .config
                             .as_to_node_network_delay,
                     );
+                    self.check_drained(event.time);
+                    return;
+                }
+
+                if let Some(eviction_time) = self.evicted_pods.remove(&pod_name) {
+                    // pod is already evicted from the node - consider it as removed at time of
+                    // eviction.
+                    self.ctx.emit(
+                        PodRemovedFromNode {
+                            removed: true,
+                            removal_time: eviction_time,
+                            pod_name,
+                        },
+                        self.runtime.as_ref().unwrap().api_server,
+                        self.runtime
+                            .as_ref()
+                            .unwrap()
+                            .config
+                            .as_to_node_network_delay,
+                    );
                     return;
                 }
 
@@ -331,6 +1267,100 @@ impl EventHandler for NodeComponent {
                         .as_to_node_network_delay,
                 );
             }
+            RestartPod { pod_name } => {
+                self.start_pod_run(&pod_name, event.time);
+
+                self.ctx.emit(
+                    PodRestarted {
+                        pod_name,
+                        node_name: self.node_name().to_string(),
+                        restart_time: event.time,
+                    },
+                    self.runtime.as_ref().unwrap().api_server,
+                    self.runtime
+                        .as_ref()
+                        .unwrap()
+                        .config
+                        .as_to_node_network_delay,
+                );
+            }
+            EvictPodRequest { pod_name, reason } => {
+                self.evict_pod(pod_name, reason, event.time);
+            }
+            RunNodeHeartbeatCycle {} => {
+                self.run_heartbeat_cycle(event.time);
+            }
+            RunNodePressureCheckCycle {} => {
+                self.run_pressure_check_cycle(event.time);
+            }
+            RunPodLimitsCheckCycle {} => {
+                self.run_limits_check_cycle(event.time);
+            }
+            FailNodeRequest {
+                node_name,
+                failure_type,
+            } => {
+                assert_eq!(node_name, self.node_name());
+                self.fail(failure_type, event.time);
+            }
+            RecoverNodeRequest { node_name } => {
+                assert_eq!(node_name, self.node_name());
+                if self.failure.take().is_none() {
+                    return;
+                }
+                self.ctx.emit(
+                    NodeRecovered {
+                        node_name,
+                        recovery_time: event.time,
+                    },
+                    self.runtime.as_ref().unwrap().api_server,
+                    self.runtime
+                        .as_ref()
+                        .unwrap()
+                        .config
+                        .as_to_node_network_delay,
+                );
+            }
+            CordonNodeRequest { node_name } => {
+                assert_eq!(node_name, self.node_name());
+                self.runtime.as_mut().unwrap().node.spec.unschedulable = true;
+            }
+            UncordonNodeRequest { node_name } => {
+                assert_eq!(node_name, self.node_name());
+                self.runtime.as_mut().unwrap().node.spec.unschedulable = false;
+            }
+            DrainNodeRequest {
+                node_name,
+                grace_period,
+                remove_node: _,
+            } => {
+                assert_eq!(node_name, self.node_name());
+                self.drain(grace_period, event.time);
+            }
+            TaintNodeRequest { node_name, taint } => {
+                assert_eq!(node_name, self.node_name());
+                let effect = taint.effect.clone();
+                self.runtime.as_mut().unwrap().node.add_taint(taint);
+                if effect == TaintEffect::NoExecute {
+                    self.apply_no_execute_taints_to_all_pods(event.time);
+                }
+            }
+            UntaintNodeRequest {
+                node_name,
+                key,
+                effect,
+            } => {
+                assert_eq!(node_name, self.node_name());
+                self.runtime
+                    .as_mut()
+                    .unwrap()
+                    .node
+                    .remove_taint(&key, &effect);
+                if effect == TaintEffect::NoExecute {
+                    // Pods may tolerate the rest of taints forever now.
+                    self.apply_no_execute_taints_to_all_pods(event.time);
+                }
+            }
         });
     }
 }
@@ -10,7 +10,7 @@
 //!
 
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
 use dslab_core::Simulation;
@@ -23,12 +23,15 @@ use crate::config::SimulationConfig;
 
 pub struct NodeComponentPool {
     pool: VecDeque<Rc<RefCell<NodeComponent>>>,
+    /// Number of running pods in each pod group, shared by all nodes of the pool.
+    pod_group_sizes: Rc<RefCell<HashMap<String, usize>>>,
 }
 
 impl Default for NodeComponentPool {
     fn default() -> Self {
         Self {
             pool: Default::default(),
+            pod_group_sizes: Default::default(),
         }
     }
 }
@@ -44,7 +47,10 @@ impl NodeComponentPool {
             sim.add_handler(context_name, node_component.clone());
             pool.push_back(node_component)
         }
-        Self { pool }
+        Self {
+            pool,
+            pod_group_sizes: Default::default(),
+        }
     }
 
     pub fn allocate_component(
@@ -61,15 +67,26 @@ impl NodeComponentPool {
             api_server,
             node,
             config,
+            pod_group_sizes: self.pod_group_sizes.clone(),
         });
         node_component
     }
 
+    pub fn pod_group_sizes(&self) -> Rc<RefCell<HashMap<String, usize>>> {
+        self.pod_group_sizes.clone()
+    }
+
     pub fn reclaim_component(&mut self, node_component: Rc<RefCell<NodeComponent>>) {
         node_component.borrow_mut().runtime = None;
         node_component.borrow_mut().removed = false;
         node_component.borrow_mut().removal_time = 0.0;
+        node_component.borrow_mut().failure = None;
+        node_component.borrow_mut().heartbeat_event = None;
+        node_component.borrow_mut().pressure_check_event = None;
+        node_component.borrow_mut().limits_check_event = None;
+        node_component.borrow_mut().drain_grace_period = None;
         node_component.borrow_mut().canceled_pods.clear();
+        node_component.borrow_mut().evicted_pods.clear();
         node_component.borrow_mut().running_pods.clear();
 
         self.pool.push_back(node_component);
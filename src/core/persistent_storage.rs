@@ -6,6 +6,7 @@ use std::cell::RefCell;
 use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 
+use dslab_core::event::EventData;
 use dslab_core::{cast, log_debug, Event, EventHandler, SimulationContext};
 
 use crate::autoscalers::cluster_autoscaler::interface::{
@@ -15,28 +16,40 @@ use crate::autoscalers::cluster_autoscaler::kube_cluster_autoscaler::CLUSTER_AUT
 
 use crate::core::common::{ObjectsInfo, RuntimeResourcesUsageModelConfig, SimComponentId};
 use crate::core::events::{
-    AddNodeToCache, AssignPodToNodeRequest, AssignPodToNodeResponse, ClusterAutoscalerRequest,
-    ClusterAutoscalerResponse, CreateNodeRequest, CreateNodeResponse, CreatePodRequest,
-    NodeAddedToCluster, NodeRemovedFromCluster, PodFinishedRunning, PodNotScheduled,
-    PodRemovedFromNode, PodScheduleRequest, PodStartedRunning, RemoveNodeFromCache,
-    RemoveNodeRequest, RemoveNodeResponse, RemovePodFromCache, RemovePodRequest, RemovePodResponse,
+    AddNodeToCache, AddPodToCache, AssignPodToNodeRequest, AssignPodToNodeResponse,
+    ClusterAutoscalerRequest, ClusterAutoscalerResponse, CordonNodeRequest, CordonNodeResponse,
+    CreateNodeRequest, CreateNodeResponse, CreatePodRequest, DeschedulerRequest,
+    DeschedulerResponse, EvictPodRequest, EvictPodResponse, NodeAddedToCluster, NodeFailed,
+    NodeRecovered, NodeRemovedFromCluster, PodAssignmentConflict, PodEvictedFromNode,
+    PodFinishedRunning, PodNotScheduled, PodRemovedFromNode, PodRestarted, PodScheduleRequest,
+    PodStartedRunning, RemoveNodeFromCache, RemoveNodeRequest, RemoveNodeResponse,
+    RemovePodFromCache, RemovePodRequest, RemovePodResponse, TaintNodeRequest, TaintNodeResponse,
+    UncordonNodeRequest, UncordonNodeResponse, UntaintNodeRequest, UntaintNodeResponse,
+    UpdateNodeConditionRequest, UpdateNodeInCache,
 };
-use crate::core::node::{Node, NodeConditionType};
+use crate::core::node::{Node, NodeConditionType, Taint, TaintEffect, NODE_NOT_READY_TAINT_KEY};
 use crate::core::pod::{Pod, PodConditionType};
 use crate::core::resource_usage::helpers::default_resource_usage_config;
+use crate::core::scheduler::kube_scheduler::pod_scheduler_name;
 
 use crate::config::SimulationConfig;
 use crate::metrics::collector::MetricsCollector;
 
 pub struct PersistentStorage {
     api_server: SimComponentId,
-    scheduler: SimComponentId,
+    /// Scheduler components and network delays to them, the first one is the default scheduler.
+    schedulers: Vec<(SimComponentId, f64)>,
+    /// Map from scheduler name to the index of the component in `schedulers` which schedules pods
+    /// with that name. Pods with other names go to the default scheduler.
+    scheduler_indices: HashMap<String, usize>,
 
     storage_data: ObjectsInfo,
     /// Map of node name and pod names which were assigned to that node.
     /// Pods that finished running successfully. Transferred from `storage_data` upon finish.
     assignments: HashMap<String, BTreeSet<String>>,
     pub succeeded_pods: HashMap<String, Pod>,
+    /// Pods that failed with their node. Transferred from `storage_data` upon failure.
+    pub failed_pods: HashMap<String, Pod>,
 
     unscheduled_pods_cache: BTreeSet<String>,
 
@@ -56,10 +69,12 @@ impl PersistentStorage {
     ) -> Self {
         Self {
             api_server: api_server_id,
-            scheduler: scheduler_id,
+            schedulers: vec![(scheduler_id, config.ps_to_sched_network_delay)],
+            scheduler_indices: Default::default(),
             storage_data: Default::default(),
             assignments: Default::default(),
             succeeded_pods: Default::default(),
+            failed_pods: Default::default(),
             unscheduled_pods_cache: Default::default(),
             ctx,
             config,
@@ -67,6 +82,20 @@ impl PersistentStorage {
         }
     }
 
+    /// Registers one more scheduler component which schedules pods with the given scheduler names.
+    pub fn add_scheduler(
+        &mut self,
+        scheduler_id: SimComponentId,
+        scheduler_names: Vec<String>,
+        network_delay: f64,
+    ) {
+        let scheduler_idx = self.schedulers.len();
+        self.schedulers.push((scheduler_id, network_delay));
+        for scheduler_name in scheduler_names.into_iter() {
+            self.scheduler_indices.insert(scheduler_name, scheduler_idx);
+        }
+    }
+
     pub fn add_node(&mut self, node: Node) {
         let node_name = node.metadata.name.clone();
         let existing_key = self
@@ -98,6 +127,10 @@ impl PersistentStorage {
         self.storage_data.nodes.get(node_name)
     }
 
+    pub fn get_pod(&self, pod_name: &str) -> Option<&Pod> {
+        self.storage_data.pods.get(pod_name)
+    }
+
     pub fn node_count(&self) -> usize {
         self.storage_data.nodes.len()
     }
@@ -167,6 +200,27 @@ impl PersistentStorage {
         }
     }
 
+    /// Returns index of the scheduler component responsible for the pod.
+    fn pod_scheduler(&self, pod: &Pod) -> usize {
+        *self
+            .scheduler_indices
+            .get(pod_scheduler_name(pod))
+            .unwrap_or(&0)
+    }
+
+    fn emit_to_scheduler<T: EventData>(&self, data: T, scheduler_idx: usize) {
+        let (scheduler, network_delay) = self.schedulers[scheduler_idx];
+        self.ctx.emit(data, scheduler, network_delay);
+    }
+
+    /// Every scheduler keeps its own cache of nodes and assigned pods, so cluster changes are sent
+    /// to all of them.
+    fn emit_to_all_schedulers<T: EventData + Clone>(&self, data: T) {
+        for &(scheduler, network_delay) in self.schedulers.iter() {
+            self.ctx.emit(data.clone(), scheduler, network_delay);
+        }
+    }
+
     /// Release node resources which pod has taken and remove pod from assignments.
     /// It is is optional as we could persist remove node request earlier then pod could finish or
     /// removed. In remove request handling we remove node from storage.
@@ -205,11 +259,7 @@ impl EventHandler for PersistentStorage {
                     .update_condition("True".to_string(), NodeConditionType::NodeCreated, add_time);
                 // tell scheduler about new node in the cluster
                 let node = self.storage_data.nodes.get(&node_name).unwrap().clone();
-                self.ctx.emit(
-                    AddNodeToCache { node },
-                    self.scheduler,
-                    self.config.ps_to_sched_network_delay,
-                );
+                self.emit_to_all_schedulers(AddNodeToCache { node });
                 log_debug!(
                     self.ctx,
                     "Updated node conditions: {:?}",
@@ -226,6 +276,7 @@ impl EventHandler for PersistentStorage {
                 // Considering creation time as the time pod added to the persistent storage,
                 // because it is just an entry in hash map.
                 pod.update_condition("True".to_string(), PodConditionType::PodCreated, event.time);
+                pod.status.qos_class = pod.spec.resources.qos_class();
 
                 if pod.spec.resources.usage_model_config.is_none() {
                     pod.spec.resources.usage_model_config =
@@ -240,18 +291,44 @@ impl EventHandler for PersistentStorage {
                 }
 
                 self.add_pod(pod.clone());
-                // Send info about newly created pod to scheduler.
-                self.ctx.emit(
-                    PodScheduleRequest { pod },
-                    self.scheduler,
-                    self.config.ps_to_sched_network_delay,
-                );
+                // Send info about newly created pod to the scheduler chosen by its scheduler name.
+                let scheduler_idx = self.pod_scheduler(&pod);
+                self.emit_to_scheduler(PodScheduleRequest { pod }, scheduler_idx);
             }
             AssignPodToNodeRequest {
                 assign_time,
                 pod_name,
                 node_name,
             } => {
+                // Schedulers assign pods based on their own caches, so resources of the node might
+                // be already taken by a pod of another scheduler. Then the later assignment is
+                // rejected like an update of a stale object.
+                let pod = self.storage_data.pods.get(&pod_name).unwrap();
+                let node = self.storage_data.nodes.get(&node_name).unwrap();
+                let scheduler_idx = self.pod_scheduler(pod);
+                if pod.spec.resources.requests.cpu > node.status.allocatable.cpu
+                    || pod.spec.resources.requests.ram > node.status.allocatable.ram
+                {
+                    log_debug!(
+                        self.ctx,
+                        "Assignment of pod {} to node {} conflicts with other assignments",
+                        pod_name,
+                        node_name
+                    );
+                    self.metrics_collector
+                        .borrow_mut()
+                        .accumulated_metrics
+                        .pod_assignment_conflicts += 1;
+                    self.emit_to_scheduler(
+                        PodAssignmentConflict {
+                            pod_name,
+                            node_name,
+                        },
+                        scheduler_idx,
+                    );
+                    return;
+                }
+
                 let pod = self.storage_data.pods.get_mut(&pod_name).unwrap();
                 pod.update_condition(
                     "True".to_string(),
@@ -270,10 +347,21 @@ impl EventHandler for PersistentStorage {
                     .unwrap()
                     .insert(pod_name.clone());
 
+                // Other schedulers should account resources taken by the pod.
+                for (idx, &(scheduler, network_delay)) in self.schedulers.iter().enumerate() {
+                    if idx != scheduler_idx {
+                        self.ctx
+                            .emit(AddPodToCache { pod: pod.clone() }, scheduler, network_delay);
+                    }
+                }
+
                 self.ctx.emit(
                     AssignPodToNodeResponse {
                         pod_name,
                         pod_requests: pod.spec.resources.requests.clone(),
+                        pod_limits: pod.spec.resources.limits.clone(),
+                        pod_qos_class: pod.status.qos_class.clone(),
+                        pod_tolerations: pod.spec.tolerations.clone(),
                         pod_group: pod.metadata.labels.get("pod_group").cloned(),
                         pod_group_creation_time: pod
                             .metadata
@@ -282,6 +370,9 @@ impl EventHandler for PersistentStorage {
                             .cloned(),
                         node_name,
                         pod_duration: pod.spec.running_duration,
+                        pod_restart_policy: pod.spec.restart_policy.clone(),
+                        pod_failure_probability: pod.spec.failure_probability,
+                        pod_failure_time: pod.spec.failure_time,
                         resources_usage_model_config: pod
                             .spec
                             .resources
@@ -313,11 +404,30 @@ impl EventHandler for PersistentStorage {
                 let pod = self.storage_data.pods.get_mut(&pod_name).unwrap();
                 pod.update_condition("True".to_string(), PodConditionType::PodRunning, start_time);
             }
+            PodRestarted {
+                pod_name,
+                node_name,
+                restart_time,
+            } => {
+                // Pod could be removed or evicted while its restart was on the way.
+                let pod = match self.storage_data.pods.get_mut(&pod_name) {
+                    Some(pod) if pod.status.assigned_node == node_name => pod,
+                    _ => return,
+                };
+                pod.status.restart_count += 1;
+                pod.update_condition(
+                    "True".to_string(),
+                    PodConditionType::PodRunning,
+                    restart_time,
+                );
+            }
             PodFinishedRunning {
                 pod_name,
                 node_name,
                 finish_time,
                 finish_result,
+                failure_reason,
+                qos_class,
             } => {
                 // Remove request may come earlier and remove pod from storage, so we check if
                 // it's still in there, update condition and clean up information.
@@ -325,26 +435,30 @@ impl EventHandler for PersistentStorage {
                     let (pod_name, mut pod) =
                         self.storage_data.pods.remove_entry(&pod_name).unwrap();
                     pod.update_condition("True".to_string(), finish_result.clone(), finish_time);
+                    pod.status.failure_reason = failure_reason.clone();
 
                     self.clean_up_pod_info(&pod);
 
-                    self.metrics_collector
-                        .borrow_mut()
-                        .accumulated_metrics
-                        .increment_pod_duration(pod.spec.running_duration.unwrap());
-                    self.succeeded_pods.insert(pod_name, pod);
+                    if finish_result == PodConditionType::PodFailed {
+                        // Failed pod may be long running, so it does not count in pod duration.
+                        self.failed_pods.insert(pod_name, pod);
+                    } else {
+                        self.metrics_collector
+                            .borrow_mut()
+                            .accumulated_metrics
+                            .increment_pod_duration(pod.spec.running_duration.unwrap());
+                        self.succeeded_pods.insert(pod_name, pod);
+                    }
                 }
 
-                self.ctx.emit(
-                    PodFinishedRunning {
-                        pod_name: pod_name.clone(),
-                        node_name,
-                        finish_time,
-                        finish_result,
-                    },
-                    self.scheduler,
-                    self.config.ps_to_sched_network_delay,
-                );
+                self.emit_to_all_schedulers(PodFinishedRunning {
+                    pod_name: pod_name.clone(),
+                    node_name,
+                    finish_time,
+                    finish_result,
+                    failure_reason,
+                    qos_class,
+                });
 
                 // TODO: temporary (may be refactored) function for checking running results
                 // self.print_running_info(pod_name);
@@ -372,10 +486,201 @@ impl EventHandler for PersistentStorage {
 
                 // Redirects to scheduler for pod rescheduling. Scheduler knows which pods to
                 // reschedule.
+                self.emit_to_all_schedulers(RemoveNodeFromCache { node_name });
+            }
+            NodeFailed {
+                node_name,
+                failure_type,
+                failure_time,
+            } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(self.ctx, "Failed node {} is already removed", node_name);
+                        return;
+                    }
+                };
+                log_debug!(
+                    self.ctx,
+                    "Node {} failed with {:?} failure at time: {}",
+                    node_name,
+                    failure_type,
+                    failure_time
+                );
+                node.update_condition(
+                    "False".to_string(),
+                    NodeConditionType::NodeReady,
+                    failure_time,
+                );
+                node.update_condition(
+                    "True".to_string(),
+                    NodeConditionType::NodeFailed,
+                    failure_time,
+                );
+                node.add_taint(Taint {
+                    key: NODE_NOT_READY_TAINT_KEY.to_string(),
+                    value: Default::default(),
+                    effect: TaintEffect::NoSchedule,
+                });
+
+                // Scheduler should not place pods to the failed node.
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
+            }
+            UpdateNodeConditionRequest {
+                node_name,
+                condition_type,
+                status,
+                transition_time,
+            } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(
+                            self.ctx,
+                            "Cannot update condition of node {} as it is removed",
+                            node_name
+                        );
+                        return;
+                    }
+                };
+                let taint_key = condition_type.pressure_taint_key();
+                let under_pressure = status == "True";
+                node.update_condition(status, condition_type, transition_time);
+
+                // Scheduler should not place pods to the node under pressure.
+                if let Some(key) = taint_key {
+                    if under_pressure {
+                        node.add_taint(Taint {
+                            key: key.to_string(),
+                            value: Default::default(),
+                            effect: TaintEffect::NoSchedule,
+                        });
+                    } else {
+                        node.remove_taint(key, &TaintEffect::NoSchedule);
+                    }
+                    let node = node.clone();
+                    self.emit_to_all_schedulers(UpdateNodeInCache { node });
+                }
+            }
+            NodeRecovered {
+                node_name,
+                recovery_time,
+            } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(self.ctx, "Recovered node {} is already removed", node_name);
+                        return;
+                    }
+                };
+                node.update_condition(
+                    "True".to_string(),
+                    NodeConditionType::NodeReady,
+                    recovery_time,
+                );
+                node.update_condition(
+                    "False".to_string(),
+                    NodeConditionType::NodeFailed,
+                    recovery_time,
+                );
+                node.remove_taint(NODE_NOT_READY_TAINT_KEY, &TaintEffect::NoSchedule);
+
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
+            }
+            TaintNodeRequest { node_name, taint } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(self.ctx, "Cannot taint node {} as it is removed", node_name);
+                        return;
+                    }
+                };
+                node.add_taint(taint.clone());
+
+                // Scheduler should not place pods which do not tolerate the taint to the node.
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
                 self.ctx.emit(
-                    RemoveNodeFromCache { node_name },
-                    self.scheduler,
-                    self.config.ps_to_sched_network_delay,
+                    TaintNodeResponse { node_name, taint },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            UntaintNodeRequest {
+                node_name,
+                key,
+                effect,
+            } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(
+                            self.ctx,
+                            "Cannot untaint node {} as it is removed",
+                            node_name
+                        );
+                        return;
+                    }
+                };
+                node.remove_taint(&key, &effect);
+
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
+                self.ctx.emit(
+                    UntaintNodeResponse {
+                        node_name,
+                        key,
+                        effect,
+                    },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            CordonNodeRequest { node_name } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(
+                            self.ctx,
+                            "Cannot cordon node {} as it is removed",
+                            node_name
+                        );
+                        return;
+                    }
+                };
+                node.spec.unschedulable = true;
+
+                // Scheduler should not place new pods to the node.
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
+                self.ctx.emit(
+                    CordonNodeResponse { node_name },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            UncordonNodeRequest { node_name } => {
+                let node = match self.storage_data.nodes.get_mut(&node_name) {
+                    Some(node) => node,
+                    None => {
+                        log_debug!(
+                            self.ctx,
+                            "Cannot uncordon node {} as it is removed",
+                            node_name
+                        );
+                        return;
+                    }
+                };
+                node.spec.unschedulable = false;
+
+                let node = node.clone();
+                self.emit_to_all_schedulers(UpdateNodeInCache { node });
+                self.ctx.emit(
+                    UncordonNodeResponse { node_name },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
                 );
             }
             ClusterAutoscalerRequest { request_type } => {
@@ -410,6 +715,23 @@ impl EventHandler for PersistentStorage {
                     self.config.as_to_ps_network_delay,
                 );
             }
+            DeschedulerRequest {} => {
+                let assigned_pods = self
+                    .storage_data
+                    .pods
+                    .values()
+                    .filter(|pod| !pod.status.assigned_node.is_empty())
+                    .cloned()
+                    .collect();
+                self.ctx.emit(
+                    DeschedulerResponse {
+                        nodes: self.storage_data.nodes.values().cloned().collect(),
+                        assigned_pods,
+                    },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
             RemovePodRequest { pod_name } => {
                 if !self.storage_data.pods.contains_key(&pod_name) {
                     // pod has already been removed or finished running - do nothing
@@ -439,13 +761,9 @@ impl EventHandler for PersistentStorage {
                     // Otherwise, pod has not been assigned, meaning that it is probably still in
                     // scheduling queues. So we can directly send request to scheduler to update its
                     // cache as well as response to api server.
-                    self.ctx.emit(
-                        RemovePodFromCache {
-                            pod_name: pod_name.clone(),
-                        },
-                        self.scheduler,
-                        self.config.ps_to_sched_network_delay,
-                    );
+                    self.emit_to_all_schedulers(RemovePodFromCache {
+                        pod_name: pod_name.clone(),
+                    });
                 }
 
                 self.ctx.emit(
@@ -480,11 +798,82 @@ impl EventHandler for PersistentStorage {
 
                 // Pod is removed from a node, so tell scheduler to remove it from cache
                 // (queue or assignments) too.
+                self.emit_to_all_schedulers(RemovePodFromCache { pod_name });
+            }
+            EvictPodRequest { pod_name, reason } => {
+                // Pod could have already finished, been removed or not assigned yet (e.g.
+                // rescheduled due to node removal), then there is nothing to evict.
+                let assigned_node = self
+                    .storage_data
+                    .pods
+                    .get(&pod_name)
+                    .map(|pod| pod.status.assigned_node.clone())
+                    .filter(|assigned_node| !assigned_node.is_empty());
+
                 self.ctx.emit(
-                    RemovePodFromCache { pod_name },
-                    self.scheduler,
-                    self.config.ps_to_sched_network_delay,
+                    EvictPodResponse {
+                        assigned_node,
+                        pod_name,
+                        reason,
+                    },
+                    self.api_server,
+                    self.config.as_to_ps_network_delay,
+                );
+            }
+            PodEvictedFromNode {
+                evicted,
+                eviction_time,
+                pod_name,
+                node_name,
+                reason,
+            } => {
+                if !evicted {
+                    log_debug!(
+                        self.ctx,
+                        "Pod {} was not evicted as it is not running on node {} anymore",
+                        pod_name,
+                        node_name,
+                    );
+                    return;
+                }
+
+                // Remove request may come earlier and remove pod from storage.
+                let pod = match self.storage_data.pods.get(&pod_name) {
+                    Some(pod) if pod.status.assigned_node == node_name => pod.clone(),
+                    _ => return,
+                };
+                self.clean_up_pod_info(&pod);
+
+                let pod = self.storage_data.pods.get_mut(&pod_name).unwrap();
+                pod.status.assigned_node = Default::default();
+                pod.update_condition(
+                    "True".to_string(),
+                    PodConditionType::PodEvicted,
+                    eviction_time,
+                );
+                pod.update_condition(
+                    "False".to_string(),
+                    PodConditionType::PodRunning,
+                    eviction_time,
+                );
+
+                log_debug!(
+                    self.ctx,
+                    "Pod {} evicted from node {} at time: {} due to {:?}",
+                    pod_name,
+                    node_name,
+                    eviction_time,
+                    reason,
                 );
+
+                // Tell scheduler to release pod's resources and put it back to the queue.
+                self.emit_to_all_schedulers(PodEvictedFromNode {
+                    evicted,
+                    eviction_time,
+                    pod_name,
+                    node_name,
+                    reason,
+                });
             }
         })
     }
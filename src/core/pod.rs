@@ -1,8 +1,12 @@
 //! Type definition for Pod primitive in k8s cluster
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::core::affinity::{Affinity, TopologySpreadConstraint};
 use crate::core::common::{ObjectMeta, RuntimeResources, RuntimeResourcesUsageModelConfig};
+use crate::core::node::{Taint, TaintEffect};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 pub struct Resources {
@@ -12,6 +16,52 @@ pub struct Resources {
     pub usage_model_config: Option<RuntimeResourcesUsageModelConfig>,
 }
 
+impl Resources {
+    pub fn qos_class(&self) -> QoSClass {
+        QoSClass::from_resources(&self.requests, &self.limits)
+    }
+}
+
+// https://kubernetes.io/docs/concepts/workloads/pods/pod-qos/
+// Classes are declared from the least to the most protected one, so that pods with lesser class
+// are evicted first.
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum QoSClass {
+    // Pod has neither requests nor limits.
+    #[default]
+    BestEffort,
+    // Pod is neither BestEffort nor Guaranteed.
+    Burstable,
+    // Pod has cpu and ram limits and its requests are equal to them.
+    Guaranteed,
+}
+
+impl QoSClass {
+    /// Zero limit of a resource means that the resource is not limited. Like in k8s, zero request
+    /// of a limited resource defaults to its limit.
+    pub fn from_resources(requests: &RuntimeResources, limits: &RuntimeResources) -> Self {
+        let requests = RuntimeResources {
+            cpu: if requests.cpu == 0 {
+                limits.cpu
+            } else {
+                requests.cpu
+            },
+            ram: if requests.ram == 0 {
+                limits.ram
+            } else {
+                requests.ram
+            },
+        };
+        if requests.cpu == 0 && requests.ram == 0 && limits.cpu == 0 && limits.ram == 0 {
+            QoSClass::BestEffort
+        } else if limits.cpu > 0 && limits.ram > 0 && &requests == limits {
+            QoSClass::Guaranteed
+        } else {
+            QoSClass::Burstable
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 pub struct PodSpec {
     /// Simplified: instead of vector of containers - one container with resources and duration
@@ -19,6 +69,121 @@ pub struct PodSpec {
     /// Custom field to simulate container workload duration.
     /// None is used for infinite duration to simulate long-running services.
     pub running_duration: Option<f64>, // in seconds
+    /// Priority of a pod. Pods with higher priority are popped from the scheduling queue first and
+    /// may preempt pods with lower priority if there is no room for them in a cluster.
+    #[serde(default)]
+    pub priority: i32,
+    /// Whether a pod is allowed to preempt pods with lower priority.
+    #[serde(default)]
+    pub preemption_policy: PreemptionPolicy,
+    /// Tolerations allow a pod to be scheduled to and to keep running on nodes with matching
+    /// taints.
+    #[serde(default)]
+    pub tolerations: Vec<Toleration>,
+    /// Pod can be scheduled only to nodes which have all these labels.
+    #[serde(default)]
+    pub node_selector: HashMap<String, String>,
+    #[serde(default)]
+    pub affinity: Affinity,
+    /// Constraints on how pods are spread among topology domains of a cluster.
+    #[serde(default)]
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    /// Whether a pod is restarted on the same node after its run finishes.
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// Probability of every run of a pod to fail. Failing run stops at `failure_time` if it is
+    /// set, otherwise at uniformly random time within the running duration.
+    #[serde(default)]
+    pub failure_probability: Option<f64>,
+    /// Time since the start of a run when it fails. If failure probability is not set, every run
+    /// fails. Runs which would fail after the running duration succeed.
+    #[serde(default)]
+    pub failure_time: Option<f64>,
+}
+
+// https://kubernetes.io/docs/concepts/workloads/pods/pod-lifecycle/#restart-policy
+// Unlike k8s the default is Never, as most pods in traces are batch jobs.
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum RestartPolicy {
+    // Pod is restarted after every run, so it never finishes.
+    Always,
+    // Pod is restarted only after failed runs.
+    OnFailure,
+    #[default]
+    Never,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum PreemptionPolicy {
+    #[default]
+    PreemptLowerPriority,
+    Never,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum TolerationOperator {
+    // Toleration matches a taint with the same key regardless of its value.
+    Exists,
+    // Toleration matches a taint with the same key and value.
+    #[default]
+    Equal,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Toleration {
+    /// Taint key that the toleration applies to. Empty key with `Exists` operator matches all
+    /// taints.
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    #[serde(default)]
+    pub value: String,
+    /// Taint effect to match. None matches all effects.
+    #[serde(default)]
+    pub effect: Option<TaintEffect>,
+    /// Period of time the pod keeps running on a node after a matching NoExecute taint is added.
+    /// None means the pod is never evicted due to the taint. Ignored for other effects.
+    #[serde(default)]
+    pub toleration_seconds: Option<f64>,
+}
+
+impl Toleration {
+    pub fn tolerates(&self, taint: &Taint) -> bool {
+        if self.effect.is_some() && self.effect.as_ref().unwrap() != &taint.effect {
+            return false;
+        }
+        match self.operator {
+            TolerationOperator::Exists => self.key.is_empty() || self.key == taint.key,
+            TolerationOperator::Equal => self.key == taint.key && self.value == taint.value,
+        }
+    }
+}
+
+/// The reason a running pod is evicted from a node and returned back to the scheduling queues.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum EvictionReason {
+    // Pod is preempted by the scheduler to free place for a pod with higher priority.
+    Preemption,
+    // Pod does not tolerate NoExecute taint of a node or its toleration time is over.
+    NoExecuteTaint,
+    // Pod is evicted by the descheduler strategy with the given name to be placed better.
+    Descheduling { strategy: String },
+    // Pod is evicted from the node being drained after its termination grace period.
+    NodeDrain,
+    // Pod is evicted by the node under memory pressure to reclaim memory.
+    NodePressure,
+}
+
+/// The reason a pod run failed.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum PodFailureReason {
+    // Pod run failed according to its failure probability or failure time.
+    Error,
+    // Pod used more memory than its limit and was killed.
+    OOMKilled,
+    // Node the pod was running on failed.
+    NodeFailure,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -40,6 +205,9 @@ pub enum PodConditionType {
     PodFailed,
     // Pod is manually (with event request) removed from a node, terminating all its running containers.
     PodRemoved,
+    // Pod is evicted from a node and its containers are terminated. Pod goes back to the scheduling
+    // queues to be placed again.
+    PodEvicted,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -57,6 +225,15 @@ pub struct PodStatus {
     pub start_time: f64,
     pub conditions: Vec<PodCondition>,
     pub assigned_node: String,
+    /// The number of restarts of a pod according to its restart policy on all nodes it ran on.
+    #[serde(default)]
+    pub restart_count: u32,
+    /// The reason of a failure if a pod has failed.
+    #[serde(default)]
+    pub failure_reason: Option<PodFailureReason>,
+    /// QoS class derived from resources of a pod when it is created.
+    #[serde(default)]
+    pub qos_class: QoSClass,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -69,6 +246,11 @@ pub struct Pod {
 
 impl Pod {
     pub fn new(name: String, cpu: u32, ram: u64, running_duration: Option<f64>) -> Self {
+        let resources = Resources {
+            limits: RuntimeResources { cpu, ram },
+            requests: RuntimeResources { cpu, ram },
+            usage_model_config: None,
+        };
         Self {
             metadata: ObjectMeta {
                 name: name,
@@ -76,14 +258,22 @@ impl Pod {
                 creation_timestamp: Default::default(),
             },
             spec: PodSpec {
-                resources: Resources {
-                    limits: RuntimeResources { cpu, ram },
-                    requests: RuntimeResources { cpu, ram },
-                    usage_model_config: None,
-                },
+                resources: resources.clone(),
                 running_duration,
+                priority: 0,
+                preemption_policy: Default::default(),
+                tolerations: Default::default(),
+                node_selector: Default::default(),
+                affinity: Default::default(),
+                topology_spread_constraints: Default::default(),
+                restart_policy: Default::default(),
+                failure_probability: None,
+                failure_time: None,
+            },
+            status: PodStatus {
+                qos_class: resources.qos_class(),
+                ..Default::default()
             },
-            status: Default::default(),
         }
     }
 
@@ -121,3 +311,50 @@ impl Pod {
             .find(|c| c.condition_type == condition_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::common::RuntimeResources;
+    use crate::core::pod::QoSClass;
+
+    fn resources(cpu: u32, ram: u64) -> RuntimeResources {
+        RuntimeResources { cpu, ram }
+    }
+
+    #[test]
+    fn test_qos_class_from_resources() {
+        assert_eq!(
+            QoSClass::BestEffort,
+            QoSClass::from_resources(&resources(0, 0), &resources(0, 0))
+        );
+        assert_eq!(
+            QoSClass::Guaranteed,
+            QoSClass::from_resources(&resources(1000, 1024), &resources(1000, 1024))
+        );
+        assert_eq!(
+            QoSClass::Burstable,
+            QoSClass::from_resources(&resources(500, 1024), &resources(1000, 1024))
+        );
+        // Ram is not limited.
+        assert_eq!(
+            QoSClass::Burstable,
+            QoSClass::from_resources(&resources(1000, 1024), &resources(1000, 0))
+        );
+    }
+
+    #[test]
+    fn test_zero_requests_default_to_limits() {
+        assert_eq!(
+            QoSClass::Guaranteed,
+            QoSClass::from_resources(&resources(0, 0), &resources(1000, 1024))
+        );
+        assert_eq!(
+            QoSClass::Guaranteed,
+            QoSClass::from_resources(&resources(1000, 0), &resources(1000, 1024))
+        );
+        assert_eq!(
+            QoSClass::Burstable,
+            QoSClass::from_resources(&resources(0, 0), &resources(1000, 0))
+        );
+    }
+}
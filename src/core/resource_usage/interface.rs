@@ -7,6 +7,11 @@ use serde::{Deserialize, Serialize};
 /// `pod_count` is optional argument which is needed for pod group resource usage model
 pub trait ResourceUsageModel {
     fn current_usage(&mut self, time: f64, pod_count: Option<usize>) -> f64;
+
+    /// Whether usage is a fraction of pod requests rather than an absolute amount of resource.
+    fn is_utilization(&self) -> bool {
+        false
+    }
 }
 
 /// Config describes model name and configuration in arbitrary format which certain implementation of
@@ -98,6 +98,10 @@ impl ResourceUsageModel for PodGroupResourceUsageModel {
         self.last_poll_time = time;
         self.current_utilization(time, pod_count.unwrap())
     }
+
+    fn is_utilization(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,531 @@
+//! Scheduling algorithm which delegates decisions to an external scheduler extender process, so
+//! scheduling policies can be prototyped in other languages without recompiling the simulator.
+//! Payloads are modelled on the kube-scheduler extender API.
+//! https://github.com/kubernetes/design-proposals-archive/blob/main/scheduling/scheduler_extender.md
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::core::node::Node;
+use crate::core::pod::Pod;
+use crate::core::scheduler::framework::{CycleState, EvaluatedNodes, Status};
+use crate::core::scheduler::interface::{ClusterSnapshot, PodSchedulingAlgorithm, ScheduleError};
+use crate::core::scheduler::plugin::pod_fits_node_resources;
+use crate::core::scheduler::preemption::PreemptionCandidate;
+
+/// Either `url_prefix` or `command` should be set. Verbs which are not set are skipped: without
+/// filter all nodes with enough resources are feasible, without prioritize the first feasible node
+/// is taken and without bind the pod is bound by the simulator.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SchedulerExtenderConfig {
+    /// Prefix of extender endpoints, e.g. `http://127.0.0.1:8888/scheduler`. Verbs are appended
+    /// to it and payloads are sent as bodies of POST requests.
+    pub url_prefix: Option<String>,
+    /// Program with arguments which is started as the extender process. Payloads are exchanged
+    /// as JSON lines over its stdin and stdout, a request looks like
+    /// `{"verb": "filter", "args": {...}}` and a response is the payload itself.
+    pub command: Option<Vec<String>>,
+    pub filter_verb: Option<String>,
+    pub prioritize_verb: Option<String>,
+    pub bind_verb: Option<String>,
+}
+
+/// Arguments of filter and prioritize verbs. Nodes have resources left after assignments known to
+/// the scheduler.
+#[derive(Debug, Serialize)]
+pub struct ExtenderArgs<'a> {
+    pub pod: &'a Pod,
+    pub nodes: Vec<&'a Node>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExtenderFilterResult {
+    /// Names of nodes which passed the filter.
+    #[serde(rename = "nodenames")]
+    pub node_names: Vec<String>,
+    /// Map from names of nodes which did not pass the filter to the reasons.
+    #[serde(rename = "failedNodes")]
+    pub failed_nodes: HashMap<String, String>,
+    /// Non-empty if the pod could not be processed at all.
+    pub error: String,
+}
+
+/// Score of a node returned by prioritize verb, higher is better. Nodes missing in the result get
+/// zero score.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct HostPriority {
+    pub host: String,
+    pub score: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExtenderBindingArgs<'a> {
+    #[serde(rename = "podName")]
+    pub pod_name: &'a str,
+    pub node: &'a str,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExtenderBindingResult {
+    pub error: String,
+}
+
+/// Sends a serialized payload for the verb and returns the serialized response.
+trait ExtenderTransport {
+    fn call(&self, verb: &str, args: &str) -> Result<String, String>;
+}
+
+/// Opens a new connection for every request, responses should not use chunked encoding.
+struct HttpTransport {
+    address: String,
+    path_prefix: String,
+}
+
+impl HttpTransport {
+    fn new(url_prefix: &str) -> Result<Self, String> {
+        let url = url_prefix
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("only http urls are supported, got {:?}", url_prefix))?;
+        let (address, path) = match url.find('/') {
+            Some(idx) => url.split_at(idx),
+            None => (url, ""),
+        };
+        Ok(Self {
+            address: address.to_string(),
+            path_prefix: path.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+impl ExtenderTransport for HttpTransport {
+    fn call(&self, verb: &str, args: &str) -> Result<String, String> {
+        let mut stream = TcpStream::connect(&self.address).map_err(|err| err.to_string())?;
+        let request = format!(
+            "POST {}/{} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path_prefix,
+            verb,
+            self.address,
+            args.len(),
+            args
+        );
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|err| err.to_string())?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|err| err.to_string())?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| "malformed http response".to_string())?;
+        let status_line = head.lines().next().unwrap_or_default();
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("extender responded with {:?}", status_line));
+        }
+        Ok(body.to_string())
+    }
+}
+
+/// Extender process which lives as long as the algorithm.
+struct PipeTransport {
+    process: Child,
+    stdin: RefCell<ChildStdin>,
+    stdout: RefCell<BufReader<ChildStdout>>,
+}
+
+impl PipeTransport {
+    fn new(command: &[String]) -> Result<Self, String> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| "extender command is empty".to_string())?;
+        let mut process = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("failed to start extender {:?}: {}", program, err))?;
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+        Ok(Self {
+            process,
+            stdin: RefCell::new(stdin),
+            stdout: RefCell::new(BufReader::new(stdout)),
+        })
+    }
+}
+
+impl ExtenderTransport for PipeTransport {
+    fn call(&self, verb: &str, args: &str) -> Result<String, String> {
+        let request = format!(
+            "{{\"verb\":{},\"args\":{}}}\n",
+            serde_json::to_string(verb).unwrap(),
+            args
+        );
+        let mut stdin = self.stdin.borrow_mut();
+        stdin
+            .write_all(request.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|err| err.to_string())?;
+
+        let mut response = String::new();
+        let read = self
+            .stdout
+            .borrow_mut()
+            .read_line(&mut response)
+            .map_err(|err| err.to_string())?;
+        if read == 0 {
+            return Err("extender process closed its stdout".to_string());
+        }
+        Ok(response)
+    }
+}
+
+impl Drop for PipeTransport {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// Nodes where the pod does not fit by resources are never sent to the extender, like in-tree
+/// filters run before extenders in kube-scheduler. Preemption is not supported.
+pub struct ExtenderScheduler {
+    config: SchedulerExtenderConfig,
+    transport: Box<dyn ExtenderTransport>,
+}
+
+impl ExtenderScheduler {
+    pub fn new(config: SchedulerExtenderConfig) -> Result<Self, String> {
+        let transport: Box<dyn ExtenderTransport> = match (&config.url_prefix, &config.command) {
+            (Some(url_prefix), None) => Box::new(HttpTransport::new(url_prefix)?),
+            (None, Some(command)) => Box::new(PipeTransport::new(command)?),
+            _ => return Err("exactly one of url_prefix and command should be set".to_string()),
+        };
+        Ok(Self { config, transport })
+    }
+
+    fn call<T: DeserializeOwned>(&self, verb: &str, args: &impl Serialize) -> Result<T, String> {
+        let args = serde_json::to_string(args).unwrap();
+        let response = self.transport.call(verb, &args)?;
+        serde_json::from_str(&response)
+            .map_err(|err| format!("invalid response to {:?}: {}", verb, err))
+    }
+
+    fn filter<'a>(&self, pod: &'a Pod, nodes: Vec<&'a Node>) -> Result<Vec<&'a Node>, String> {
+        let filter_verb = match &self.config.filter_verb {
+            Some(filter_verb) => filter_verb,
+            None => return Ok(nodes),
+        };
+        let result: ExtenderFilterResult = self.call(
+            filter_verb,
+            &ExtenderArgs {
+                pod,
+                nodes: nodes.clone(),
+            },
+        )?;
+        if !result.error.is_empty() {
+            return Err(result.error);
+        }
+        // Unknown names are ignored, so the pod is never assigned to a node it does not fit.
+        Ok(nodes
+            .into_iter()
+            .filter(|node| result.node_names.contains(&node.metadata.name))
+            .collect())
+    }
+
+    fn select_node(&self, pod: &Pod, feasible_nodes: Vec<&Node>) -> Result<String, String> {
+        let prioritize_verb = match &self.config.prioritize_verb {
+            Some(prioritize_verb) => prioritize_verb,
+            None => return Ok(feasible_nodes[0].metadata.name.clone()),
+        };
+        let priorities: Vec<HostPriority> = self.call(
+            prioritize_verb,
+            &ExtenderArgs {
+                pod,
+                nodes: feasible_nodes.clone(),
+            },
+        )?;
+        let scores: HashMap<&str, i64> = priorities
+            .iter()
+            .map(|priority| (priority.host.as_str(), priority.score))
+            .collect();
+
+        // The first node with the highest score wins.
+        let mut best_node = feasible_nodes[0];
+        let mut best_score = *scores.get(best_node.metadata.name.as_str()).unwrap_or(&0);
+        for node in feasible_nodes.into_iter().skip(1) {
+            let score = *scores.get(node.metadata.name.as_str()).unwrap_or(&0);
+            if score > best_score {
+                (best_node, best_score) = (node, score);
+            }
+        }
+        Ok(best_node.metadata.name.clone())
+    }
+}
+
+impl PodSchedulingAlgorithm for ExtenderScheduler {
+    fn schedule_one(
+        &self,
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
+    ) -> Result<String, ScheduleError> {
+        let requested_resources = &pod.spec.resources.requests;
+        if requested_resources.cpu == 0 && requested_resources.ram == 0 {
+            return Err(ScheduleError::RequestedResourcesAreZeros);
+        }
+        if snapshot.nodes.is_empty() {
+            return Err(ScheduleError::NoNodesInCluster);
+        }
+
+        let fitting_nodes: Vec<&Node> = snapshot
+            .nodes
+            .values()
+            .filter(|node| pod_fits_node_resources(pod, node))
+            .collect();
+        let feasible_nodes = if fitting_nodes.is_empty() {
+            fitting_nodes
+        } else {
+            self.filter(pod, fitting_nodes)
+                .map_err(ScheduleError::ExtenderError)?
+        };
+        state.set_evaluated_nodes(EvaluatedNodes {
+            filtered: snapshot.nodes.len(),
+            scored: feasible_nodes.len(),
+        });
+        if feasible_nodes.is_empty() {
+            return Err(ScheduleError::NoSufficientResources);
+        }
+
+        self.select_node(pod, feasible_nodes)
+            .map_err(ScheduleError::ExtenderError)
+    }
+
+    fn post_filter(
+        &self,
+        _pod: &Pod,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Option<PreemptionCandidate> {
+        None
+    }
+
+    fn bind(&self, pod: &Pod, node_name: &str, _state: &CycleState) -> Status {
+        let bind_verb = match &self.config.bind_verb {
+            Some(bind_verb) => bind_verb,
+            None => return Status::Success,
+        };
+        let result: Result<ExtenderBindingResult, String> = self.call(
+            bind_verb,
+            &ExtenderBindingArgs {
+                pod_name: &pod.metadata.name,
+                node: node_name,
+            },
+        );
+        match result {
+            Ok(result) if result.error.is_empty() => Status::Success,
+            Ok(result) => Status::Error(result.error),
+            Err(err) => Status::Error(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::core::node::Node;
+    use crate::core::pod::Pod;
+    use crate::core::scheduler::extender::{ExtenderScheduler, SchedulerExtenderConfig};
+    use crate::core::scheduler::framework::{CycleState, Status};
+    use crate::core::scheduler::interface::{
+        ClusterSnapshot, PodSchedulingAlgorithm, ScheduleError,
+    };
+
+    fn nodes() -> BTreeMap<String, Node> {
+        [
+            Node::new("node_1".to_string(), 16000, 17179869184),
+            Node::new("node_2".to_string(), 16000, 17179869184),
+            Node::new("node_3".to_string(), 16000, 17179869184),
+            Node::new("small_node".to_string(), 1000, 17179869184),
+        ]
+        .into_iter()
+        .map(|node| (node.metadata.name.clone(), node))
+        .collect()
+    }
+
+    fn schedule(
+        scheduler: &ExtenderScheduler,
+        pod: &Pod,
+        nodes: &BTreeMap<String, Node>,
+    ) -> Result<String, ScheduleError> {
+        let (pods, assignments, pending_evictions) =
+            (BTreeMap::new(), HashMap::new(), HashSet::new());
+        let snapshot = ClusterSnapshot {
+            nodes,
+            pods: &pods,
+            assignments: &assignments,
+            pending_evictions: &pending_evictions,
+        };
+        scheduler.schedule_one(pod, &snapshot, &mut CycleState::default())
+    }
+
+    // Stand-in extender: filters out node_1, prefers node_3 and accepts all bindings. Paths of
+    // requests and names of nodes sent to filter are reported to the channel.
+    fn start_http_extender(
+        requests: usize,
+    ) -> (String, mpsc::Receiver<(String, BTreeSet<String>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 4096];
+                let body = loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let content_length: usize = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("Content-Length: "))
+                            .unwrap()
+                            .parse()
+                            .unwrap();
+                        if body.len() >= content_length {
+                            break (head.to_string(), body.to_string());
+                        }
+                    }
+                };
+                let (head, body) = body;
+                let path = head.split_whitespace().nth(1).unwrap().to_string();
+                let args: serde_json::Value = serde_json::from_str(&body).unwrap();
+                let node_names: BTreeSet<String> = args["nodes"]
+                    .as_array()
+                    .map(|nodes| {
+                        nodes
+                            .iter()
+                            .map(|node| node["metadata"]["name"].as_str().unwrap().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let response = match path.as_str() {
+                    "/scheduler/filter" => serde_json::json!({
+                        "nodenames": node_names.iter().filter(|name| *name != "node_1").collect::<Vec<_>>(),
+                        "failedNodes": {"node_1": "rejected by policy"},
+                    }),
+                    "/scheduler/prioritize" => serde_json::json!(node_names
+                        .iter()
+                        .map(|name| serde_json::json!({"host": name, "score": if name == "node_3" { 10 } else { 1 }}))
+                        .collect::<Vec<_>>()),
+                    _ => serde_json::json!({}),
+                }
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+                sender.send((path, node_names)).unwrap();
+            }
+        });
+        (format!("http://{}/scheduler", address), receiver)
+    }
+
+    #[test]
+    fn test_http_extender() {
+        let (url_prefix, requests) = start_http_extender(3);
+        let scheduler = ExtenderScheduler::new(SchedulerExtenderConfig {
+            url_prefix: Some(url_prefix),
+            command: None,
+            filter_verb: Some("filter".to_string()),
+            prioritize_verb: Some("prioritize".to_string()),
+            bind_verb: Some("bind".to_string()),
+        })
+        .unwrap();
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+
+        assert_eq!(
+            Ok("node_3".to_string()),
+            schedule(&scheduler, &pod, &nodes())
+        );
+        assert_eq!(
+            Status::Success,
+            scheduler.bind(&pod, "node_3", &CycleState::default())
+        );
+
+        let (path, node_names) = requests.recv().unwrap();
+        assert_eq!("/scheduler/filter", path);
+        // The pod does not fit into small_node, so it is not sent to the extender.
+        assert_eq!(
+            BTreeSet::from(["node_1", "node_2", "node_3"].map(String::from)),
+            node_names
+        );
+        let (path, node_names) = requests.recv().unwrap();
+        assert_eq!("/scheduler/prioritize", path);
+        assert_eq!(
+            BTreeSet::from(["node_2", "node_3"].map(String::from)),
+            node_names
+        );
+        assert_eq!("/scheduler/bind", requests.recv().unwrap().0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_extender() {
+        let pipe_scheduler = |command: &str| {
+            let mut config: SchedulerExtenderConfig =
+                serde_yaml::from_str(&format!("command: [sh, -c, '{}']", command)).unwrap();
+            config.filter_verb = Some("filter".to_string());
+            ExtenderScheduler::new(config).unwrap()
+        };
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+        let big_pod = Pod::new("pod_2".to_string(), 32000, 8589934592, Some(5.0));
+
+        // Stand-in extender which accepts only node_2 whatever it is asked.
+        let scheduler =
+            pipe_scheduler(r#"while read request; do echo "{\"nodenames\": [\"node_2\"]}"; done"#);
+        assert_eq!(
+            Ok("node_2".to_string()),
+            schedule(&scheduler, &pod, &nodes())
+        );
+        // Extender is not asked when the pod does not fit anywhere.
+        assert_eq!(
+            Err(ScheduleError::NoSufficientResources),
+            schedule(&scheduler, &big_pod, &nodes())
+        );
+        assert_eq!(
+            Ok("node_2".to_string()),
+            schedule(&scheduler, &pod, &nodes())
+        );
+
+        let broken_scheduler = pipe_scheduler("read request; echo not json");
+        assert!(matches!(
+            schedule(&broken_scheduler, &pod, &nodes()),
+            Err(ScheduleError::ExtenderError(_))
+        ));
+        // Extender process has exited.
+        assert!(matches!(
+            schedule(&broken_scheduler, &pod, &nodes()),
+            Err(ScheduleError::ExtenderError(_))
+        ));
+    }
+}
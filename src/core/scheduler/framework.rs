@@ -0,0 +1,231 @@
+//! Types shared by plugins of scheduling framework extension points.
+//! https://kubernetes.io/docs/concepts/scheduling-eviction/scheduling-framework/
+
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use dslab_core::event::EventId;
+
+use crate::core::node::Node;
+use crate::core::pod::Pod;
+use crate::core::scheduler::queue::QueuedPodInfo;
+
+/// Result of running a plugin at some extension point.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Success,
+    /// Pod cannot be scheduled or bound at the moment, the reason is used for logging.
+    Unschedulable(String),
+    /// Returned by permit plugins to delay binding of a pod for at most the given number of
+    /// seconds until the pod is allowed or rejected.
+    Wait(f64),
+    /// Returned by bind plugins which do not handle a pod, so the next bind plugin is tried.
+    Skip,
+    Error(String),
+}
+
+impl Status {
+    pub fn is_success(&self) -> bool {
+        *self == Status::Success
+    }
+}
+
+/// Result of running all permit plugins of a profile for a pod.
+#[derive(Debug, PartialEq)]
+pub enum PermitDecision {
+    Allow,
+    /// Pod waits until all the plugins allow it or the timeout in seconds expires.
+    Wait {
+        plugins: BTreeSet<String>,
+        timeout: f64,
+    },
+    Reject(String),
+}
+
+/// State of a single scheduling attempt of a pod. It is created when the pod is popped from the
+/// scheduling queue and lives until the pod is bound or returned to the queue. Plugins use it to
+/// pass data computed at earlier extension points to later ones, e.g. from PreFilter to Filter.
+#[derive(Default)]
+pub struct CycleState {
+    data: HashMap<String, Box<dyn Any>>,
+    unschedulable_plugins: BTreeSet<String>,
+    evaluated_nodes: Option<EvaluatedNodes>,
+}
+
+/// Numbers of nodes processed by the scheduling algorithm in a scheduling attempt. They are used
+/// by scheduling time models.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EvaluatedNodes {
+    /// Nodes checked by filter plugins.
+    pub filtered: usize,
+    /// Feasible nodes found by filter plugins and scored.
+    pub scored: usize,
+}
+
+impl CycleState {
+    pub fn write<T: Any>(&mut self, key: &str, value: T) {
+        self.data.insert(key.to_string(), Box::new(value));
+    }
+
+    /// Returns None if there is no value for the key or the value has another type.
+    pub fn read<T: Any>(&self, key: &str) -> Option<&T> {
+        self.data.get(key)?.downcast_ref::<T>()
+    }
+
+    /// Records that the plugin rejected the pod at PreFilter or some nodes at Filter.
+    pub fn add_unschedulable_plugin(&mut self, plugin_name: &str) {
+        self.unschedulable_plugins.insert(plugin_name.to_string());
+    }
+
+    pub fn unschedulable_plugins(&self) -> &BTreeSet<String> {
+        &self.unschedulable_plugins
+    }
+
+    pub fn set_evaluated_nodes(&mut self, evaluated_nodes: EvaluatedNodes) {
+        self.evaluated_nodes = Some(evaluated_nodes);
+    }
+
+    /// Returns None if the algorithm does not report evaluated nodes or did not reach filters.
+    pub fn evaluated_nodes(&self) -> Option<EvaluatedNodes> {
+        self.evaluated_nodes
+    }
+}
+
+/// Cluster events which may make unschedulable pods schedulable.
+pub enum ClusterEvent<'a> {
+    NodeAdded(&'a Node),
+    /// Labels, taints or conditions of the node changed.
+    NodeUpdated(&'a Node),
+    /// Pod bound by another scheduler is assigned to the node, e.g. it may satisfy inter-pod
+    /// affinity of unschedulable pods.
+    AssignedPodAdded {
+        pod: &'a Pod,
+        node: &'a Node,
+    },
+    /// Pod assigned to the node finished, was evicted or removed, so its resources are released.
+    AssignedPodDeleted {
+        pod: &'a Pod,
+        node: &'a Node,
+    },
+}
+
+/// Decision of a plugin whether an unschedulable pod is worth retrying after a cluster event.
+#[derive(Debug, PartialEq)]
+pub enum QueueingHint {
+    Queue,
+    Skip,
+}
+
+impl QueueingHint {
+    pub fn queue_if(condition: bool) -> Self {
+        if condition {
+            QueueingHint::Queue
+        } else {
+            QueueingHint::Skip
+        }
+    }
+}
+
+/// Pod which is reserved on a node, but waits for permit plugins before binding.
+pub struct WaitingPod {
+    pub node_name: String,
+    /// Names of permit plugins which asked to wait and have not allowed the pod yet.
+    pub pending_plugins: BTreeSet<String>,
+    /// Set when some plugin rejects the pod.
+    pub rejection_reason: Option<String>,
+    /// Simulation time when the pod started waiting.
+    pub wait_start_time: f64,
+    /// Absolute simulation time when the pod is rejected if it is not allowed by all plugins.
+    pub deadline: f64,
+    pub(crate) timeout_event_id: EventId,
+    pub(crate) state: CycleState,
+    pub(crate) queued_pod_info: QueuedPodInfo,
+    /// Simulated duration of the scheduling algorithm run which assigned the node.
+    pub(crate) pod_schedule_time: f64,
+}
+
+impl WaitingPod {
+    pub fn is_allowed(&self) -> bool {
+        self.rejection_reason.is_none() && self.pending_plugins.is_empty()
+    }
+}
+
+/// Pods waiting at permit extension point. Permit plugins may allow or reject other waiting pods,
+/// e.g. when the last pod of a group arrives.
+#[derive(Default)]
+pub struct WaitingPods {
+    pods: BTreeMap<String, WaitingPod>,
+}
+
+impl WaitingPods {
+    pub fn get(&self, pod_name: &str) -> Option<&WaitingPod> {
+        self.pods.get(pod_name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &WaitingPod)> {
+        self.pods.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pods.is_empty()
+    }
+
+    /// Removes the plugin from the plugins the pod waits for.
+    pub fn allow(&mut self, pod_name: &str, plugin_name: &str) {
+        if let Some(waiting_pod) = self.pods.get_mut(pod_name) {
+            waiting_pod.pending_plugins.remove(plugin_name);
+        }
+    }
+
+    pub fn reject(&mut self, pod_name: &str, reason: String) {
+        if let Some(waiting_pod) = self.pods.get_mut(pod_name) {
+            waiting_pod.rejection_reason = Some(reason);
+        }
+    }
+
+    pub(crate) fn insert(&mut self, pod_name: String, waiting_pod: WaitingPod) {
+        self.pods.insert(pod_name, waiting_pod);
+    }
+
+    pub(crate) fn remove(&mut self, pod_name: &str) -> Option<WaitingPod> {
+        self.pods.remove(pod_name)
+    }
+
+    /// Removes and returns pods which are allowed or rejected since the last call.
+    pub(crate) fn take_resolved(&mut self) -> Vec<(String, WaitingPod)> {
+        let resolved: Vec<String> = self
+            .pods
+            .iter()
+            .filter(|(_, waiting_pod)| {
+                waiting_pod.is_allowed() || waiting_pod.rejection_reason.is_some()
+            })
+            .map(|(pod_name, _)| pod_name.clone())
+            .collect();
+        resolved
+            .into_iter()
+            .map(|pod_name| {
+                let waiting_pod = self.pods.remove(&pod_name).unwrap();
+                (pod_name, waiting_pod)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::scheduler::framework::CycleState;
+
+    #[test]
+    fn test_cycle_state() {
+        let mut state = CycleState::default();
+        state.write("counts", vec![1, 2, 3]);
+
+        assert_eq!(Some(&vec![1, 2, 3]), state.read::<Vec<i32>>("counts"));
+        assert_eq!(None, state.read::<String>("counts"));
+        assert_eq!(None, state.read::<Vec<i32>>("other"));
+    }
+}
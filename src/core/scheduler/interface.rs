@@ -1,23 +1,128 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crate::core::node::Node;
 use crate::core::pod::Pod;
+use crate::core::scheduler::framework::{
+    ClusterEvent, CycleState, PermitDecision, QueueingHint, Status, WaitingPods,
+};
+use crate::core::scheduler::preemption::{select_preemption_candidate, PreemptionCandidate};
 
 #[derive(Debug, PartialEq)]
 pub enum ScheduleError {
     NoNodesInCluster,
     NoSufficientResources,
     RequestedResourcesAreZeros,
+    // Pod requests a scheduler name which has no profile in scheduler config.
+    SchedulerProfileNotFound,
+    // Some PreFilter plugin found that the pod cannot be scheduled at the moment.
+    RejectedByPreFilter(String),
+    // Scheduler extender failed to process the pod.
+    ExtenderError(String),
+}
+
+// Read-only view of the scheduler cache at the moment of scheduling a pod.
+pub struct ClusterSnapshot<'a> {
+    // Map of node names and nodes themselves with resources left after assignments.
+    pub nodes: &'a BTreeMap<String, Node>,
+    // Map of pod names and pods known to scheduler, both assigned and waiting in queues.
+    pub pods: &'a BTreeMap<String, Pod>,
+    // Map from node name to names of pods assigned to that node.
+    pub assignments: &'a HashMap<String, BTreeSet<String>>,
+    // Names of pods which are requested to be evicted, but are not evicted yet.
+    pub pending_evictions: &'a HashSet<String>,
 }
 
 // Trait which should implement any scheduler in kubernetriks framework.
+// Besides `schedule_one` the scheduler component calls other scheduling framework extension points
+// of the algorithm for every scheduled pod. Their default implementations do nothing, except
+// `post_filter` which runs default preemption.
 pub trait PodSchedulingAlgorithm {
     // A method to assign a node on which the pod will be executed.
-    // `nodes` is a hash map of node names and nodes themselves.
+    // `snapshot` describes current nodes and pods assigned to them.
+    // `state` is created for every scheduling attempt of the pod and is passed to all extension
+    // points of that attempt.
     // Returns Result consisting of name of assigned node or scheduling error.
     fn schedule_one(
         &self,
         pod: &Pod,
-        nodes: &BTreeMap<String, Node>,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
     ) -> Result<String, ScheduleError>;
+
+    // Is called when the pod does not fit into any node. Returns a node where the pod fits after
+    // evicting the victims.
+    fn post_filter(
+        &self,
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Option<PreemptionCandidate> {
+        select_preemption_candidate(
+            pod,
+            snapshot.nodes,
+            snapshot.pods,
+            snapshot.assignments,
+            snapshot.pending_evictions,
+        )
+    }
+
+    // Is called after node resources are reserved for the pod in scheduler cache.
+    fn reserve(&self, _pod: &Pod, _node_name: &str, _state: &mut CycleState) -> Status {
+        Status::Success
+    }
+
+    // Is called when reserve, permit or binding of the pod fails to roll back reserve.
+    fn unreserve(
+        &self,
+        _pod: &Pod,
+        _node_name: &str,
+        _snapshot: &ClusterSnapshot,
+        _state: &mut CycleState,
+        _waiting_pods: &mut WaitingPods,
+    ) {
+    }
+
+    fn permit(
+        &self,
+        _pod: &Pod,
+        _node_name: &str,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+        _waiting_pods: &mut WaitingPods,
+    ) -> PermitDecision {
+        PermitDecision::Allow
+    }
+
+    fn pre_bind(&self, _pod: &Pod, _node_name: &str, _state: &CycleState) -> Status {
+        Status::Success
+    }
+
+    fn bind(&self, _pod: &Pod, _node_name: &str, _state: &CycleState) -> Status {
+        Status::Success
+    }
+
+    // Decides whether the cluster event may make the pod schedulable. `unschedulable_plugins` are
+    // plugins which rejected the pod in its last scheduling attempt, see
+    // `CycleState::add_unschedulable_plugin`.
+    fn queueing_hint(
+        &self,
+        _pod: &Pod,
+        _unschedulable_plugins: &BTreeSet<String>,
+        _event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        QueueingHint::Queue
+    }
+}
+
+// Trait of algorithms which place all pods popped from the active queue in a scheduling cycle at
+// once, e.g. by solving an ILP. Unlike `PodSchedulingAlgorithm` they are not given other scheduling
+// framework extension points and do not preempt pods.
+pub trait BatchPodSchedulingAlgorithm {
+    // `pods` are ordered as in the active queue, i.e. by priority and then by queue time.
+    // `snapshot` has nodes with resources left before placing any pod of the batch.
+    // Returns map from pod name to the name of assigned node. Pods missing in the map stay
+    // unscheduled, as well as pods which do not fit the node together with pods of the batch
+    // assigned there before them.
+    fn schedule_batch(&self, pods: &[&Pod], snapshot: &ClusterSnapshot) -> HashMap<String, String>;
 }
@@ -1,13 +1,37 @@
 //! Implementation of kube-scheduler component which is responsible for scheduling pods for nodes.
 
 use std::borrow::Borrow;
-use std::collections::{BTreeMap, HashMap};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
 
 use crate::core::node::Node;
 use crate::core::pod::Pod;
 
-use crate::core::scheduler::interface::{PodSchedulingAlgorithm, ScheduleError};
-use crate::core::scheduler::plugin::{PluginType, PLUGIN_REGISTRY};
+use crate::core::scheduler::framework::{
+    ClusterEvent, CycleState, EvaluatedNodes, PermitDecision, QueueingHint, Status, WaitingPods,
+};
+use crate::core::scheduler::interface::{ClusterSnapshot, PodSchedulingAlgorithm, ScheduleError};
+use crate::core::scheduler::plugin::{
+    find_bind_plugin, find_filter_plugin, find_permit_plugin, find_post_filter_plugin,
+    find_pre_bind_plugin, find_pre_filter_plugin, find_reserve_plugin, find_score_plugin_factory,
+    ScorePlugin, PLUGIN_REGISTRY,
+};
+use crate::core::scheduler::preemption::PreemptionCandidate;
+use crate::core::scheduler::queue::{
+    DEFAULT_POD_INITIAL_BACKOFF_SECONDS, DEFAULT_POD_MAX_BACKOFF_SECONDS,
+};
+
+pub const DEFAULT_SCHEDULER_NAME: &str = "default_scheduler";
+
+// Clusters smaller than that are always filtered entirely.
+pub const MIN_FEASIBLE_NODES_TO_FIND: usize = 100;
+// Lower bound of the adaptive percentage of nodes to score.
+pub const MIN_FEASIBLE_NODES_PERCENTAGE_TO_FIND: usize = 5;
+// Adaptive percentage of nodes to score is this value minus one percent per 125 nodes.
+const BASE_PERCENTAGE_OF_NODES_TO_SCORE: usize = 50;
 
 pub struct KubeScheduler {
     // Profiles are scheduling profiles that kube-scheduler supports. Pods can
@@ -15,61 +39,456 @@ pub struct KubeScheduler {
     // scheduler name in labels. Pods that don't specify any scheduler name are scheduled
     // with the "default-scheduler" profile, if present here.
     pub config: KubeSchedulerConfig,
+    // Score plugins of each profile created from their args, in the same order as in the profile.
+    score_plugins: HashMap<String, Vec<Box<dyn ScorePlugin>>>,
+    // Index of the node to start filtering from in the next scheduling attempt, so that nodes are
+    // evaluated in round robin when only a part of them is enough.
+    next_start_node_index: Cell<usize>,
 }
 
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct KubeSchedulerConfig {
-    // Map from scheduler name to scheduler profile
+    // Map from scheduler name to scheduler profile. In config profiles are listed as a sequence.
+    #[serde(deserialize_with = "deserialize_profiles")]
     pub profiles: HashMap<String, KubeSchedulerProfile>,
+    // Backoff of a pod after the first failed scheduling attempt, it doubles with every next
+    // failed attempt up to the max backoff.
+    #[serde(default = "default_pod_initial_backoff_seconds")]
+    pub pod_initial_backoff_seconds: f64,
+    #[serde(default = "default_pod_max_backoff_seconds")]
+    pub pod_max_backoff_seconds: f64,
+}
+
+fn default_pod_initial_backoff_seconds() -> f64 {
+    DEFAULT_POD_INITIAL_BACKOFF_SECONDS
+}
+
+fn default_pod_max_backoff_seconds() -> f64 {
+    DEFAULT_POD_MAX_BACKOFF_SECONDS
+}
+
+impl Default for KubeSchedulerConfig {
+    fn default() -> Self {
+        default_kube_scheduler_config()
+    }
 }
 
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct KubeSchedulerProfile {
     pub scheduler_name: String,
     pub plugins: Plugins,
+    // Percentage of all nodes which is enough to find feasible for a pod before scoring them, the
+    // same as percentageOfNodesToScore in kube-scheduler. If not set or 0, the percentage is
+    // computed from the cluster size.
+    #[serde(default)]
+    pub percentage_of_nodes_to_score: Option<u32>,
 }
 
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Plugins {
     // Each extension point is a list of enabled plugins which are registered globally in plugin registry.
+    // Plugins are run in the listed order.
+    #[serde(default)]
+    pub pre_filter: Vec<Plugin>,
+    #[serde(default)]
     pub filter: Vec<Plugin>,
+    #[serde(default)]
+    pub post_filter: Vec<Plugin>,
+    #[serde(default)]
     pub score: Vec<Plugin>,
+    #[serde(default)]
+    pub reserve: Vec<Plugin>,
+    #[serde(default)]
+    pub permit: Vec<Plugin>,
+    #[serde(default)]
+    pub pre_bind: Vec<Plugin>,
+    #[serde(default)]
+    pub bind: Vec<Plugin>,
+}
+
+impl Plugins {
+    // Lists enabled plugins of each extension point in the order they are run.
+    fn extension_points(&self) -> [(&'static str, &Vec<Plugin>); 8] {
+        [
+            ("pre_filter", &self.pre_filter),
+            ("filter", &self.filter),
+            ("post_filter", &self.post_filter),
+            ("score", &self.score),
+            ("reserve", &self.reserve),
+            ("permit", &self.permit),
+            ("pre_bind", &self.pre_bind),
+            ("bind", &self.bind),
+        ]
+    }
+}
+
+// Name of the scheduler profile requested by the pod in its labels.
+pub fn pod_scheduler_name(pod: &Pod) -> &str {
+    pod.metadata
+        .labels
+        .get("scheduler_name")
+        .map_or(DEFAULT_SCHEDULER_NAME, |name| name.as_str())
+}
+
+fn plugin_implements(plugin_name: &str, extension_point: &str) -> bool {
+    match extension_point {
+        "pre_filter" => find_pre_filter_plugin(plugin_name).is_some(),
+        "filter" => find_filter_plugin(plugin_name).is_some(),
+        "post_filter" => find_post_filter_plugin(plugin_name).is_some(),
+        "score" => find_score_plugin_factory(plugin_name).is_some(),
+        "reserve" => find_reserve_plugin(plugin_name).is_some(),
+        "permit" => find_permit_plugin(plugin_name).is_some(),
+        "pre_bind" => find_pre_bind_plugin(plugin_name).is_some(),
+        "bind" => find_bind_plugin(plugin_name).is_some(),
+        _ => false,
+    }
 }
 
 // Plugin specifies a plugin name and its weight when applicable.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Plugin {
     pub name: String,
-    // Weight is used only for Score plugins.
+    // Weight is used only for Score plugins. Score plugin without weight has weight 1.0.
+    #[serde(default)]
     pub weight: Option<f64>,
+    // Arguments of configurable plugins, their format depends on the plugin.
+    #[serde(default)]
+    pub args: Option<serde_yaml::Value>,
+}
+
+fn deserialize_profiles<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, KubeSchedulerProfile>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let profiles = Vec::<KubeSchedulerProfile>::deserialize(deserializer)?;
+    let mut profiles_by_name: HashMap<String, KubeSchedulerProfile> = Default::default();
+    for profile in profiles.into_iter() {
+        let scheduler_name = profile.scheduler_name.clone();
+        if profiles_by_name
+            .insert(scheduler_name.clone(), profile)
+            .is_some()
+        {
+            return Err(serde::de::Error::custom(format!(
+                "duplicate scheduler profile {:?}",
+                scheduler_name
+            )));
+        }
+    }
+    Ok(profiles_by_name)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KubeSchedulerConfigError {
+    NoProfiles,
+    InvalidBackoff {
+        initial_backoff: f64,
+        max_backoff: f64,
+    },
+    UnknownPlugin {
+        scheduler_name: String,
+        plugin_name: String,
+    },
+    WrongExtensionPoint {
+        scheduler_name: String,
+        plugin_name: String,
+        extension_point: String,
+    },
+    InvalidWeight {
+        scheduler_name: String,
+        plugin_name: String,
+        weight: f64,
+    },
+    InvalidArgs {
+        scheduler_name: String,
+        plugin_name: String,
+        reason: String,
+    },
+    InvalidPercentageOfNodesToScore {
+        scheduler_name: String,
+        percentage: u32,
+    },
+}
+
+impl fmt::Display for KubeSchedulerConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KubeSchedulerConfigError::NoProfiles => {
+                write!(f, "scheduler config has no profiles")
+            }
+            KubeSchedulerConfigError::InvalidBackoff {
+                initial_backoff,
+                max_backoff,
+            } => write!(
+                f,
+                "invalid pod backoff: initial {} and max {} must be positive and initial must not exceed max",
+                initial_backoff, max_backoff
+            ),
+            KubeSchedulerConfigError::UnknownPlugin {
+                scheduler_name,
+                plugin_name,
+            } => write!(
+                f,
+                "profile {:?}: plugin {:?} is not registered",
+                scheduler_name, plugin_name
+            ),
+            KubeSchedulerConfigError::WrongExtensionPoint {
+                scheduler_name,
+                plugin_name,
+                extension_point,
+            } => write!(
+                f,
+                "profile {:?}: plugin {:?} is enabled at {} extension point, but does not implement it",
+                scheduler_name, plugin_name, extension_point
+            ),
+            KubeSchedulerConfigError::InvalidWeight {
+                scheduler_name,
+                plugin_name,
+                weight,
+            } => write!(
+                f,
+                "profile {:?}: plugin {:?} has invalid weight {}, must be positive",
+                scheduler_name, plugin_name, weight
+            ),
+            KubeSchedulerConfigError::InvalidPercentageOfNodesToScore {
+                scheduler_name,
+                percentage,
+            } => write!(
+                f,
+                "profile {:?}: percentage of nodes to score {} is greater than 100",
+                scheduler_name, percentage
+            ),
+            KubeSchedulerConfigError::InvalidArgs {
+                scheduler_name,
+                plugin_name,
+                reason,
+            } => write!(
+                f,
+                "profile {:?}: plugin {:?} has invalid args: {}",
+                scheduler_name, plugin_name, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KubeSchedulerConfigError {}
+
+impl KubeSchedulerConfig {
+    /// Checks that every enabled plugin is registered in `PLUGIN_REGISTRY` and implements the
+    /// extension point it is enabled at.
+    pub fn validate(&self) -> Result<(), KubeSchedulerConfigError> {
+        if self.profiles.is_empty() {
+            return Err(KubeSchedulerConfigError::NoProfiles);
+        }
+        if !(self.pod_initial_backoff_seconds > 0.0
+            && self.pod_initial_backoff_seconds <= self.pod_max_backoff_seconds)
+        {
+            return Err(KubeSchedulerConfigError::InvalidBackoff {
+                initial_backoff: self.pod_initial_backoff_seconds,
+                max_backoff: self.pod_max_backoff_seconds,
+            });
+        }
+        // Profiles are checked in the order of their names, so that the reported error does not
+        // depend on the order of the map.
+        let mut profiles: Vec<(&String, &KubeSchedulerProfile)> = self.profiles.iter().collect();
+        profiles.sort_by_key(|(scheduler_name, _)| *scheduler_name);
+        for (scheduler_name, profile) in profiles.into_iter() {
+            if let Some(percentage) = profile.percentage_of_nodes_to_score {
+                if percentage > 100 {
+                    return Err(KubeSchedulerConfigError::InvalidPercentageOfNodesToScore {
+                        scheduler_name: scheduler_name.clone(),
+                        percentage,
+                    });
+                }
+            }
+            let unknown_plugin = |plugin: &Plugin| {
+                (!PLUGIN_REGISTRY.contains_key(plugin.name.as_str())).then(|| {
+                    KubeSchedulerConfigError::UnknownPlugin {
+                        scheduler_name: scheduler_name.clone(),
+                        plugin_name: plugin.name.clone(),
+                    }
+                })
+            };
+            for (extension_point, plugins) in profile.plugins.extension_points() {
+                for plugin in plugins.iter() {
+                    if let Some(err) = unknown_plugin(plugin) {
+                        return Err(err);
+                    }
+                    if !plugin_implements(&plugin.name, extension_point) {
+                        return Err(KubeSchedulerConfigError::WrongExtensionPoint {
+                            scheduler_name: scheduler_name.clone(),
+                            plugin_name: plugin.name.clone(),
+                            extension_point: extension_point.to_string(),
+                        });
+                    }
+                    // Only score plugins are configurable for now.
+                    if extension_point != "score" && plugin.args.is_some() {
+                        return Err(KubeSchedulerConfigError::InvalidArgs {
+                            scheduler_name: scheduler_name.clone(),
+                            plugin_name: plugin.name.clone(),
+                            reason: format!("{} plugins do not accept args", extension_point),
+                        });
+                    }
+                }
+            }
+            for plugin in profile.plugins.score.iter() {
+                if let Some(weight) = plugin.weight {
+                    if !(weight.is_finite() && weight > 0.0) {
+                        return Err(KubeSchedulerConfigError::InvalidWeight {
+                            scheduler_name: scheduler_name.clone(),
+                            plugin_name: plugin.name.clone(),
+                            weight,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn default_kube_scheduler_config() -> KubeSchedulerConfig {
-    let scheduler_name = "default_scheduler".to_string();
+    let scheduler_name = DEFAULT_SCHEDULER_NAME.to_string();
     let default_profile = KubeSchedulerProfile {
         scheduler_name: scheduler_name.clone(),
         plugins: Plugins {
-            filter: vec![Plugin {
-                name: "Fit".to_string(),
+            pre_filter: vec![
+                Plugin {
+                    name: "InterPodAffinity".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "PodTopologySpread".to_string(),
+                    weight: None,
+                    args: None,
+                },
+            ],
+            filter: vec![
+                Plugin {
+                    name: "Fit".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "NodeAffinity".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "InterPodAffinity".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "PodTopologySpread".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "TaintToleration".to_string(),
+                    weight: None,
+                    args: None,
+                },
+                Plugin {
+                    name: "NodeUnschedulable".to_string(),
+                    weight: None,
+                    args: None,
+                },
+            ],
+            post_filter: vec![Plugin {
+                name: "DefaultPreemption".to_string(),
                 weight: None,
+                args: None,
             }],
-            score: vec![Plugin {
-                name: "LeastAllocatedResources".to_string(),
-                weight: Some(1.0),
+            score: vec![
+                Plugin {
+                    name: "LeastAllocatedResources".to_string(),
+                    weight: Some(1.0),
+                    args: None,
+                },
+                Plugin {
+                    name: "NodeAffinity".to_string(),
+                    weight: Some(1.0),
+                    args: None,
+                },
+                Plugin {
+                    name: "InterPodAffinity".to_string(),
+                    weight: Some(1.0),
+                    args: None,
+                },
+                Plugin {
+                    name: "PodTopologySpread".to_string(),
+                    weight: Some(1.0),
+                    args: None,
+                },
+                Plugin {
+                    name: "TaintToleration".to_string(),
+                    weight: Some(1.0),
+                    args: None,
+                },
+            ],
+            reserve: vec![],
+            permit: vec![],
+            pre_bind: vec![],
+            bind: vec![Plugin {
+                name: "DefaultBinder".to_string(),
+                weight: None,
+                args: None,
             }],
         },
+        percentage_of_nodes_to_score: None,
     };
     KubeSchedulerConfig {
         profiles: HashMap::from([(scheduler_name, default_profile)]),
+        pod_initial_backoff_seconds: DEFAULT_POD_INITIAL_BACKOFF_SECONDS,
+        pod_max_backoff_seconds: DEFAULT_POD_MAX_BACKOFF_SECONDS,
     }
 }
 
 impl KubeScheduler {
-    pub fn new(config: KubeSchedulerConfig) -> Self {
-        Self { config }
+    pub fn new(config: KubeSchedulerConfig) -> Result<Self, KubeSchedulerConfigError> {
+        config.validate()?;
+
+        let mut score_plugins: HashMap<String, Vec<Box<dyn ScorePlugin>>> = Default::default();
+        for (scheduler_name, profile) in config.profiles.iter() {
+            let mut profile_score_plugins: Vec<Box<dyn ScorePlugin>> = vec![];
+            for plugin in profile.plugins.score.iter() {
+                let factory = find_score_plugin_factory(&plugin.name).unwrap();
+                profile_score_plugins.push(factory(plugin.args.as_ref()).map_err(|reason| {
+                    KubeSchedulerConfigError::InvalidArgs {
+                        scheduler_name: scheduler_name.clone(),
+                        plugin_name: plugin.name.clone(),
+                        reason,
+                    }
+                })?);
+            }
+            score_plugins.insert(scheduler_name.clone(), profile_score_plugins);
+        }
+
+        Ok(Self {
+            config,
+            score_plugins,
+            next_start_node_index: Cell::new(0),
+        })
+    }
+
+    // Returns scheduler name and profile which the pod requests.
+    fn pod_profile(&self, pod: &Pod) -> Option<(&str, &KubeSchedulerProfile)> {
+        self.config
+            .profiles
+            .get_key_value(pod_scheduler_name(pod))
+            .map(|(scheduler_name, profile)| (scheduler_name.as_str(), profile))
     }
 
     fn schedule_one(
         &self,
         pod: &Pod,
-        nodes: &BTreeMap<String, Node>,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
     ) -> Result<String, ScheduleError> {
+        let nodes = snapshot.nodes;
         let requested_resources = &pod.spec.resources.requests;
         if requested_resources.cpu == 0 && requested_resources.ram == 0 {
             return Err(ScheduleError::RequestedResourcesAreZeros);
@@ -79,61 +498,83 @@ impl KubeScheduler {
             return Err(ScheduleError::NoNodesInCluster);
         }
 
-        let default_scheduler_name = "default_scheduler".to_string();
-        let pod_scheduler = pod
-            .metadata
-            .labels
-            .get("scheduler_name")
-            .unwrap_or(&default_scheduler_name);
+        let (pod_scheduler, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return Err(ScheduleError::SchedulerProfileNotFound),
+        };
 
-        let mut filtered_nodes = nodes.values().into_iter().collect::<Vec<&Node>>();
-
-        for filter in self
-            .config
-            .profiles
-            .get(pod_scheduler)
-            .unwrap()
-            .plugins
-            .filter
-            .iter()
-        {
-            let plugin = PLUGIN_REGISTRY.get(&filter.name as &str).unwrap();
-            match plugin {
-                PluginType::FilterPlugin(filter_plugin) => {
-                    filtered_nodes = filter_plugin.filter(pod, filtered_nodes);
+        for pre_filter in profile.plugins.pre_filter.iter() {
+            let pre_filter_plugin = find_pre_filter_plugin(&pre_filter.name).unwrap();
+            match pre_filter_plugin.pre_filter(pod, snapshot, state) {
+                Status::Success | Status::Skip => {}
+                Status::Unschedulable(reason) | Status::Error(reason) => {
+                    state.add_unschedulable_plugin(&pre_filter.name);
+                    return Err(ScheduleError::RejectedByPreFilter(reason));
+                }
+                Status::Wait(_) => {
+                    return Err(ScheduleError::RejectedByPreFilter(format!(
+                        "plugin {:?} returned unexpected wait status",
+                        pre_filter.name
+                    )))
                 }
-                _ => panic!("{:?} plugin is not of FilterPlugin enum type", filter.name),
             }
         }
 
+        // Nodes are filtered in chunks starting from the rotating index until enough feasible nodes
+        // are found.
+        let num_nodes_to_find =
+            num_feasible_nodes_to_find(profile.percentage_of_nodes_to_score, nodes.len());
+        let start_node_index = self.next_start_node_index.get() % nodes.len();
+        let mut candidate_nodes = nodes
+            .values()
+            .skip(start_node_index)
+            .chain(nodes.values().take(start_node_index));
+        let mut filtered_nodes: Vec<&Node> = vec![];
+        let mut processed_nodes = 0;
+        while filtered_nodes.len() < num_nodes_to_find {
+            let chunk: Vec<&Node> = candidate_nodes
+                .by_ref()
+                .take(num_nodes_to_find - filtered_nodes.len())
+                .collect();
+            if chunk.is_empty() {
+                break;
+            }
+            processed_nodes += chunk.len();
+            filtered_nodes.extend(Self::run_filter_plugins(
+                pod, chunk, profile, snapshot, state,
+            ));
+        }
+        self.next_start_node_index
+            .set((start_node_index + processed_nodes) % nodes.len());
+        state.set_evaluated_nodes(EvaluatedNodes {
+            filtered: processed_nodes,
+            scored: filtered_nodes.len(),
+        });
+
         if filtered_nodes.len() == 0 {
             return Err(ScheduleError::NoSufficientResources);
         }
 
-        let mut node_scores: BTreeMap<&str, (&Node, f64)> = Default::default();
+        let mut node_scores: BTreeMap<&str, (&Node, f64)> = filtered_nodes
+            .iter()
+            .map(|node| (node.metadata.name.borrow(), (*node, 0.0)))
+            .collect();
 
-        for scorer in self
-            .config
-            .profiles
-            .get(pod_scheduler)
-            .unwrap()
+        for (scorer, score_plugin) in profile
             .plugins
             .score
             .iter()
+            .zip(self.score_plugins[pod_scheduler].iter())
         {
-            let plugin = PLUGIN_REGISTRY.get(&scorer.name as &str).unwrap();
-            match plugin {
-                PluginType::ScorePlugin(score_plugin) => {
-                    for node in filtered_nodes.iter() {
-                        let node_name = node.metadata.name.borrow();
-                        if !node_scores.contains_key(node_name) {
-                            node_scores.insert(node_name, (node, 0.0));
-                        }
-                        let node_score = score_plugin.score(pod, node) * scorer.weight.unwrap();
-                        (*node_scores.get_mut(node_name).unwrap()).1 += node_score;
-                    }
-                }
-                _ => panic!("{:?} plugin is not of ScorePlugin enum type", scorer.name),
+            let mut plugin_scores: Vec<(&Node, f64)> = filtered_nodes
+                .iter()
+                .map(|node| (*node, score_plugin.score(pod, node, snapshot, state)))
+                .collect();
+            score_plugin.normalize_scores(pod, &mut plugin_scores, state);
+
+            for (node, score) in plugin_scores {
+                let node_name: &str = node.metadata.name.borrow();
+                node_scores.get_mut(node_name).unwrap().1 += score * scorer.weight.unwrap_or(1.0);
             }
         }
 
@@ -149,6 +590,45 @@ impl KubeScheduler {
 
         Ok(assigned_node.to_string())
     }
+
+    fn run_filter_plugins<'a>(
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        profile: &KubeSchedulerProfile,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
+    ) -> Vec<&'a Node> {
+        let mut filtered_nodes = nodes;
+        for filter in profile.plugins.filter.iter() {
+            let filter_plugin = find_filter_plugin(&filter.name).unwrap();
+            let nodes_before_filter = filtered_nodes.len();
+            filtered_nodes = filter_plugin.filter(pod, filtered_nodes, snapshot, state);
+            if filtered_nodes.len() < nodes_before_filter {
+                state.add_unschedulable_plugin(&filter.name);
+            }
+        }
+        filtered_nodes
+    }
+}
+
+// Number of feasible nodes after which filtering stops, see numFeasibleNodesToFind of
+// kube-scheduler.
+pub fn num_feasible_nodes_to_find(
+    percentage_of_nodes_to_score: Option<u32>,
+    num_all_nodes: usize,
+) -> usize {
+    let percentage = percentage_of_nodes_to_score.unwrap_or(0) as usize;
+    if num_all_nodes < MIN_FEASIBLE_NODES_TO_FIND || percentage >= 100 {
+        return num_all_nodes;
+    }
+    let percentage = if percentage == 0 {
+        BASE_PERCENTAGE_OF_NODES_TO_SCORE
+            .saturating_sub(num_all_nodes / 125)
+            .max(MIN_FEASIBLE_NODES_PERCENTAGE_TO_FIND)
+    } else {
+        percentage
+    };
+    (num_all_nodes * percentage / 100).max(MIN_FEASIBLE_NODES_TO_FIND)
 }
 
 impl PodSchedulingAlgorithm for KubeScheduler {
@@ -156,8 +636,498 @@ impl PodSchedulingAlgorithm for KubeScheduler {
     fn schedule_one(
         &self,
         pod: &Pod,
-        nodes: &BTreeMap<String, Node>,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
     ) -> Result<String, ScheduleError> {
-        KubeScheduler::schedule_one(self, pod, nodes)
+        KubeScheduler::schedule_one(self, pod, snapshot, state)
+    }
+
+    fn post_filter(
+        &self,
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+    ) -> Option<PreemptionCandidate> {
+        let (_, profile) = self.pod_profile(pod)?;
+        profile.plugins.post_filter.iter().find_map(|plugin| {
+            find_post_filter_plugin(&plugin.name)
+                .unwrap()
+                .post_filter(pod, snapshot, state)
+        })
+    }
+
+    fn reserve(&self, pod: &Pod, node_name: &str, state: &mut CycleState) -> Status {
+        let (_, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return Status::Success,
+        };
+        for plugin in profile.plugins.reserve.iter() {
+            let status = find_reserve_plugin(&plugin.name)
+                .unwrap()
+                .reserve(pod, node_name, state);
+            if !status.is_success() && status != Status::Skip {
+                return status;
+            }
+        }
+        Status::Success
+    }
+
+    fn unreserve(
+        &self,
+        pod: &Pod,
+        node_name: &str,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
+        waiting_pods: &mut WaitingPods,
+    ) {
+        let (_, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return,
+        };
+        for plugin in profile.plugins.reserve.iter().rev() {
+            find_reserve_plugin(&plugin.name).unwrap().unreserve(
+                pod,
+                node_name,
+                snapshot,
+                state,
+                waiting_pods,
+            );
+        }
+    }
+
+    fn permit(
+        &self,
+        pod: &Pod,
+        node_name: &str,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+        waiting_pods: &mut WaitingPods,
+    ) -> PermitDecision {
+        let (_, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return PermitDecision::Allow,
+        };
+        let mut waiting_plugins: BTreeSet<String> = Default::default();
+        let mut wait_timeout = f64::MAX;
+        for plugin in profile.plugins.permit.iter() {
+            match find_permit_plugin(&plugin.name).unwrap().permit(
+                pod,
+                node_name,
+                snapshot,
+                state,
+                waiting_pods,
+            ) {
+                Status::Success | Status::Skip => {}
+                Status::Wait(timeout) => {
+                    waiting_plugins.insert(plugin.name.clone());
+                    // The pod is rejected as soon as the first plugin's timeout expires.
+                    wait_timeout = wait_timeout.min(timeout);
+                }
+                Status::Unschedulable(reason) | Status::Error(reason) => {
+                    return PermitDecision::Reject(reason)
+                }
+            }
+        }
+        if waiting_plugins.is_empty() {
+            PermitDecision::Allow
+        } else {
+            PermitDecision::Wait {
+                plugins: waiting_plugins,
+                timeout: wait_timeout,
+            }
+        }
+    }
+
+    fn pre_bind(&self, pod: &Pod, node_name: &str, state: &CycleState) -> Status {
+        let (_, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return Status::Success,
+        };
+        for plugin in profile.plugins.pre_bind.iter() {
+            let status = find_pre_bind_plugin(&plugin.name)
+                .unwrap()
+                .pre_bind(pod, node_name, state);
+            if !status.is_success() && status != Status::Skip {
+                return status;
+            }
+        }
+        Status::Success
+    }
+
+    // The first bind plugin which does not skip the pod decides the result. Profile without bind
+    // plugins binds all pods.
+    fn bind(&self, pod: &Pod, node_name: &str, state: &CycleState) -> Status {
+        let (_, profile) = match self.pod_profile(pod) {
+            Some(profile) => profile,
+            None => return Status::Success,
+        };
+        profile
+            .plugins
+            .bind
+            .iter()
+            .map(|plugin| {
+                find_bind_plugin(&plugin.name)
+                    .unwrap()
+                    .bind(pod, node_name, state)
+            })
+            .find(|status| *status != Status::Skip)
+            .unwrap_or(Status::Success)
+    }
+
+    // The pod is retried if any filter plugin which rejected it allows. Pods rejected by other
+    // extension points are retried after any event.
+    fn queueing_hint(
+        &self,
+        pod: &Pod,
+        unschedulable_plugins: &BTreeSet<String>,
+        event: &ClusterEvent,
+        snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        let retry = unschedulable_plugins.is_empty()
+            || unschedulable_plugins.iter().any(|plugin_name| {
+                match find_filter_plugin(plugin_name) {
+                    Some(filter_plugin) => {
+                        filter_plugin.queueing_hint(pod, event, snapshot) == QueueingHint::Queue
+                    }
+                    None => true,
+                }
+            });
+        QueueingHint::queue_if(retry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+    use crate::core::node::{Node, Taint, TaintEffect};
+    use crate::core::pod::Pod;
+    use crate::core::scheduler::framework::{
+        ClusterEvent, CycleState, EvaluatedNodes, QueueingHint,
+    };
+    use crate::core::scheduler::interface::{ClusterSnapshot, PodSchedulingAlgorithm};
+    use crate::core::scheduler::kube_scheduler::{
+        default_kube_scheduler_config, num_feasible_nodes_to_find, KubeScheduler,
+        KubeSchedulerConfig, KubeSchedulerConfigError,
+    };
+
+    fn config_with_plugins(plugins: &str) -> Result<KubeSchedulerConfig, serde_yaml::Error> {
+        serde_yaml::from_str(&format!(
+            r#"
+        profiles:
+        - scheduler_name: default_scheduler
+          plugins:
+            {}
+        "#,
+            plugins
+        ))
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(default_kube_scheduler_config().validate().is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_config() {
+        let config = config_with_plugins(
+            r#"
+            filter:
+            - name: Fit
+            score:
+            - name: LeastAllocatedResources
+              weight: 2.0
+            - name: NodeAffinity"#,
+        )
+        .unwrap();
+        let profile = &config.profiles["default_scheduler"];
+        assert_eq!(1, profile.plugins.filter.len());
+        assert_eq!(Some(2.0), profile.plugins.score[0].weight);
+        assert_eq!(None, profile.plugins.score[1].weight);
+        assert!(KubeScheduler::new(config).is_ok());
+
+        let duplicate_profiles = serde_yaml::from_str::<KubeSchedulerConfig>(
+            r#"
+        profiles:
+        - scheduler_name: default_scheduler
+          plugins: {}
+        - scheduler_name: default_scheduler
+          plugins: {}
+        "#,
+        );
+        assert!(duplicate_profiles.is_err());
+    }
+
+    #[test]
+    fn test_invalid_plugins_are_rejected() {
+        let validate =
+            |plugins: &str| KubeScheduler::new(config_with_plugins(plugins).unwrap()).err();
+
+        assert_eq!(
+            Some(KubeSchedulerConfigError::UnknownPlugin {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "Fitt".to_string(),
+            }),
+            validate("filter: [{name: Fitt}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::WrongExtensionPoint {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "LeastAllocatedResources".to_string(),
+                extension_point: "filter".to_string(),
+            }),
+            validate("filter: [{name: LeastAllocatedResources}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::WrongExtensionPoint {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "Fit".to_string(),
+                extension_point: "score".to_string(),
+            }),
+            validate("score: [{name: Fit, weight: 1.0}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::WrongExtensionPoint {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "DefaultBinder".to_string(),
+                extension_point: "post_filter".to_string(),
+            }),
+            validate("post_filter: [{name: DefaultBinder}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::InvalidWeight {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "NodeAffinity".to_string(),
+                weight: -1.0,
+            }),
+            validate("score: [{name: NodeAffinity, weight: -1.0}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::InvalidArgs {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "Fit".to_string(),
+                reason: "filter plugins do not accept args".to_string(),
+            }),
+            validate("filter: [{name: Fit, args: {resources: {cpu: 1.0}}}]")
+        );
+        assert_eq!(
+            Some(KubeSchedulerConfigError::InvalidArgs {
+                scheduler_name: "default_scheduler".to_string(),
+                plugin_name: "PodTopologySpread".to_string(),
+                reason: "pre_filter plugins do not accept args".to_string(),
+            }),
+            validate("pre_filter: [{name: PodTopologySpread, args: {}}]")
+        );
+        assert!(matches!(
+            validate("score: [{name: MostAllocated, args: {resources: {cpu: -1.0}}}]"),
+            Some(KubeSchedulerConfigError::InvalidArgs { .. })
+        ));
+        assert_eq!(
+            Some(KubeSchedulerConfigError::NoProfiles),
+            KubeScheduler::new(serde_yaml::from_str("profiles: []").unwrap()).err()
+        );
+
+        let mut config = default_kube_scheduler_config();
+        config
+            .profiles
+            .get_mut("default_scheduler")
+            .unwrap()
+            .percentage_of_nodes_to_score = Some(101);
+        assert_eq!(
+            Some(KubeSchedulerConfigError::InvalidPercentageOfNodesToScore {
+                scheduler_name: "default_scheduler".to_string(),
+                percentage: 101,
+            }),
+            KubeScheduler::new(config).err()
+        );
+
+        let mut config = default_kube_scheduler_config();
+        config.pod_initial_backoff_seconds = 20.0;
+        assert_eq!(
+            Some(KubeSchedulerConfigError::InvalidBackoff {
+                initial_backoff: 20.0,
+                max_backoff: 10.0,
+            }),
+            KubeScheduler::new(config).err()
+        );
+
+        // With several invalid profiles the error is always reported for the first one by name.
+        for _ in 0..10 {
+            let config: KubeSchedulerConfig = serde_yaml::from_str(
+                r#"
+            profiles:
+            - scheduler_name: c_scheduler
+              plugins: {filter: [{name: Fitt}]}
+            - scheduler_name: a_scheduler
+              plugins: {filter: [{name: Fitt}]}
+            - scheduler_name: b_scheduler
+              plugins: {filter: [{name: Fitt}]}
+            "#,
+            )
+            .unwrap();
+            assert_eq!(
+                Some(KubeSchedulerConfigError::UnknownPlugin {
+                    scheduler_name: "a_scheduler".to_string(),
+                    plugin_name: "Fitt".to_string(),
+                }),
+                config.validate().err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_num_feasible_nodes_to_find() {
+        // Small clusters are filtered entirely.
+        assert_eq!(50, num_feasible_nodes_to_find(None, 50));
+        assert_eq!(99, num_feasible_nodes_to_find(Some(10), 99));
+        // Adaptive percentage is 50% minus 1% per 125 nodes, but not less than 5%.
+        assert_eq!(420, num_feasible_nodes_to_find(None, 1000));
+        assert_eq!(500, num_feasible_nodes_to_find(Some(0), 5000));
+        assert_eq!(5000, num_feasible_nodes_to_find(None, 100000));
+        // At least 100 nodes are found.
+        assert_eq!(100, num_feasible_nodes_to_find(Some(10), 500));
+        assert_eq!(300, num_feasible_nodes_to_find(Some(30), 1000));
+        assert_eq!(1000, num_feasible_nodes_to_find(Some(100), 1000));
+    }
+
+    #[test]
+    fn test_percentage_of_nodes_to_score() {
+        let mut config = config_with_plugins(
+            r#"
+            filter:
+            - name: Fit
+            score:
+            - name: LeastAllocatedResources"#,
+        )
+        .unwrap();
+        config
+            .profiles
+            .get_mut("default_scheduler")
+            .unwrap()
+            .percentage_of_nodes_to_score = Some(50);
+        let scheduler = KubeScheduler::new(config).unwrap();
+
+        // The last node is too small for the pod.
+        let nodes: BTreeMap<String, Node> = (0..400)
+            .map(|idx| {
+                let name = format!("node_{:03}", idx);
+                let cpu = if idx < 399 { 16000 } else { 2000 };
+                (name.clone(), Node::new(name, cpu, 17179869184))
+            })
+            .collect();
+        let (pods, assignments, pending_evictions) =
+            (BTreeMap::new(), HashMap::new(), HashSet::new());
+        let snapshot = ClusterSnapshot {
+            nodes: &nodes,
+            pods: &pods,
+            assignments: &assignments,
+            pending_evictions: &pending_evictions,
+        };
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+        let schedule = |expected_node: &str, filtered: usize| {
+            let mut state = CycleState::default();
+            assert_eq!(
+                expected_node,
+                scheduler.schedule_one(&pod, &snapshot, &mut state).unwrap()
+            );
+            assert_eq!(
+                Some(EvaluatedNodes {
+                    filtered,
+                    scored: 200
+                }),
+                state.evaluated_nodes()
+            );
+        };
+
+        // 200 feasible nodes are enough. All of them have equal scores and the last one by name
+        // is chosen. Every attempt starts from the node where the previous one stopped.
+        schedule("node_199", 200);
+        schedule("node_398", 201);
+        schedule("node_200", 200);
+    }
+
+    #[test]
+    fn test_queueing_hint() {
+        let scheduler = KubeScheduler::new(default_kube_scheduler_config()).unwrap();
+        let (nodes, pods, assignments, pending_evictions) = (
+            BTreeMap::new(),
+            BTreeMap::new(),
+            HashMap::new(),
+            HashSet::new(),
+        );
+        let snapshot = ClusterSnapshot {
+            nodes: &nodes,
+            pods: &pods,
+            assignments: &assignments,
+            pending_evictions: &pending_evictions,
+        };
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+        let small_node = Node::new("small_node".to_string(), 2000, 8589934592);
+        let mut tainted_node = Node::new("tainted_node".to_string(), 8000, 17179869184);
+        tainted_node.spec.taints = vec![Taint {
+            key: "dedicated".to_string(),
+            value: "gpu".to_string(),
+            effect: TaintEffect::NoSchedule,
+        }];
+        let hint = |unschedulable_plugins: &[&str], event: &ClusterEvent| {
+            let unschedulable_plugins: BTreeSet<String> = unschedulable_plugins
+                .iter()
+                .map(|plugin_name| plugin_name.to_string())
+                .collect();
+            scheduler.queueing_hint(&pod, &unschedulable_plugins, event, &snapshot)
+        };
+
+        // Pod which does not fit into nodes is retried only if the new node is large enough.
+        let fit = ["Fit"];
+        assert_eq!(
+            QueueingHint::Skip,
+            hint(&fit, &ClusterEvent::NodeAdded(&small_node))
+        );
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(&fit, &ClusterEvent::NodeAdded(&tainted_node))
+        );
+        assert_eq!(
+            QueueingHint::Skip,
+            hint(&fit, &ClusterEvent::NodeUpdated(&tainted_node))
+        );
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(
+                &fit,
+                &ClusterEvent::AssignedPodDeleted {
+                    pod: &pod,
+                    node: &tainted_node
+                }
+            )
+        );
+
+        // It is enough that one of the plugins which rejected the pod allows retrying.
+        let fit_and_taints = ["Fit", "TaintToleration"];
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(&fit_and_taints, &ClusterEvent::NodeAdded(&small_node))
+        );
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(&fit_and_taints, &ClusterEvent::NodeUpdated(&small_node))
+        );
+        assert_eq!(
+            QueueingHint::Skip,
+            hint(&fit_and_taints, &ClusterEvent::NodeUpdated(&tainted_node))
+        );
+
+        // Pods which failed not at filters are retried after any event.
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(&[], &ClusterEvent::NodeAdded(&small_node))
+        );
+        assert_eq!(
+            QueueingHint::Queue,
+            hint(
+                &["PodTopologySpread"],
+                &ClusterEvent::NodeAdded(&small_node)
+            )
+        );
     }
 }
@@ -1,6 +1,9 @@
+pub mod extender;
+pub mod framework;
 pub mod interface;
 pub mod kube_scheduler;
 pub mod model;
 pub mod plugin;
+pub mod preemption;
 pub mod queue;
 pub mod scheduler;
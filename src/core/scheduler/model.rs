@@ -1,27 +1,554 @@
 //! Models which simulates the time of pod scheduling based on different parameters.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
+use dslab_core::SimulationContext;
+use serde::Deserialize;
+
+use crate::core::scheduler::framework::EvaluatedNodes;
+use crate::core::scheduler::kube_scheduler::{pod_scheduler_name, KubeSchedulerConfig};
 use crate::core::{node::Node, pod::Pod};
 
 pub trait PodSchedulingTimeModel {
-    fn simulate_time(&self, pod: &Pod, nodes: &BTreeMap<String, Node>) -> f64;
+    /// `evaluated_nodes` are numbers of nodes which the scheduling algorithm processed for the pod.
+    fn simulate_time(
+        &self,
+        pod: &Pod,
+        nodes: &BTreeMap<String, Node>,
+        evaluated_nodes: EvaluatedNodes,
+    ) -> f64;
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct PodSchedulingTimeModelConfig {
+    /// Name of the model. Used in `resolve_pod_scheduling_time_model`.
+    #[serde(default = "model_type_default")]
+    pub model_type: String,
+    /// Config of the model with the corresponding name. If not set, defaults are used where
+    /// possible.
+    pub constant_time_per_node: Option<ConstantTimePerNodeModelConfig>,
+    pub per_plugin_cost: Option<PerPluginCostModelConfig>,
+    pub sampled_distribution: Option<SampledDistributionModelConfig>,
+    pub latency_histogram: Option<LatencyHistogramModelConfig>,
+}
+
+impl Default for PodSchedulingTimeModelConfig {
+    fn default() -> Self {
+        Self {
+            model_type: model_type_default(),
+            constant_time_per_node: None,
+            per_plugin_cost: None,
+            sampled_distribution: None,
+            latency_histogram: None,
+        }
+    }
+}
+
+fn model_type_default() -> String {
+    "constant_time_per_node".to_string()
+}
+
+fn time_per_node_default() -> f64 {
+    0.000001 // 1 us
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct ConstantTimePerNodeModelConfig {
+    #[serde(default = "time_per_node_default")]
+    pub time_per_node: f64,
 }
 
 pub struct ConstantTimePerNodeModel {
     constant_time_per_node: f64,
 }
 
+impl ConstantTimePerNodeModel {
+    pub fn new(config: ConstantTimePerNodeModelConfig) -> Self {
+        Self {
+            constant_time_per_node: config.time_per_node,
+        }
+    }
+}
+
 impl Default for ConstantTimePerNodeModel {
     fn default() -> Self {
         Self {
-            constant_time_per_node: 0.000001,
-        } // 1 us
+            constant_time_per_node: time_per_node_default(),
+        }
     }
 }
 
 impl PodSchedulingTimeModel for ConstantTimePerNodeModel {
-    fn simulate_time(&self, _pod: &Pod, nodes: &BTreeMap<String, Node>) -> f64 {
-        self.constant_time_per_node * nodes.len() as f64
+    fn simulate_time(
+        &self,
+        _pod: &Pod,
+        _nodes: &BTreeMap<String, Node>,
+        evaluated_nodes: EvaluatedNodes,
+    ) -> f64 {
+        self.constant_time_per_node * evaluated_nodes.filtered as f64
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct PerPluginCostModelConfig {
+    /// Time spent on a pod regardless of plugins, e.g. on taking a snapshot of the cache.
+    #[serde(default)]
+    pub base_time: f64,
+    /// Time of a single invocation of a filter or score plugin which is not listed in
+    /// `plugin_time_per_node`.
+    #[serde(default = "time_per_node_default")]
+    pub default_plugin_time_per_node: f64,
+    /// Map from plugin name to the time of its single invocation for a node.
+    #[serde(default)]
+    pub plugin_time_per_node: HashMap<String, f64>,
+}
+
+/// Filter plugins are invoked for every evaluated node and score plugins for every feasible one, so
+/// the time of a pod depends on plugins of the profile which schedules the pod.
+pub struct PerPluginCostModel {
+    base_time: f64,
+    // Map from scheduler name to summed time of filter plugins and of score plugins of its profile
+    // per node.
+    profile_time_per_node: HashMap<String, (f64, f64)>,
+}
+
+impl PerPluginCostModel {
+    pub fn new(config: PerPluginCostModelConfig, scheduler_config: &KubeSchedulerConfig) -> Self {
+        let plugin_time = |plugin_name: &str| {
+            *config
+                .plugin_time_per_node
+                .get(plugin_name)
+                .unwrap_or(&config.default_plugin_time_per_node)
+        };
+        let profile_time_per_node = scheduler_config
+            .profiles
+            .iter()
+            .map(|(scheduler_name, profile)| {
+                let filter_time_per_node: f64 = profile
+                    .plugins
+                    .filter
+                    .iter()
+                    .map(|plugin| plugin_time(&plugin.name))
+                    .sum();
+                let score_time_per_node: f64 = profile
+                    .plugins
+                    .score
+                    .iter()
+                    .map(|plugin| plugin_time(&plugin.name))
+                    .sum();
+                (
+                    scheduler_name.clone(),
+                    (filter_time_per_node, score_time_per_node),
+                )
+            })
+            .collect();
+        Self {
+            base_time: config.base_time,
+            profile_time_per_node,
+        }
+    }
+}
+
+impl PodSchedulingTimeModel for PerPluginCostModel {
+    fn simulate_time(
+        &self,
+        pod: &Pod,
+        _nodes: &BTreeMap<String, Node>,
+        evaluated_nodes: EvaluatedNodes,
+    ) -> f64 {
+        // Pods with unknown scheduler name are rejected without running plugins.
+        let (filter_time_per_node, score_time_per_node) = self
+            .profile_time_per_node
+            .get(pod_scheduler_name(pod))
+            .unwrap_or(&(0.0, 0.0));
+        self.base_time
+            + filter_time_per_node * evaluated_nodes.filtered as f64
+            + score_time_per_node * evaluated_nodes.scored as f64
+    }
+}
+
+/// Distributions of pod scheduling time in seconds.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub enum TimeDistribution {
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    Exponential {
+        mean: f64,
+    },
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    /// Logarithm of the time is normally distributed with the given parameters.
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SampledDistributionModelConfig {
+    pub distribution: TimeDistribution,
+}
+
+/// Samples time of every pod independently from the distribution, negative samples are
+/// truncated to zero.
+pub struct SampledDistributionModel {
+    distribution: TimeDistribution,
+    ctx: SimulationContext,
+}
+
+impl SampledDistributionModel {
+    pub fn new(config: SampledDistributionModelConfig, ctx: SimulationContext) -> Self {
+        Self {
+            distribution: config.distribution,
+            ctx,
+        }
+    }
+
+    // Box-Muller transform.
+    fn sample_standard_normal(&self) -> f64 {
+        let u1 = 1.0 - self.ctx.rand();
+        let u2 = self.ctx.rand();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+impl PodSchedulingTimeModel for SampledDistributionModel {
+    fn simulate_time(
+        &self,
+        _pod: &Pod,
+        _nodes: &BTreeMap<String, Node>,
+        _evaluated_nodes: EvaluatedNodes,
+    ) -> f64 {
+        let time = match self.distribution {
+            TimeDistribution::Uniform { min, max } => min + (max - min) * self.ctx.rand(),
+            TimeDistribution::Exponential { mean } => -mean * (1.0 - self.ctx.rand()).ln(),
+            TimeDistribution::Normal { mean, std_dev } => {
+                mean + std_dev * self.sample_standard_normal()
+            }
+            TimeDistribution::LogNormal { mu, sigma } => {
+                (mu + sigma * self.sample_standard_normal()).exp()
+            }
+        };
+        time.max(0.0)
+    }
+}
+
+/// Bucket of a latency histogram in the format of Prometheus histograms which kube-scheduler
+/// exports, e.g. `scheduler_scheduling_algorithm_duration_seconds`.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct HistogramBucket {
+    /// Upper bound of the bucket in seconds, the last bucket might be `.inf`.
+    pub le: f64,
+    /// Cumulative count of observations less or equal to the upper bound.
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct LatencyHistogramModelConfig {
+    pub buckets: Vec<HistogramBucket>,
+}
+
+/// Samples time from the distribution fitted to the histogram: a bucket is chosen with the
+/// probability proportional to the number of its observations and time is uniform within the
+/// bucket. Observations of the infinite bucket are assigned the largest finite upper bound.
+pub struct LatencyHistogramModel {
+    // Bounds of buckets with non-zero observations and cumulative probabilities of them.
+    buckets: Vec<(f64, f64, f64)>,
+    ctx: SimulationContext,
+}
+
+impl LatencyHistogramModel {
+    pub fn new(
+        config: LatencyHistogramModelConfig,
+        ctx: SimulationContext,
+    ) -> Result<Self, String> {
+        let total_count = config.buckets.last().map_or(0, |bucket| bucket.count);
+        if total_count == 0 {
+            return Err("latency histogram should have observations".to_string());
+        }
+
+        let mut buckets = vec![];
+        let (mut lower_bound, mut prev_count) = (0.0, 0);
+        for bucket in config.buckets.iter() {
+            if bucket.le <= lower_bound {
+                return Err(format!(
+                    "latency histogram buckets should have increasing upper bounds, got {} after {}",
+                    bucket.le, lower_bound
+                ));
+            }
+            if bucket.count < prev_count {
+                return Err(format!(
+                    "latency histogram buckets should have cumulative counts, got {} after {}",
+                    bucket.count, prev_count
+                ));
+            }
+            let upper_bound = if bucket.le.is_finite() {
+                bucket.le
+            } else {
+                lower_bound
+            };
+            if bucket.count > prev_count {
+                let cumulative_probability = bucket.count as f64 / total_count as f64;
+                buckets.push((lower_bound, upper_bound, cumulative_probability));
+            }
+            (lower_bound, prev_count) = (upper_bound, bucket.count);
+        }
+        Ok(Self { buckets, ctx })
+    }
+}
+
+impl PodSchedulingTimeModel for LatencyHistogramModel {
+    fn simulate_time(
+        &self,
+        _pod: &Pod,
+        _nodes: &BTreeMap<String, Node>,
+        _evaluated_nodes: EvaluatedNodes,
+    ) -> f64 {
+        let probability = self.ctx.rand();
+        let &(lower_bound, upper_bound, _) = self
+            .buckets
+            .iter()
+            .find(|(_, _, cumulative_probability)| probability < *cumulative_probability)
+            .unwrap_or(self.buckets.last().unwrap());
+        lower_bound + (upper_bound - lower_bound) * self.ctx.rand()
+    }
+}
+
+/// Returns a description of the problem if the model type is unknown or its config is invalid.
+pub fn resolve_pod_scheduling_time_model(
+    model_config: PodSchedulingTimeModelConfig,
+    scheduler_config: &KubeSchedulerConfig,
+    ctx: SimulationContext,
+) -> Result<Box<dyn PodSchedulingTimeModel>, String> {
+    let missing_config = || {
+        format!(
+            "model {:?} requires {:?} config section",
+            model_config.model_type, model_config.model_type
+        )
+    };
+    match &model_config.model_type as &str {
+        "constant_time_per_node" => match model_config.constant_time_per_node {
+            Some(config) => Ok(Box::new(ConstantTimePerNodeModel::new(config))),
+            None => Ok(Box::new(ConstantTimePerNodeModel::default())),
+        },
+        "per_plugin_cost" => {
+            let config = model_config
+                .per_plugin_cost
+                .unwrap_or(PerPluginCostModelConfig {
+                    base_time: 0.0,
+                    default_plugin_time_per_node: time_per_node_default(),
+                    plugin_time_per_node: Default::default(),
+                });
+            Ok(Box::new(PerPluginCostModel::new(config, scheduler_config)))
+        }
+        "sampled_distribution" => {
+            let config = model_config
+                .sampled_distribution
+                .ok_or_else(missing_config)?;
+            Ok(Box::new(SampledDistributionModel::new(config, ctx)))
+        }
+        "latency_histogram" => {
+            let config = model_config.latency_histogram.ok_or_else(missing_config)?;
+            Ok(Box::new(LatencyHistogramModel::new(config, ctx)?))
+        }
+        model_type => Err(format!(
+            "unsupported pod scheduling time model {:?}",
+            model_type
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use dslab_core::Simulation;
+
+    use crate::core::node::Node;
+    use crate::core::pod::Pod;
+    use crate::core::scheduler::framework::EvaluatedNodes;
+    use crate::core::scheduler::kube_scheduler::default_kube_scheduler_config;
+    use crate::core::scheduler::model::{
+        resolve_pod_scheduling_time_model, HistogramBucket, LatencyHistogramModel,
+        LatencyHistogramModelConfig, PodSchedulingTimeModel, PodSchedulingTimeModelConfig,
+        TimeDistribution,
+    };
+
+    fn nodes(count: usize) -> BTreeMap<String, Node> {
+        (0..count)
+            .map(|idx| {
+                let name = format!("node_{}", idx);
+                (name.clone(), Node::new(name, 16000, 17179869184))
+            })
+            .collect()
+    }
+
+    fn try_resolve_model(config: &str) -> Result<Box<dyn PodSchedulingTimeModel>, String> {
+        let mut sim = Simulation::new(123);
+        resolve_pod_scheduling_time_model(
+            serde_yaml::from_str::<PodSchedulingTimeModelConfig>(config).unwrap(),
+            &default_kube_scheduler_config(),
+            sim.create_context("pod_scheduling_time_model"),
+        )
+    }
+
+    fn resolve_model(config: &str) -> Box<dyn PodSchedulingTimeModel> {
+        try_resolve_model(config).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_model_config_is_rejected() {
+        let err = try_resolve_model("model_type: constant_time")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "unsupported pod scheduling time model \"constant_time\"",
+            err
+        );
+
+        let err = try_resolve_model("model_type: latency_histogram")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "model \"latency_histogram\" requires \"latency_histogram\" config section",
+            err
+        );
+
+        let err = try_resolve_model(
+            r#"
+        model_type: latency_histogram
+        latency_histogram:
+          buckets:
+          - le: 0.002
+            count: 10
+          - le: 0.001
+            count: 20
+        "#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("increasing upper bounds"));
+    }
+
+    #[test]
+    fn test_per_plugin_cost_model() {
+        let model = resolve_model(
+            r#"
+        model_type: per_plugin_cost
+        per_plugin_cost:
+          base_time: 0.001
+          default_plugin_time_per_node: 0.00001
+          plugin_time_per_node:
+            InterPodAffinity: 0.0001
+        "#,
+        );
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+        let scheduler_config = default_kube_scheduler_config();
+        let plugins = &scheduler_config.profiles["default_scheduler"].plugins;
+        // InterPodAffinity is both filter and score plugin in the default profile.
+        let filter_time_per_node = (plugins.filter.len() - 1) as f64 * 0.00001 + 0.0001;
+        let score_time_per_node = (plugins.score.len() - 1) as f64 * 0.00001 + 0.0001;
+        let evaluated_nodes = EvaluatedNodes {
+            filtered: 10,
+            scored: 4,
+        };
+        let time = model.simulate_time(&pod, &nodes(10), evaluated_nodes);
+        let expected_time = 0.001 + 10.0 * filter_time_per_node + 4.0 * score_time_per_node;
+        assert!((expected_time - time).abs() < 1e-12);
+
+        let mut other_scheduler_pod = pod.clone();
+        other_scheduler_pod
+            .metadata
+            .labels
+            .insert("scheduler_name".to_string(), "other".to_string());
+        assert_eq!(
+            0.001,
+            model.simulate_time(&other_scheduler_pod, &nodes(10), evaluated_nodes)
+        );
+    }
+
+    #[test]
+    fn test_sampled_distribution_model() {
+        let config: PodSchedulingTimeModelConfig = serde_yaml::from_str(
+            r#"
+        model_type: sampled_distribution
+        sampled_distribution:
+          distribution: !Uniform
+            min: 0.01
+            max: 0.02
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            TimeDistribution::Uniform {
+                min: 0.01,
+                max: 0.02
+            },
+            config.sampled_distribution.as_ref().unwrap().distribution
+        );
+
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+        let model = resolve_model(
+            r#"
+        model_type: sampled_distribution
+        sampled_distribution:
+          distribution: !Exponential
+            mean: 0.01
+        "#,
+        );
+        let samples: Vec<f64> = (0..10000)
+            .map(|_| model.simulate_time(&pod, &nodes(1), Default::default()))
+            .collect();
+        assert!(samples.iter().all(|time| *time >= 0.0));
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean - 0.01).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_latency_histogram_model() {
+        let mut sim = Simulation::new(123);
+        let model = LatencyHistogramModel::new(
+            LatencyHistogramModelConfig {
+                buckets: vec![
+                    HistogramBucket {
+                        le: 0.001,
+                        count: 0,
+                    },
+                    HistogramBucket {
+                        le: 0.002,
+                        count: 75,
+                    },
+                    HistogramBucket {
+                        le: 0.004,
+                        count: 75,
+                    },
+                    HistogramBucket {
+                        le: 0.008,
+                        count: 100,
+                    },
+                    HistogramBucket {
+                        le: f64::INFINITY,
+                        count: 100,
+                    },
+                ],
+            },
+            sim.create_context("pod_scheduling_time_model"),
+        )
+        .unwrap();
+        let pod = Pod::new("pod_1".to_string(), 4000, 8589934592, Some(5.0));
+
+        let samples: Vec<f64> = (0..10000)
+            .map(|_| model.simulate_time(&pod, &nodes(1), Default::default()))
+            .collect();
+        assert!(samples
+            .iter()
+            .all(|time| (0.001..=0.002).contains(time) || (0.004..=0.008).contains(time)));
+        let fast_share =
+            samples.iter().filter(|time| **time <= 0.002).count() as f64 / samples.len() as f64;
+        assert!((fast_share - 0.75).abs() < 0.02);
     }
 }
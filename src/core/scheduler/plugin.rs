@@ -1,63 +1,2220 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 use lazy_static::lazy_static;
 
-use crate::core::node::Node;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::core::affinity::{
+    LabelSelector, PodAffinityTerm, TopologySpreadConstraint, UnsatisfiableConstraintAction,
+};
+use crate::core::node::{Node, Taint, TaintEffect, NODE_UNSCHEDULABLE_TAINT_KEY};
 use crate::core::pod::Pod;
+use crate::core::scheduler::framework::{
+    ClusterEvent, CycleState, QueueingHint, Status, WaitingPods,
+};
+use crate::core::scheduler::interface::ClusterSnapshot;
+use crate::core::scheduler::preemption::{select_preemption_candidate, PreemptionCandidate};
+
+/// Maximal score which score plugins that do not depend on resources give to a node.
+pub const MAX_NODE_SCORE: f64 = 100.0;
+
+/// Creates a score plugin from `args` of the plugin in a scheduler profile. Returns a description
+/// of the problem if arguments are invalid.
+pub type ScorePluginFactory =
+    fn(Option<&serde_yaml::Value>) -> Result<Box<dyn ScorePlugin>, String>;
 
 pub enum PluginType {
+    PreFilterPlugin(Box<dyn PreFilterPlugin>),
     FilterPlugin(Box<dyn FilterPlugin>),
-    ScorePlugin(Box<dyn ScorePlugin>),
+    PostFilterPlugin(Box<dyn PostFilterPlugin>),
+    // Score plugins may be configured differently in each profile, so they are created by
+    // scheduler for every profile.
+    ScorePlugin(ScorePluginFactory),
+    ReservePlugin(Box<dyn ReservePlugin>),
+    PermitPlugin(Box<dyn PermitPlugin>),
+    PreBindPlugin(Box<dyn PreBindPlugin>),
+    BindPlugin(Box<dyn BindPlugin>),
 }
 
 lazy_static! {
-    pub static ref PLUGIN_REGISTRY: HashMap<&'static str, PluginType> = {
+    // One plugin may implement several extension points, so it is registered under the same name
+    // for each of them.
+    pub static ref PLUGIN_REGISTRY: HashMap<&'static str, Vec<PluginType>> = {
         HashMap::from([
-            ("Fit", PluginType::FilterPlugin(Box::new(Fit {}))),
+            ("Fit", vec![PluginType::FilterPlugin(Box::new(Fit {}))]),
             (
                 "LeastAllocatedResources",
-                PluginType::ScorePlugin(Box::new(LeastAllocatedResources {})),
+                vec![PluginType::ScorePlugin(from_args::<LeastAllocatedResources>)],
+            ),
+            (
+                "MostAllocated",
+                vec![PluginType::ScorePlugin(from_args::<MostAllocated>)],
+            ),
+            (
+                "BalancedAllocation",
+                vec![PluginType::ScorePlugin(from_args::<BalancedAllocation>)],
+            ),
+            (
+                "RequestedToCapacityRatio",
+                vec![PluginType::ScorePlugin(from_args::<RequestedToCapacityRatio>)],
+            ),
+            (
+                "NodeAffinity",
+                vec![
+                    PluginType::FilterPlugin(Box::new(NodeAffinity {})),
+                    PluginType::ScorePlugin(without_args::<NodeAffinity>),
+                ],
+            ),
+            (
+                "InterPodAffinity",
+                vec![
+                    PluginType::PreFilterPlugin(Box::new(InterPodAffinity {})),
+                    PluginType::FilterPlugin(Box::new(InterPodAffinity {})),
+                    PluginType::ScorePlugin(without_args::<InterPodAffinity>),
+                ],
+            ),
+            (
+                "PodTopologySpread",
+                vec![
+                    PluginType::PreFilterPlugin(Box::new(PodTopologySpread {})),
+                    PluginType::FilterPlugin(Box::new(PodTopologySpread {})),
+                    PluginType::ScorePlugin(without_args::<PodTopologySpread>),
+                ],
+            ),
+            (
+                "TaintToleration",
+                vec![
+                    PluginType::FilterPlugin(Box::new(TaintToleration {})),
+                    PluginType::ScorePlugin(without_args::<TaintToleration>),
+                ],
+            ),
+            (
+                "NodeUnschedulable",
+                vec![PluginType::FilterPlugin(Box::new(NodeUnschedulable {}))],
+            ),
+            (
+                "Coscheduling",
+                vec![
+                    PluginType::ReservePlugin(Box::new(Coscheduling {})),
+                    PluginType::PermitPlugin(Box::new(Coscheduling {})),
+                ],
+            ),
+            (
+                "DefaultPreemption",
+                vec![PluginType::PostFilterPlugin(Box::new(DefaultPreemption {}))],
+            ),
+            (
+                "DefaultBinder",
+                vec![PluginType::BindPlugin(Box::new(DefaultBinder {}))],
             ),
         ])
     };
 }
 
+fn find_plugin<T: ?Sized>(
+    name: &str,
+    extract: fn(&'static PluginType) -> Option<&'static T>,
+) -> Option<&'static T> {
+    PLUGIN_REGISTRY.get(name)?.iter().find_map(extract)
+}
+
+// Functions below return None if plugin is not registered or does not implement the extension
+// point.
+
+pub fn find_pre_filter_plugin(name: &str) -> Option<&'static dyn PreFilterPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::PreFilterPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_filter_plugin(name: &str) -> Option<&'static dyn FilterPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::FilterPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_post_filter_plugin(name: &str) -> Option<&'static dyn PostFilterPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::PostFilterPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_score_plugin_factory(name: &str) -> Option<ScorePluginFactory> {
+    PLUGIN_REGISTRY
+        .get(name)?
+        .iter()
+        .find_map(|plugin| match plugin {
+            PluginType::ScorePlugin(factory) => Some(*factory),
+            _ => None,
+        })
+}
+
+pub fn find_reserve_plugin(name: &str) -> Option<&'static dyn ReservePlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::ReservePlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_permit_plugin(name: &str) -> Option<&'static dyn PermitPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::PermitPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_pre_bind_plugin(name: &str) -> Option<&'static dyn PreBindPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::PreBindPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+pub fn find_bind_plugin(name: &str) -> Option<&'static dyn BindPlugin> {
+    find_plugin(name, |plugin| match plugin {
+        PluginType::BindPlugin(plugin) => Some(plugin.as_ref()),
+        _ => None,
+    })
+}
+
+/// PreFilter extension point. Plugins check whether a pod may be scheduled at all and precompute
+/// data for other extension points in cycle state.
+pub trait PreFilterPlugin: Send + Sync {
+    fn pre_filter(&self, pod: &Pod, snapshot: &ClusterSnapshot, state: &mut CycleState) -> Status;
+}
+
 pub trait FilterPlugin: Send + Sync {
-    fn filter<'a>(&self, pod: &'a Pod, nodes: Vec<&'a Node>) -> Vec<&'a Node>;
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+    ) -> Vec<&'a Node>;
+
+    /// Is called for pods which were rejected by the plugin to check whether the cluster event
+    /// may make them schedulable. By default pods are retried after any event.
+    fn queueing_hint(
+        &self,
+        _pod: &Pod,
+        _event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        QueueingHint::Queue
+    }
 }
 
 pub trait ScorePlugin: Send + Sync {
-    fn score(&self, pod: &Pod, node: &Node) -> f64;
+    fn score(&self, pod: &Pod, node: &Node, snapshot: &ClusterSnapshot, state: &CycleState) -> f64;
+
+    /// NormalizeScore extension point. Is called with scores of all nodes which passed filters
+    /// after they are scored by the plugin.
+    fn normalize_scores(&self, _pod: &Pod, _scores: &mut [(&Node, f64)], _state: &CycleState) {}
+}
+
+/// PostFilter extension point. Is called when no node passes filters, plugins are tried in order
+/// until one of them returns a candidate node where the pod fits after evicting victims.
+pub trait PostFilterPlugin: Send + Sync {
+    fn post_filter(
+        &self,
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+    ) -> Option<PreemptionCandidate>;
+}
+
+/// Reserve extension point. Is called after node resources are reserved for a pod in scheduler
+/// cache. If reserve of some plugin fails or the pod is not bound later, unreserve of all reserve
+/// plugins is called in reverse order. Unreserve may reject other waiting pods.
+pub trait ReservePlugin: Send + Sync {
+    fn reserve(&self, pod: &Pod, node_name: &str, state: &mut CycleState) -> Status;
+
+    fn unreserve(
+        &self,
+        pod: &Pod,
+        node_name: &str,
+        snapshot: &ClusterSnapshot,
+        state: &mut CycleState,
+        waiting_pods: &mut WaitingPods,
+    );
+}
+
+/// Permit extension point. Plugin may allow binding of a pod, reject it or make it wait. Waiting
+/// pods are allowed or rejected by permit plugins through `waiting_pods` when other pods come.
+pub trait PermitPlugin: Send + Sync {
+    fn permit(
+        &self,
+        pod: &Pod,
+        node_name: &str,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+        waiting_pods: &mut WaitingPods,
+    ) -> Status;
+}
+
+/// PreBind extension point. Is called before binding, failure returns the pod to the queue.
+pub trait PreBindPlugin: Send + Sync {
+    fn pre_bind(&self, pod: &Pod, node_name: &str, state: &CycleState) -> Status;
+}
+
+/// Bind extension point. Bind plugins are tried in order until one of them does not skip the pod.
+pub trait BindPlugin: Send + Sync {
+    fn bind(&self, pod: &Pod, node_name: &str, state: &CycleState) -> Status;
+}
+
+/// Score plugin which is deserialized from its arguments. Missing arguments mean default plugin.
+pub trait ConfigurableScorePlugin: ScorePlugin + DeserializeOwned + Default + 'static {
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn from_args<P: ConfigurableScorePlugin>(
+    args: Option<&serde_yaml::Value>,
+) -> Result<Box<dyn ScorePlugin>, String> {
+    let plugin = match args {
+        Some(args) => serde_yaml::from_value::<P>(args.clone()).map_err(|err| err.to_string())?,
+        None => P::default(),
+    };
+    plugin.validate()?;
+    Ok(Box::new(plugin))
+}
+
+fn without_args<P: ScorePlugin + Default + 'static>(
+    args: Option<&serde_yaml::Value>,
+) -> Result<Box<dyn ScorePlugin>, String> {
+    match args {
+        Some(_) => Err("plugin does not accept args".to_string()),
+        None => Ok(Box::new(P::default())),
+    }
 }
 
 // Fit is a plugin that checks if a node has sufficient resources.
 pub struct Fit {}
 impl FilterPlugin for Fit {
-    fn filter<'a>(&self, pod: &'a Pod, nodes: Vec<&'a Node>) -> Vec<&'a Node> {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Vec<&'a Node> {
         nodes
             .into_iter()
-            .filter(|&node| {
-                pod.spec.resources.requests.cpu <= node.status.allocatable.cpu
-                    && pod.spec.resources.requests.ram <= node.status.allocatable.ram
-            })
+            .filter(|&node| pod_fits_node_resources(pod, node))
             .collect()
     }
+
+    // Only new nodes and released resources matter, node updates do not change allocatable
+    // resources.
+    fn queueing_hint(
+        &self,
+        pod: &Pod,
+        event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        match event {
+            ClusterEvent::NodeAdded(node) | ClusterEvent::AssignedPodDeleted { node, .. } => {
+                QueueingHint::queue_if(pod_fits_node_resources(pod, node))
+            }
+            ClusterEvent::NodeUpdated(_) | ClusterEvent::AssignedPodAdded { .. } => {
+                QueueingHint::Skip
+            }
+        }
+    }
+}
+
+pub fn pod_fits_node_resources(pod: &Pod, node: &Node) -> bool {
+    pod.spec.resources.requests.cpu <= node.status.allocatable.cpu
+        && pod.spec.resources.requests.ram <= node.status.allocatable.ram
+}
+
+fn default_resource_weight() -> f64 {
+    1.0
+}
+
+/// Weights of resources in scores of resource based plugins. Weights for cpu and memory are equal
+/// by default.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceWeights {
+    #[serde(default = "default_resource_weight")]
+    pub cpu: f64,
+    #[serde(default = "default_resource_weight")]
+    pub ram: f64,
+}
+
+impl Default for ResourceWeights {
+    fn default() -> Self {
+        Self {
+            cpu: default_resource_weight(),
+            ram: default_resource_weight(),
+        }
+    }
+}
+
+impl ResourceWeights {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.cpu >= 0.0 && self.ram >= 0.0 && self.cpu + self.ram > 0.0) {
+            return Err(format!(
+                "resource weights must be non-negative with positive sum, got cpu {} and ram {}",
+                self.cpu, self.ram
+            ));
+        }
+        Ok(())
+    }
+
+    fn weighted_average(&self, cpu_value: f64, ram_value: f64) -> f64 {
+        (cpu_value * self.cpu + ram_value * self.ram) / (self.cpu + self.ram)
+    }
+}
+
+/// Fractions of node capacity of cpu and ram which are requested by assigned pods together with
+/// the pod if it is placed to the node.
+fn requested_fractions(pod: &Pod, node: &Node) -> (f64, f64) {
+    let fraction = |capacity: u64, allocatable: u64, request: u64| {
+        if capacity == 0 {
+            return 1.0;
+        }
+        let requested = capacity.saturating_sub(allocatable) + request;
+        (requested as f64 / capacity as f64).min(1.0)
+    };
+    let capacity = &node.status.capacity;
+    let allocatable = &node.status.allocatable;
+    let requests = &pod.spec.resources.requests;
+    (
+        fraction(
+            capacity.cpu as u64,
+            allocatable.cpu as u64,
+            requests.cpu as u64,
+        ),
+        fraction(capacity.ram, allocatable.ram, requests.ram),
+    )
 }
 
 // Least requested resources plugin is a score plugin. Its score means that after subtracting pod's
 // requested resources from node's allocatable resources, the node with the highest
 // percentage (relatively to current allocatable) is prioritized for scheduling.
-//
-// Weights for cpu and memory are equal by default.
-pub struct LeastAllocatedResources {}
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LeastAllocatedResources {
+    #[serde(default)]
+    pub resources: ResourceWeights,
+}
 impl ScorePlugin for LeastAllocatedResources {
-    fn score(&self, pod: &Pod, node: &Node) -> f64 {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
         let cpu_score = (node.status.allocatable.cpu - pod.spec.resources.requests.cpu) as f64
             * 100.0
             / node.status.allocatable.cpu as f64;
         let ram_score = (node.status.allocatable.ram - pod.spec.resources.requests.ram) as f64
             * 100.0
             / node.status.allocatable.ram as f64;
-        (cpu_score + ram_score) / 2.0
+        self.resources.weighted_average(cpu_score, ram_score)
+    }
+}
+
+impl ConfigurableScorePlugin for LeastAllocatedResources {
+    fn validate(&self) -> Result<(), String> {
+        self.resources.validate()
+    }
+}
+
+// MostAllocated is a score plugin which favors nodes with the highest share of requested
+// resources of node capacity after placing the pod. It packs pods tightly, so that fewer nodes are
+// used and the rest can be scaled down by cluster autoscaler.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MostAllocated {
+    #[serde(default)]
+    pub resources: ResourceWeights,
+}
+impl ScorePlugin for MostAllocated {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
+        let (cpu_fraction, ram_fraction) = requested_fractions(pod, node);
+        MAX_NODE_SCORE * self.resources.weighted_average(cpu_fraction, ram_fraction)
+    }
+}
+
+impl ConfigurableScorePlugin for MostAllocated {
+    fn validate(&self) -> Result<(), String> {
+        self.resources.validate()
+    }
+}
+
+// BalancedAllocation is a score plugin which favors nodes with balanced usage of resources after
+// placing the pod. Score is MAX_NODE_SCORE multiplied by one minus weighted standard deviation of
+// requested fractions of cpu and ram.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalancedAllocation {
+    #[serde(default)]
+    pub resources: ResourceWeights,
+}
+impl ScorePlugin for BalancedAllocation {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
+        let (cpu_fraction, ram_fraction) = requested_fractions(pod, node);
+        let mean = self.resources.weighted_average(cpu_fraction, ram_fraction);
+        let variance = self
+            .resources
+            .weighted_average((cpu_fraction - mean).powi(2), (ram_fraction - mean).powi(2));
+        MAX_NODE_SCORE * (1.0 - variance.sqrt())
+    }
+}
+
+impl ConfigurableScorePlugin for BalancedAllocation {
+    fn validate(&self) -> Result<(), String> {
+        self.resources.validate()
+    }
+}
+
+/// Point of a piecewise linear function which maps utilization of a resource to a score.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct UtilizationShapePoint {
+    /// Requested share of resource capacity in percents, from 0 to 100.
+    pub utilization: f64,
+    /// Score from 0 to 10 like in kube-scheduler, it is scaled to MAX_NODE_SCORE.
+    pub score: f64,
+}
+
+fn default_shape() -> Vec<UtilizationShapePoint> {
+    vec![
+        UtilizationShapePoint {
+            utilization: 0.0,
+            score: 0.0,
+        },
+        UtilizationShapePoint {
+            utilization: 100.0,
+            score: 10.0,
+        },
+    ]
+}
+
+// RequestedToCapacityRatio is a score plugin which maps requested fraction of each resource after
+// placing the pod to a score with a configured shape function. Default shape favors the most
+// allocated nodes, shape with decreasing scores favors the least allocated ones.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestedToCapacityRatio {
+    /// Points sorted by utilization, score is interpolated linearly between them and is constant
+    /// outside of them.
+    #[serde(default = "default_shape")]
+    pub shape: Vec<UtilizationShapePoint>,
+    #[serde(default)]
+    pub resources: ResourceWeights,
+}
+
+impl Default for RequestedToCapacityRatio {
+    fn default() -> Self {
+        Self {
+            shape: default_shape(),
+            resources: Default::default(),
+        }
+    }
+}
+
+impl RequestedToCapacityRatio {
+    fn shape_score(&self, utilization: f64) -> f64 {
+        let first = &self.shape[0];
+        if utilization <= first.utilization {
+            return first.score;
+        }
+        for points in self.shape.windows(2) {
+            let (left, right) = (&points[0], &points[1]);
+            if utilization <= right.utilization {
+                return left.score
+                    + (right.score - left.score) * (utilization - left.utilization)
+                        / (right.utilization - left.utilization);
+            }
+        }
+        self.shape.last().unwrap().score
+    }
+}
+
+impl ScorePlugin for RequestedToCapacityRatio {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
+        let (cpu_fraction, ram_fraction) = requested_fractions(pod, node);
+        let scale = MAX_NODE_SCORE / 10.0;
+        self.resources.weighted_average(
+            self.shape_score(cpu_fraction * 100.0) * scale,
+            self.shape_score(ram_fraction * 100.0) * scale,
+        )
+    }
+}
+
+impl ConfigurableScorePlugin for RequestedToCapacityRatio {
+    fn validate(&self) -> Result<(), String> {
+        if self.shape.is_empty() {
+            return Err("shape must have at least one point".to_string());
+        }
+        for point in self.shape.iter() {
+            if !(0.0..=100.0).contains(&point.utilization) || !(0.0..=10.0).contains(&point.score) {
+                return Err(format!(
+                    "shape point {:?} is out of range, utilization must be in [0, 100] and score in [0, 10]",
+                    point
+                ));
+            }
+        }
+        if self
+            .shape
+            .windows(2)
+            .any(|points| points[0].utilization >= points[1].utilization)
+        {
+            return Err(
+                "shape points must be sorted by strictly increasing utilization".to_string(),
+            );
+        }
+        self.resources.validate()
+    }
+}
+
+/// Checks that pod tolerates all NoSchedule and NoExecute taints of the node.
+pub fn pod_tolerates_node_taints(pod: &Pod, node: &Node) -> bool {
+    node.spec
+        .taints
+        .iter()
+        .filter(|taint| taint.effect != TaintEffect::PreferNoSchedule)
+        .all(|taint| taint.is_tolerated_by(&pod.spec.tolerations))
+}
+
+// TaintToleration is a filter and score plugin. As a filter it checks that a pod tolerates
+// NoSchedule and NoExecute taints of a node. As a score plugin it prefers nodes with fewer
+// PreferNoSchedule taints which are not tolerated by the pod.
+#[derive(Default)]
+pub struct TaintToleration {}
+impl FilterPlugin for TaintToleration {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Vec<&'a Node> {
+        nodes
+            .into_iter()
+            .filter(|&node| pod_tolerates_node_taints(pod, node))
+            .collect()
+    }
+
+    fn queueing_hint(
+        &self,
+        pod: &Pod,
+        event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        match event {
+            ClusterEvent::NodeAdded(node) | ClusterEvent::NodeUpdated(node) => {
+                QueueingHint::queue_if(pod_tolerates_node_taints(pod, node))
+            }
+            ClusterEvent::AssignedPodAdded { .. } | ClusterEvent::AssignedPodDeleted { .. } => {
+                QueueingHint::Skip
+            }
+        }
+    }
+}
+
+impl ScorePlugin for TaintToleration {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
+        let intolerable_taints = node
+            .spec
+            .taints
+            .iter()
+            .filter(|taint| {
+                taint.effect == TaintEffect::PreferNoSchedule
+                    && !taint.is_tolerated_by(&pod.spec.tolerations)
+            })
+            .count();
+        MAX_NODE_SCORE / (1 + intolerable_taints) as f64
+    }
+}
+
+/// Checks that the node is not cordoned or the pod tolerates it.
+pub fn pod_tolerates_node_unschedulable(pod: &Pod, node: &Node) -> bool {
+    if !node.spec.unschedulable {
+        return true;
+    }
+    Taint {
+        key: NODE_UNSCHEDULABLE_TAINT_KEY.to_string(),
+        value: Default::default(),
+        effect: TaintEffect::NoSchedule,
+    }
+    .is_tolerated_by(&pod.spec.tolerations)
+}
+
+// NodeUnschedulable is a filter plugin that rejects cordoned nodes unless a pod tolerates the
+// unschedulable taint.
+pub struct NodeUnschedulable {}
+impl FilterPlugin for NodeUnschedulable {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Vec<&'a Node> {
+        nodes
+            .into_iter()
+            .filter(|&node| pod_tolerates_node_unschedulable(pod, node))
+            .collect()
+    }
+
+    fn queueing_hint(
+        &self,
+        pod: &Pod,
+        event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        match event {
+            ClusterEvent::NodeAdded(node) | ClusterEvent::NodeUpdated(node) => {
+                QueueingHint::queue_if(pod_tolerates_node_unschedulable(pod, node))
+            }
+            ClusterEvent::AssignedPodAdded { .. } | ClusterEvent::AssignedPodDeleted { .. } => {
+                QueueingHint::Skip
+            }
+        }
+    }
+}
+
+/// Checks that node labels satisfy pod's node selector and required node affinity terms.
+pub fn pod_matches_node_affinity(pod: &Pod, node: &Node) -> bool {
+    let labels = &node.metadata.labels;
+    let node_selector_matches = pod
+        .spec
+        .node_selector
+        .iter()
+        .all(|(key, value)| labels.get(key) == Some(value));
+
+    if let Some(node_affinity) = pod.spec.affinity.node_affinity.as_ref() {
+        return node_selector_matches && node_affinity.required_terms_match(labels);
+    }
+    node_selector_matches
+}
+
+// NodeAffinity is a filter and score plugin. As a filter it checks pod's node selector and required
+// node affinity terms. As a score plugin it gives the node a share of maximal score proportional to
+// the weight of preferred node affinity terms the node matches.
+#[derive(Default)]
+pub struct NodeAffinity {}
+impl FilterPlugin for NodeAffinity {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Vec<&'a Node> {
+        nodes
+            .into_iter()
+            .filter(|&node| pod_matches_node_affinity(pod, node))
+            .collect()
+    }
+
+    fn queueing_hint(
+        &self,
+        pod: &Pod,
+        event: &ClusterEvent,
+        _snapshot: &ClusterSnapshot,
+    ) -> QueueingHint {
+        match event {
+            ClusterEvent::NodeAdded(node) | ClusterEvent::NodeUpdated(node) => {
+                QueueingHint::queue_if(pod_matches_node_affinity(pod, node))
+            }
+            ClusterEvent::AssignedPodAdded { .. } | ClusterEvent::AssignedPodDeleted { .. } => {
+                QueueingHint::Skip
+            }
+        }
+    }
+}
+
+impl ScorePlugin for NodeAffinity {
+    fn score(
+        &self,
+        pod: &Pod,
+        node: &Node,
+        _snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> f64 {
+        let node_affinity = match pod.spec.affinity.node_affinity.as_ref() {
+            Some(node_affinity) => node_affinity,
+            None => return 0.0,
+        };
+        let total_weight: u32 = node_affinity.preferred.iter().map(|term| term.weight).sum();
+        if total_weight == 0 {
+            return 0.0;
+        }
+        MAX_NODE_SCORE * node_affinity.preferred_terms_weight(&node.metadata.labels) as f64
+            / total_weight as f64
+    }
+}
+
+/// Counts assigned pods matching the label selector in each topology domain, i.e. per value of the
+/// topology key label of nodes.
+fn count_matching_pods_per_domain<'a>(
+    label_selector: &LabelSelector,
+    topology_key: &str,
+    snapshot: &ClusterSnapshot<'a>,
+) -> HashMap<&'a str, usize> {
+    let mut counts: HashMap<&str, usize> = Default::default();
+    for (node_name, pod_names) in snapshot.assignments.iter() {
+        let domain = match snapshot
+            .nodes
+            .get(node_name)
+            .and_then(|node| node.metadata.labels.get(topology_key))
+        {
+            Some(domain) => domain,
+            None => continue,
+        };
+        let matching_pods = pod_names
+            .iter()
+            .filter_map(|pod_name| snapshot.pods.get(pod_name))
+            .filter(|pod| label_selector.matches(&pod.metadata.labels))
+            .count();
+        if matching_pods > 0 {
+            *counts.entry(domain.as_str()).or_default() += matching_pods;
+        }
+    }
+    counts
+}
+
+/// Topology domains where pod cannot be placed because of required anti-affinity of already
+/// assigned pods, as a map from topology key to its values.
+fn domains_forbidden_by_assigned_pods(
+    pod: &Pod,
+    snapshot: &ClusterSnapshot,
+) -> HashMap<String, HashSet<String>> {
+    let mut forbidden: HashMap<String, HashSet<String>> = Default::default();
+    for (node_name, pod_names) in snapshot.assignments.iter() {
+        let node = match snapshot.nodes.get(node_name) {
+            Some(node) => node,
+            None => continue,
+        };
+        for assigned_pod in pod_names
+            .iter()
+            .filter_map(|pod_name| snapshot.pods.get(pod_name))
+        {
+            let anti_affinity = match assigned_pod.spec.affinity.pod_anti_affinity.as_ref() {
+                Some(anti_affinity) => anti_affinity,
+                None => continue,
+            };
+            for term in anti_affinity.required.iter() {
+                if !term.label_selector.matches(&pod.metadata.labels) {
+                    continue;
+                }
+                if let Some(domain) = node.metadata.labels.get(&term.topology_key) {
+                    forbidden
+                        .entry(term.topology_key.clone())
+                        .or_default()
+                        .insert(domain.clone());
+                }
+            }
+        }
+    }
+    forbidden
+}
+
+/// Key of precomputed InterPodAffinityState in cycle state.
+const INTER_POD_AFFINITY_STATE_KEY: &str = "InterPodAffinity";
+
+/// Counts of matching pods per topology domain for affinity terms of a pod, in the same order as
+/// the terms in pod spec, and domains forbidden by assigned pods.
+#[derive(Clone)]
+struct InterPodAffinityState {
+    required_affinity_counts: Vec<HashMap<String, usize>>,
+    required_anti_affinity_counts: Vec<HashMap<String, usize>>,
+    preferred_affinity_counts: Vec<HashMap<String, usize>>,
+    preferred_anti_affinity_counts: Vec<HashMap<String, usize>>,
+    forbidden_domains: HashMap<String, HashSet<String>>,
+}
+
+impl InterPodAffinityState {
+    fn new(pod: &Pod, snapshot: &ClusterSnapshot) -> Self {
+        let counts = |term: &PodAffinityTerm| -> HashMap<String, usize> {
+            count_matching_pods_per_domain(&term.label_selector, &term.topology_key, snapshot)
+                .into_iter()
+                .map(|(domain, count)| (domain.to_string(), count))
+                .collect()
+        };
+        let pod_affinity = pod.spec.affinity.pod_affinity.iter();
+        let pod_anti_affinity = pod.spec.affinity.pod_anti_affinity.iter();
+        Self {
+            required_affinity_counts: pod_affinity
+                .clone()
+                .flat_map(|pod_affinity| pod_affinity.required.iter())
+                .map(|term| counts(term))
+                .collect(),
+            required_anti_affinity_counts: pod_anti_affinity
+                .clone()
+                .flat_map(|pod_anti_affinity| pod_anti_affinity.required.iter())
+                .map(|term| counts(term))
+                .collect(),
+            preferred_affinity_counts: pod_affinity
+                .flat_map(|pod_affinity| pod_affinity.preferred.iter())
+                .map(|term| counts(&term.pod_affinity_term))
+                .collect(),
+            preferred_anti_affinity_counts: pod_anti_affinity
+                .flat_map(|pod_anti_affinity| pod_anti_affinity.preferred.iter())
+                .map(|term| counts(&term.pod_affinity_term))
+                .collect(),
+            forbidden_domains: domains_forbidden_by_assigned_pods(pod, snapshot),
+        }
+    }
+}
+
+// InterPodAffinity is a prefilter, filter and score plugin. As a prefilter it counts matching pods
+// in topology domains for all terms once per scheduling attempt. As a filter it checks required
+// pod affinity and anti-affinity terms of a pod and required anti-affinity terms of already
+// assigned pods. As a score plugin it sums weights of preferred affinity terms multiplied by the
+// number of matching pods in the node's topology domain and subtracts the same for anti-affinity
+// terms. Scores are normalized to range from 0 to MAX_NODE_SCORE among nodes which passed filters.
+#[derive(Default)]
+pub struct InterPodAffinity {}
+impl InterPodAffinity {
+    /// State is taken from cycle state if PreFilter of the plugin is enabled and computed
+    /// otherwise.
+    fn cycle_state<'a>(
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        state: &'a CycleState,
+    ) -> Cow<'a, InterPodAffinityState> {
+        match state.read::<InterPodAffinityState>(INTER_POD_AFFINITY_STATE_KEY) {
+            Some(affinity_state) => Cow::Borrowed(affinity_state),
+            None => Cow::Owned(InterPodAffinityState::new(pod, snapshot)),
+        }
+    }
+}
+
+impl PreFilterPlugin for InterPodAffinity {
+    fn pre_filter(&self, pod: &Pod, snapshot: &ClusterSnapshot, state: &mut CycleState) -> Status {
+        state.write(
+            INTER_POD_AFFINITY_STATE_KEY,
+            InterPodAffinityState::new(pod, snapshot),
+        );
+        Status::Success
+    }
+}
+
+impl FilterPlugin for InterPodAffinity {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+    ) -> Vec<&'a Node> {
+        let affinity_state = InterPodAffinity::cycle_state(pod, snapshot, state);
+        let affinity = &pod.spec.affinity;
+
+        let required_affinity: Vec<(&PodAffinityTerm, &HashMap<String, usize>)> = affinity
+            .pod_affinity
+            .iter()
+            .flat_map(|pod_affinity| pod_affinity.required.iter())
+            .zip(affinity_state.required_affinity_counts.iter())
+            .collect();
+        let required_anti_affinity: Vec<(&PodAffinityTerm, &HashMap<String, usize>)> = affinity
+            .pod_anti_affinity
+            .iter()
+            .flat_map(|pod_anti_affinity| pod_anti_affinity.required.iter())
+            .zip(affinity_state.required_anti_affinity_counts.iter())
+            .collect();
+        let forbidden_domains = &affinity_state.forbidden_domains;
+
+        nodes
+            .into_iter()
+            .filter(|&node| {
+                let labels = &node.metadata.labels;
+
+                let affinity_satisfied = required_affinity.iter().all(|(term, counts)| {
+                    // The first pod of a group which matches its own affinity term is allowed to be
+                    // placed anywhere, otherwise pods of the group would never be scheduled.
+                    if counts.is_empty() && term.label_selector.matches(&pod.metadata.labels) {
+                        return labels.contains_key(&term.topology_key);
+                    }
+                    labels
+                        .get(&term.topology_key)
+                        .is_some_and(|domain| counts.contains_key(domain))
+                });
+
+                let anti_affinity_satisfied =
+                    required_anti_affinity.iter().all(|(term, counts)| {
+                        !labels
+                            .get(&term.topology_key)
+                            .is_some_and(|domain| counts.contains_key(domain))
+                    });
+
+                let not_forbidden = labels.iter().all(|(key, value)| {
+                    !forbidden_domains
+                        .get(key)
+                        .is_some_and(|domains| domains.contains(value))
+                });
+
+                affinity_satisfied && anti_affinity_satisfied && not_forbidden
+            })
+            .collect()
+    }
+}
+
+impl ScorePlugin for InterPodAffinity {
+    fn score(&self, pod: &Pod, node: &Node, snapshot: &ClusterSnapshot, state: &CycleState) -> f64 {
+        let affinity_state = InterPodAffinity::cycle_state(pod, snapshot, state);
+        let affinity = &pod.spec.affinity;
+
+        let preferred_affinity = affinity
+            .pod_affinity
+            .iter()
+            .flat_map(|pod_affinity| pod_affinity.preferred.iter())
+            .zip(affinity_state.preferred_affinity_counts.iter())
+            .map(|(term, counts)| (term.weight as i64, &term.pod_affinity_term, counts));
+        let preferred_anti_affinity = affinity
+            .pod_anti_affinity
+            .iter()
+            .flat_map(|pod_anti_affinity| pod_anti_affinity.preferred.iter())
+            .zip(affinity_state.preferred_anti_affinity_counts.iter())
+            .map(|(term, counts)| (-(term.weight as i64), &term.pod_affinity_term, counts));
+
+        preferred_affinity
+            .chain(preferred_anti_affinity)
+            .map(|(weight, term, counts)| {
+                node.metadata
+                    .labels
+                    .get(&term.topology_key)
+                    .and_then(|domain| counts.get(domain))
+                    .map_or(0, |count| weight * *count as i64)
+            })
+            .sum::<i64>() as f64
+    }
+
+    fn normalize_scores(&self, _pod: &Pod, scores: &mut [(&Node, f64)], _state: &CycleState) {
+        let (min_score, max_score) = scores.iter().fold(
+            (f64::MAX, f64::MIN),
+            |(min_score, max_score), (_, score)| (min_score.min(*score), max_score.max(*score)),
+        );
+        for (_, score) in scores.iter_mut() {
+            *score = if max_score > min_score {
+                MAX_NODE_SCORE * (*score - min_score) / (max_score - min_score)
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// Key of counts of matching pods per eligible domain for each topology spread constraint of a
+/// pod in cycle state. Counts are in the same order as constraints.
+const POD_TOPOLOGY_SPREAD_STATE_KEY: &str = "PodTopologySpread";
+
+type DomainCounts = Vec<HashMap<String, usize>>;
+
+impl PodTopologySpread {
+    /// Counts matching pods in each topology domain of the constraint. Only domains of nodes which
+    /// satisfy pod's node selector and node affinity are eligible, such domains without matching
+    /// pods have zero count.
+    fn domain_counts(
+        pod: &Pod,
+        constraint: &TopologySpreadConstraint,
+        snapshot: &ClusterSnapshot,
+    ) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> = snapshot
+            .nodes
+            .values()
+            .filter(|node| pod_matches_node_affinity(pod, node))
+            .filter_map(|node| node.metadata.labels.get(&constraint.topology_key))
+            .map(|domain| (domain.clone(), 0))
+            .collect();
+        for (domain, count) in count_matching_pods_per_domain(
+            &constraint.label_selector,
+            &constraint.topology_key,
+            snapshot,
+        ) {
+            if let Some(domain_count) = counts.get_mut(domain) {
+                *domain_count = count;
+            }
+        }
+        counts
+    }
+
+    fn all_domain_counts(pod: &Pod, snapshot: &ClusterSnapshot) -> DomainCounts {
+        pod.spec
+            .topology_spread_constraints
+            .iter()
+            .map(|constraint| PodTopologySpread::domain_counts(pod, constraint, snapshot))
+            .collect()
+    }
+
+    /// Domain counts of all constraints, which are taken from cycle state if PreFilter of the
+    /// plugin is enabled and computed otherwise.
+    fn cycle_domain_counts<'a>(
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        state: &'a CycleState,
+    ) -> Cow<'a, DomainCounts> {
+        match state.read::<DomainCounts>(POD_TOPOLOGY_SPREAD_STATE_KEY) {
+            Some(all_counts) => Cow::Borrowed(all_counts),
+            None => Cow::Owned(PodTopologySpread::all_domain_counts(pod, snapshot)),
+        }
+    }
+
+    /// Constraints with the given action together with their domain counts.
+    fn constraints_with_counts<'a>(
+        pod: &'a Pod,
+        action: UnsatisfiableConstraintAction,
+        all_counts: &'a DomainCounts,
+    ) -> Vec<(&'a TopologySpreadConstraint, &'a HashMap<String, usize>)> {
+        pod.spec
+            .topology_spread_constraints
+            .iter()
+            .zip(all_counts.iter())
+            .filter(|(constraint, _)| constraint.when_unsatisfiable == action)
+            .collect()
+    }
+}
+
+// PodTopologySpread is a prefilter, filter and score plugin. As a prefilter it counts matching pods
+// in topology domains once per scheduling attempt. As a filter it checks DoNotSchedule topology
+// spread constraints of a pod: placing the pod to a node must not make the difference between the
+// number of matching pods in the node's domain and the minimal number among eligible domains
+// greater than max skew. As a score plugin it prefers nodes whose domains contain fewer pods
+// matching ScheduleAnyway constraints. Scores are normalized among nodes which passed filters.
+#[derive(Default)]
+pub struct PodTopologySpread {}
+impl PreFilterPlugin for PodTopologySpread {
+    fn pre_filter(&self, pod: &Pod, snapshot: &ClusterSnapshot, state: &mut CycleState) -> Status {
+        state.write(
+            POD_TOPOLOGY_SPREAD_STATE_KEY,
+            PodTopologySpread::all_domain_counts(pod, snapshot),
+        );
+        Status::Success
+    }
+}
+
+impl FilterPlugin for PodTopologySpread {
+    fn filter<'a>(
+        &self,
+        pod: &'a Pod,
+        nodes: Vec<&'a Node>,
+        snapshot: &ClusterSnapshot,
+        state: &CycleState,
+    ) -> Vec<&'a Node> {
+        let all_counts = PodTopologySpread::cycle_domain_counts(pod, snapshot, state);
+        let constraints: Vec<(&TopologySpreadConstraint, &HashMap<String, usize>, usize)> =
+            PodTopologySpread::constraints_with_counts(
+                pod,
+                UnsatisfiableConstraintAction::DoNotSchedule,
+                &all_counts,
+            )
+            .into_iter()
+            .map(|(constraint, counts)| {
+                let min_count = counts.values().min().copied().unwrap_or(0);
+                (constraint, counts, min_count)
+            })
+            .collect();
+
+        nodes
+            .into_iter()
+            .filter(|&node| {
+                constraints.iter().all(|(constraint, counts, min_count)| {
+                    let domain_count = match node
+                        .metadata
+                        .labels
+                        .get(&constraint.topology_key)
+                        .and_then(|domain| counts.get(domain))
+                    {
+                        Some(count) => *count,
+                        None => return false,
+                    };
+                    let self_match =
+                        constraint.label_selector.matches(&pod.metadata.labels) as usize;
+                    domain_count + self_match - min_count <= constraint.max_skew as usize
+                })
+            })
+            .collect()
+    }
+}
+
+impl ScorePlugin for PodTopologySpread {
+    fn score(&self, pod: &Pod, node: &Node, snapshot: &ClusterSnapshot, state: &CycleState) -> f64 {
+        let all_counts = PodTopologySpread::cycle_domain_counts(pod, snapshot, state);
+        let count: usize = PodTopologySpread::constraints_with_counts(
+            pod,
+            UnsatisfiableConstraintAction::ScheduleAnyway,
+            &all_counts,
+        )
+        .into_iter()
+        .filter_map(|(constraint, counts)| {
+            node.metadata
+                .labels
+                .get(&constraint.topology_key)
+                .and_then(|domain| counts.get(domain))
+        })
+        .sum();
+        count as f64
+    }
+
+    fn normalize_scores(&self, pod: &Pod, scores: &mut [(&Node, f64)], _state: &CycleState) {
+        let topology_keys: Vec<&String> = pod
+            .spec
+            .topology_spread_constraints
+            .iter()
+            .filter(|constraint| {
+                constraint.when_unsatisfiable == UnsatisfiableConstraintAction::ScheduleAnyway
+            })
+            .map(|constraint| &constraint.topology_key)
+            .collect();
+        if topology_keys.is_empty() {
+            return;
+        }
+        // Nodes which do not belong to domains of all constraints get zero score.
+        let in_all_domains = |node: &Node| {
+            topology_keys
+                .iter()
+                .all(|key| node.metadata.labels.contains_key(*key))
+        };
+
+        let (min_score, max_score) = scores.iter().filter(|(node, _)| in_all_domains(node)).fold(
+            (f64::MAX, f64::MIN),
+            |(min_score, max_score), (_, score)| (min_score.min(*score), max_score.max(*score)),
+        );
+        for (node, score) in scores.iter_mut() {
+            *score = if !in_all_domains(node) {
+                0.0
+            } else if max_score > min_score {
+                MAX_NODE_SCORE * (max_score - *score) / (max_score - min_score)
+            } else {
+                MAX_NODE_SCORE
+            };
+        }
+    }
+}
+
+/// Label with the name of the pod group (gang) a pod belongs to.
+pub const POD_GROUP_LABEL: &str = "pod_group";
+/// Label with the minimal number of pods of the group which must be scheduled together. Pod group
+/// without this label has min member 1.
+pub const POD_GROUP_MIN_MEMBER_LABEL: &str = "pod_group_min_member";
+/// Label with the time in seconds pods of the group wait for the rest of the group at permit
+/// extension point.
+pub const POD_GROUP_SCHEDULE_TIMEOUT_LABEL: &str = "pod_group_schedule_timeout";
+pub const DEFAULT_POD_GROUP_SCHEDULE_TIMEOUT: f64 = 60.0;
+
+const COSCHEDULING_PLUGIN_NAME: &str = "Coscheduling";
+
+/// Returns the pod group name and min member of the pod, None if the pod does not belong to a
+/// pod group.
+pub fn pod_group_of(pod: &Pod) -> Option<(&str, usize)> {
+    let pod_group = pod.metadata.labels.get(POD_GROUP_LABEL)?;
+    let min_member = pod
+        .metadata
+        .labels
+        .get(POD_GROUP_MIN_MEMBER_LABEL)
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(1);
+    Some((pod_group, min_member))
+}
+
+fn is_pod_group_member(pod: &Pod, pod_group: &str) -> bool {
+    pod.metadata
+        .labels
+        .get(POD_GROUP_LABEL)
+        .is_some_and(|name| name == pod_group)
+}
+
+// Coscheduling is a reserve and permit plugin which provides all-or-nothing placement of pod
+// groups. Pods of a group wait at permit extension point holding reserved resources until at
+// least min member pods of the group are assigned nodes, then all of them are allowed to bind.
+// If the group is not gathered before timeout, pods are rejected and release resources, as well
+// as other waiting pods of the group.
+// Pods without the pod group label are skipped.
+pub struct Coscheduling {}
+impl ReservePlugin for Coscheduling {
+    fn reserve(&self, _pod: &Pod, _node_name: &str, _state: &mut CycleState) -> Status {
+        Status::Success
+    }
+
+    fn unreserve(
+        &self,
+        pod: &Pod,
+        _node_name: &str,
+        snapshot: &ClusterSnapshot,
+        _state: &mut CycleState,
+        waiting_pods: &mut WaitingPods,
+    ) {
+        let (pod_group, _) = match pod_group_of(pod) {
+            Some(pod_group) => pod_group,
+            None => return,
+        };
+        let members: Vec<String> = waiting_pods
+            .iter()
+            .filter(|(pod_name, _)| {
+                snapshot
+                    .pods
+                    .get(*pod_name)
+                    .is_some_and(|member| is_pod_group_member(member, pod_group))
+            })
+            .map(|(pod_name, _)| pod_name.clone())
+            .collect();
+        for member in members {
+            waiting_pods.reject(
+                &member,
+                format!(
+                    "pod {:?} of pod group {:?} is rejected",
+                    pod.metadata.name, pod_group
+                ),
+            );
+        }
+    }
+}
+
+impl PermitPlugin for Coscheduling {
+    fn permit(
+        &self,
+        pod: &Pod,
+        _node_name: &str,
+        snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+        waiting_pods: &mut WaitingPods,
+    ) -> Status {
+        let (pod_group, min_member) = match pod_group_of(pod) {
+            Some(pod_group) => pod_group,
+            None => return Status::Skip,
+        };
+        // The pod itself is already assigned a node at this point. Members which are running
+        // or waiting at permit are assigned as well.
+        let assigned_members = snapshot
+            .pods
+            .values()
+            .filter(|member| {
+                is_pod_group_member(member, pod_group) && !member.status.assigned_node.is_empty()
+            })
+            .count();
+        if assigned_members < min_member {
+            let timeout = pod
+                .metadata
+                .labels
+                .get(POD_GROUP_SCHEDULE_TIMEOUT_LABEL)
+                .and_then(|value| value.parse::<f64>().ok())
+                .unwrap_or(DEFAULT_POD_GROUP_SCHEDULE_TIMEOUT);
+            return Status::Wait(timeout);
+        }
+
+        let waiting_members: Vec<String> = waiting_pods
+            .iter()
+            .filter(|(pod_name, _)| {
+                snapshot
+                    .pods
+                    .get(*pod_name)
+                    .is_some_and(|member| is_pod_group_member(member, pod_group))
+            })
+            .map(|(pod_name, _)| pod_name.clone())
+            .collect();
+        for member in waiting_members {
+            waiting_pods.allow(&member, COSCHEDULING_PLUGIN_NAME);
+        }
+        Status::Success
+    }
+}
+
+// DefaultPreemption is a postfilter plugin which evicts pods with lower priority to free place
+// for a pod which does not fit into any node. See `preemption` module for details.
+pub struct DefaultPreemption {}
+impl PostFilterPlugin for DefaultPreemption {
+    fn post_filter(
+        &self,
+        pod: &Pod,
+        snapshot: &ClusterSnapshot,
+        _state: &CycleState,
+    ) -> Option<PreemptionCandidate> {
+        select_preemption_candidate(
+            pod,
+            snapshot.nodes,
+            snapshot.pods,
+            snapshot.assignments,
+            snapshot.pending_evictions,
+        )
+    }
+}
+
+// DefaultBinder is a bind plugin which binds a pod to the node through the api server.
+pub struct DefaultBinder {}
+impl BindPlugin for DefaultBinder {
+    fn bind(&self, _pod: &Pod, _node_name: &str, _state: &CycleState) -> Status {
+        Status::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::rc::Rc;
+
+    use crate::core::affinity::{
+        LabelSelector, NodeAffinity as NodeAffinitySpec, NodeSelectorOperator,
+        NodeSelectorRequirement, NodeSelectorTerm, PodAffinity, PodAffinityTerm,
+        PreferredSchedulingTerm, TopologySpreadConstraint, UnsatisfiableConstraintAction,
+        WeightedPodAffinityTerm,
+    };
+    use crate::core::node::{Node, Taint, TaintEffect, NODE_UNSCHEDULABLE_TAINT_KEY};
+    use crate::core::pod::{Pod, Toleration, TolerationOperator};
+    use crate::core::scheduler::framework::{CycleState, Status, WaitingPod, WaitingPods};
+    use crate::core::scheduler::interface::ClusterSnapshot;
+    use crate::core::scheduler::plugin::{
+        find_score_plugin_factory, BalancedAllocation, Coscheduling, DomainCounts, FilterPlugin,
+        InterPodAffinity, MostAllocated, NodeAffinity, NodeUnschedulable, PermitPlugin,
+        PodTopologySpread, PreFilterPlugin, RequestedToCapacityRatio, ReservePlugin,
+        ResourceWeights, ScorePlugin, TaintToleration, UtilizationShapePoint,
+        DEFAULT_POD_GROUP_SCHEDULE_TIMEOUT, MAX_NODE_SCORE, POD_GROUP_LABEL,
+        POD_GROUP_MIN_MEMBER_LABEL, POD_TOPOLOGY_SPREAD_STATE_KEY,
+    };
+    use crate::core::scheduler::queue::QueuedPodInfo;
+
+    #[derive(Default)]
+    struct TestCluster {
+        nodes: BTreeMap<String, Node>,
+        pods: BTreeMap<String, Pod>,
+        assignments: HashMap<String, BTreeSet<String>>,
+        pending_evictions: HashSet<String>,
+    }
+
+    impl TestCluster {
+        fn snapshot(&self) -> ClusterSnapshot<'_> {
+            ClusterSnapshot {
+                nodes: &self.nodes,
+                pods: &self.pods,
+                assignments: &self.assignments,
+                pending_evictions: &self.pending_evictions,
+            }
+        }
+
+        fn add_node(&mut self, node: Node) {
+            self.nodes.insert(node.metadata.name.clone(), node);
+        }
+
+        fn assign_pod(&mut self, mut pod: Pod, node_name: &str) {
+            pod.status.assigned_node = node_name.to_string();
+            self.assignments
+                .entry(node_name.to_string())
+                .or_default()
+                .insert(pod.metadata.name.clone());
+            self.pods.insert(pod.metadata.name.clone(), pod);
+        }
+    }
+
+    fn tainted_node(name: &str, taints: Vec<(&str, &str, TaintEffect)>) -> Node {
+        let mut node = Node::new(name.to_string(), 4000, 4000);
+        node.spec.taints = taints
+            .into_iter()
+            .map(|(key, value, effect)| Taint {
+                key: key.to_string(),
+                value: value.to_string(),
+                effect,
+            })
+            .collect();
+        node
+    }
+
+    fn filtered_names(pod: &Pod, nodes: &[Node]) -> Vec<String> {
+        TaintToleration {}
+            .filter(
+                pod,
+                nodes.iter().collect(),
+                &TestCluster::default().snapshot(),
+                &CycleState::default(),
+            )
+            .into_iter()
+            .map(|node| node.metadata.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_taint_toleration_filter() {
+        let nodes = vec![
+            tainted_node("node_1", vec![]),
+            tainted_node(
+                "node_2",
+                vec![("dedicated", "gpu", TaintEffect::NoSchedule)],
+            ),
+            tainted_node("node_3", vec![("dedicated", "db", TaintEffect::NoExecute)]),
+            tainted_node(
+                "node_4",
+                vec![("dedicated", "gpu", TaintEffect::PreferNoSchedule)],
+            ),
+        ];
+
+        let mut pod = Pod::new("pod_1".to_string(), 1000, 1000, Some(10.0));
+        assert_eq!(vec!["node_1", "node_4"], filtered_names(&pod, &nodes));
+
+        pod.spec.tolerations = vec![Toleration {
+            key: "dedicated".to_string(),
+            value: "gpu".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(
+            vec!["node_1", "node_2", "node_4"],
+            filtered_names(&pod, &nodes)
+        );
+
+        pod.spec.tolerations = vec![Toleration {
+            key: "dedicated".to_string(),
+            operator: TolerationOperator::Exists,
+            effect: Some(TaintEffect::NoExecute),
+            ..Default::default()
+        }];
+        assert_eq!(
+            vec!["node_1", "node_3", "node_4"],
+            filtered_names(&pod, &nodes)
+        );
+
+        // empty key with Exists operator tolerates everything
+        pod.spec.tolerations = vec![Toleration {
+            operator: TolerationOperator::Exists,
+            ..Default::default()
+        }];
+        assert_eq!(
+            vec!["node_1", "node_2", "node_3", "node_4"],
+            filtered_names(&pod, &nodes)
+        );
+    }
+
+    #[test]
+    fn test_node_unschedulable_filter() {
+        let mut cordoned_node = tainted_node("node_2", vec![]);
+        cordoned_node.spec.unschedulable = true;
+        let nodes = vec![tainted_node("node_1", vec![]), cordoned_node];
+        let filtered_names = |pod: &Pod| -> Vec<String> {
+            NodeUnschedulable {}
+                .filter(
+                    pod,
+                    nodes.iter().collect(),
+                    &TestCluster::default().snapshot(),
+                    &CycleState::default(),
+                )
+                .into_iter()
+                .map(|node| node.metadata.name.clone())
+                .collect()
+        };
+
+        let mut pod = Pod::new("pod_1".to_string(), 1000, 1000, Some(10.0));
+        assert_eq!(vec!["node_1"], filtered_names(&pod));
+
+        pod.spec.tolerations = vec![Toleration {
+            key: NODE_UNSCHEDULABLE_TAINT_KEY.to_string(),
+            operator: TolerationOperator::Exists,
+            effect: Some(TaintEffect::NoSchedule),
+            ..Default::default()
+        }];
+        assert_eq!(vec!["node_1", "node_2"], filtered_names(&pod));
+    }
+
+    #[test]
+    fn test_taint_toleration_score() {
+        let node_1 = tainted_node("node_1", vec![]);
+        let node_2 = tainted_node(
+            "node_2",
+            vec![
+                ("dedicated", "gpu", TaintEffect::PreferNoSchedule),
+                ("spot", "true", TaintEffect::PreferNoSchedule),
+            ],
+        );
+
+        let mut pod = Pod::new("pod_1".to_string(), 1000, 1000, Some(10.0));
+        assert_eq!(
+            MAX_NODE_SCORE,
+            TaintToleration {}.score(
+                &pod,
+                &node_1,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+        assert!(
+            TaintToleration {}.score(
+                &pod,
+                &node_2,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            ) < MAX_NODE_SCORE
+        );
+
+        pod.spec.tolerations = vec![
+            Toleration {
+                key: "dedicated".to_string(),
+                value: "gpu".to_string(),
+                ..Default::default()
+            },
+            Toleration {
+                key: "spot".to_string(),
+                operator: TolerationOperator::Exists,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            MAX_NODE_SCORE,
+            TaintToleration {}.score(
+                &pod,
+                &node_2,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+    }
+
+    fn labeled_node(name: &str, labels: Vec<(&str, &str)>) -> Node {
+        let mut node = Node::new(name.to_string(), 4000, 4000);
+        node.metadata.labels = labels
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        node
+    }
+
+    fn zone_term(zones: &[&str]) -> NodeSelectorTerm {
+        NodeSelectorTerm {
+            match_expressions: vec![NodeSelectorRequirement {
+                key: "zone".to_string(),
+                operator: NodeSelectorOperator::In,
+                values: zones.iter().map(|zone| zone.to_string()).collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_node_affinity_filter() {
+        let nodes = [
+            labeled_node("node_1", vec![("zone", "zone_a"), ("disk", "ssd")]),
+            labeled_node("node_2", vec![("zone", "zone_b"), ("disk", "ssd")]),
+            labeled_node("node_3", vec![("zone", "zone_c"), ("disk", "hdd")]),
+        ];
+        let filtered_names = |pod: &Pod| -> Vec<String> {
+            NodeAffinity {}
+                .filter(
+                    pod,
+                    nodes.iter().collect(),
+                    &TestCluster::default().snapshot(),
+                    &CycleState::default(),
+                )
+                .into_iter()
+                .map(|node| node.metadata.name.clone())
+                .collect()
+        };
+
+        let mut pod = Pod::new("pod_1".to_string(), 1000, 1000, Some(10.0));
+        assert_eq!(vec!["node_1", "node_2", "node_3"], filtered_names(&pod));
+
+        pod.spec.node_selector = HashMap::from([("disk".to_string(), "ssd".to_string())]);
+        assert_eq!(vec!["node_1", "node_2"], filtered_names(&pod));
+
+        pod.spec.affinity.node_affinity = Some(NodeAffinitySpec {
+            required: vec![zone_term(&["zone_b", "zone_c"])],
+            preferred: vec![],
+        });
+        assert_eq!(vec!["node_2"], filtered_names(&pod));
+    }
+
+    #[test]
+    fn test_node_affinity_score() {
+        let node_1 = labeled_node("node_1", vec![("zone", "zone_a")]);
+        let node_2 = labeled_node("node_2", vec![("zone", "zone_b")]);
+        let node_3 = labeled_node("node_3", vec![("zone", "zone_c")]);
+
+        let mut pod = Pod::new("pod_1".to_string(), 1000, 1000, Some(10.0));
+        assert_eq!(
+            0.0,
+            NodeAffinity {}.score(
+                &pod,
+                &node_1,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+
+        pod.spec.affinity.node_affinity = Some(NodeAffinitySpec {
+            required: vec![],
+            preferred: vec![
+                PreferredSchedulingTerm {
+                    weight: 75,
+                    preference: zone_term(&["zone_a"]),
+                },
+                PreferredSchedulingTerm {
+                    weight: 25,
+                    preference: zone_term(&["zone_a", "zone_b"]),
+                },
+            ],
+        });
+        assert_eq!(
+            MAX_NODE_SCORE,
+            NodeAffinity {}.score(
+                &pod,
+                &node_1,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+        assert_eq!(
+            25.0,
+            NodeAffinity {}.score(
+                &pod,
+                &node_2,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+        assert_eq!(
+            0.0,
+            NodeAffinity {}.score(
+                &pod,
+                &node_3,
+                &TestCluster::default().snapshot(),
+                &CycleState::default()
+            )
+        );
+    }
+
+    fn labeled_pod(name: &str, app: &str) -> Pod {
+        let mut pod = Pod::new(name.to_string(), 1000, 1000, Some(10.0));
+        pod.metadata.labels = HashMap::from([("app".to_string(), app.to_string())]);
+        pod
+    }
+
+    fn app_zone_term(app: &str) -> PodAffinityTerm {
+        PodAffinityTerm {
+            label_selector: LabelSelector {
+                match_labels: HashMap::from([("app".to_string(), app.to_string())]),
+                match_expressions: vec![],
+            },
+            topology_key: "zone".to_string(),
+        }
+    }
+
+    // zone_a: node_1 with running web pod and node_2, zone_b: node_3, node_4 without zone label.
+    fn zoned_cluster() -> TestCluster {
+        let mut cluster = TestCluster::default();
+        cluster.add_node(labeled_node("node_1", vec![("zone", "zone_a")]));
+        cluster.add_node(labeled_node("node_2", vec![("zone", "zone_a")]));
+        cluster.add_node(labeled_node("node_3", vec![("zone", "zone_b")]));
+        cluster.add_node(labeled_node("node_4", vec![]));
+        cluster.assign_pod(labeled_pod("web_1", "web"), "node_1");
+        cluster
+    }
+
+    fn inter_pod_affinity_filtered_names(pod: &Pod, cluster: &TestCluster) -> Vec<String> {
+        InterPodAffinity {}
+            .filter(
+                pod,
+                cluster.nodes.values().collect(),
+                &cluster.snapshot(),
+                &CycleState::default(),
+            )
+            .into_iter()
+            .map(|node| node.metadata.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_inter_pod_affinity_filter() {
+        let mut cluster = zoned_cluster();
+
+        let mut pod = labeled_pod("web_2", "web");
+        assert_eq!(
+            vec!["node_1", "node_2", "node_3", "node_4"],
+            inter_pod_affinity_filtered_names(&pod, &cluster)
+        );
+
+        pod.spec.affinity.pod_anti_affinity = Some(PodAffinity {
+            required: vec![app_zone_term("web")],
+            preferred: vec![],
+        });
+        assert_eq!(
+            vec!["node_3", "node_4"],
+            inter_pod_affinity_filtered_names(&pod, &cluster)
+        );
+
+        let mut pod = labeled_pod("cache_1", "cache");
+        pod.spec.affinity.pod_affinity = Some(PodAffinity {
+            required: vec![app_zone_term("web")],
+            preferred: vec![],
+        });
+        assert_eq!(
+            vec!["node_1", "node_2"],
+            inter_pod_affinity_filtered_names(&pod, &cluster)
+        );
+
+        // no db pods in the cluster, but the first db pod may be placed to any node with zone
+        let mut pod = labeled_pod("db_1", "db");
+        pod.spec.affinity.pod_affinity = Some(PodAffinity {
+            required: vec![app_zone_term("db")],
+            preferred: vec![],
+        });
+        assert_eq!(
+            vec!["node_1", "node_2", "node_3"],
+            inter_pod_affinity_filtered_names(&pod, &cluster)
+        );
+
+        // assigned pod repels other pods with its required anti-affinity
+        let mut db_pod = labeled_pod("db_2", "db");
+        db_pod.spec.affinity.pod_anti_affinity = Some(PodAffinity {
+            required: vec![app_zone_term("web")],
+            preferred: vec![],
+        });
+        cluster.assign_pod(db_pod, "node_3");
+        assert_eq!(
+            vec!["node_1", "node_2", "node_4"],
+            inter_pod_affinity_filtered_names(&labeled_pod("web_3", "web"), &cluster)
+        );
+    }
+
+    // Scores all nodes of the cluster in the order of their names and normalizes the scores.
+    fn normalized_scores(plugin: &dyn ScorePlugin, pod: &Pod, cluster: &TestCluster) -> Vec<f64> {
+        let state = CycleState::default();
+        let mut scores: Vec<(&Node, f64)> = cluster
+            .nodes
+            .values()
+            .map(|node| (node, plugin.score(pod, node, &cluster.snapshot(), &state)))
+            .collect();
+        plugin.normalize_scores(pod, &mut scores, &state);
+        scores.into_iter().map(|(_, score)| score).collect()
+    }
+
+    #[test]
+    fn test_inter_pod_affinity_score() {
+        let cluster = zoned_cluster();
+        let scores = |pod: &Pod| normalized_scores(&InterPodAffinity {}, pod, &cluster);
+
+        let mut pod = labeled_pod("cache_1", "cache");
+        assert_eq!(vec![0.0, 0.0, 0.0, 0.0], scores(&pod));
+
+        pod.spec.affinity.pod_affinity = Some(PodAffinity {
+            required: vec![],
+            preferred: vec![WeightedPodAffinityTerm {
+                weight: 10,
+                pod_affinity_term: app_zone_term("web"),
+            }],
+        });
+        assert_eq!(vec![MAX_NODE_SCORE, MAX_NODE_SCORE, 0.0, 0.0], scores(&pod));
+
+        pod.spec.affinity.pod_affinity = None;
+        pod.spec.affinity.pod_anti_affinity = Some(PodAffinity {
+            required: vec![],
+            preferred: vec![WeightedPodAffinityTerm {
+                weight: 10,
+                pod_affinity_term: app_zone_term("web"),
+            }],
+        });
+        assert_eq!(vec![0.0, 0.0, MAX_NODE_SCORE, MAX_NODE_SCORE], scores(&pod));
+    }
+
+    fn web_spread_constraint(
+        max_skew: u32,
+        when_unsatisfiable: UnsatisfiableConstraintAction,
+    ) -> TopologySpreadConstraint {
+        TopologySpreadConstraint {
+            max_skew,
+            topology_key: "zone".to_string(),
+            when_unsatisfiable,
+            label_selector: app_zone_term("web").label_selector,
+        }
+    }
+
+    #[test]
+    fn test_pod_topology_spread_filter() {
+        let cluster = zoned_cluster();
+        let filtered_names = |pod: &Pod| -> Vec<String> {
+            PodTopologySpread {}
+                .filter(
+                    pod,
+                    cluster.nodes.values().collect(),
+                    &cluster.snapshot(),
+                    &CycleState::default(),
+                )
+                .into_iter()
+                .map(|node| node.metadata.name.clone())
+                .collect()
+        };
+
+        let mut pod = labeled_pod("web_2", "web");
+        pod.spec.topology_spread_constraints = vec![web_spread_constraint(
+            1,
+            UnsatisfiableConstraintAction::DoNotSchedule,
+        )];
+        assert_eq!(vec!["node_3"], filtered_names(&pod));
+
+        pod.spec.topology_spread_constraints[0].max_skew = 2;
+        assert_eq!(vec!["node_1", "node_2", "node_3"], filtered_names(&pod));
+
+        // zone_b is not eligible, so zone_a is the only domain and skew is always zero
+        pod.spec.topology_spread_constraints[0].max_skew = 1;
+        pod.spec.node_selector = HashMap::from([("zone".to_string(), "zone_a".to_string())]);
+        assert_eq!(vec!["node_1", "node_2"], filtered_names(&pod));
+
+        // ScheduleAnyway constraints do not filter nodes
+        let mut pod = labeled_pod("web_3", "web");
+        pod.spec.topology_spread_constraints = vec![web_spread_constraint(
+            1,
+            UnsatisfiableConstraintAction::ScheduleAnyway,
+        )];
+        assert_eq!(
+            vec!["node_1", "node_2", "node_3", "node_4"],
+            filtered_names(&pod)
+        );
+    }
+
+    #[test]
+    fn test_pod_topology_spread_score() {
+        let mut cluster = zoned_cluster();
+
+        let mut pod = labeled_pod("web_2", "web");
+        pod.spec.topology_spread_constraints = vec![web_spread_constraint(
+            1,
+            UnsatisfiableConstraintAction::ScheduleAnyway,
+        )];
+        // node_4 has no zone label
+        assert_eq!(
+            vec![0.0, 0.0, MAX_NODE_SCORE, 0.0],
+            normalized_scores(&PodTopologySpread {}, &pod, &cluster)
+        );
+
+        cluster.assign_pod(labeled_pod("web_3", "web"), "node_3");
+        assert_eq!(
+            vec![MAX_NODE_SCORE, MAX_NODE_SCORE, MAX_NODE_SCORE, 0.0],
+            normalized_scores(&PodTopologySpread {}, &pod, &cluster)
+        );
+    }
+
+    #[test]
+    fn test_pod_topology_spread_pre_filter() {
+        let mut cluster = zoned_cluster();
+        let mut pod = labeled_pod("web_2", "web");
+        pod.spec.topology_spread_constraints = vec![web_spread_constraint(
+            1,
+            UnsatisfiableConstraintAction::DoNotSchedule,
+        )];
+
+        let mut state = CycleState::default();
+        assert_eq!(
+            Status::Success,
+            PodTopologySpread {}.pre_filter(&pod, &cluster.snapshot(), &mut state)
+        );
+        assert_eq!(
+            Some(&vec![HashMap::from([
+                ("zone_a".to_string(), 1),
+                ("zone_b".to_string(), 0)
+            ])]),
+            state.read::<DomainCounts>(POD_TOPOLOGY_SPREAD_STATE_KEY)
+        );
+
+        // filter uses counts computed at prefilter even if the cluster changes
+        cluster.assign_pod(labeled_pod("web_3", "web"), "node_3");
+        let filtered_names: Vec<String> = PodTopologySpread {}
+            .filter(
+                &pod,
+                cluster.nodes.values().collect(),
+                &cluster.snapshot(),
+                &state,
+            )
+            .into_iter()
+            .map(|node| node.metadata.name.clone())
+            .collect();
+        assert_eq!(vec!["node_3"], filtered_names);
+    }
+
+    #[test]
+    fn test_inter_pod_affinity_pre_filter() {
+        let mut cluster = zoned_cluster();
+        let mut pod = labeled_pod("cache_1", "cache");
+        pod.spec.affinity.pod_affinity = Some(PodAffinity {
+            required: vec![app_zone_term("web")],
+            preferred: vec![WeightedPodAffinityTerm {
+                weight: 10,
+                pod_affinity_term: app_zone_term("web"),
+            }],
+        });
+
+        let mut state = CycleState::default();
+        assert_eq!(
+            Status::Success,
+            InterPodAffinity {}.pre_filter(&pod, &cluster.snapshot(), &mut state)
+        );
+        let affinity_state = state
+            .read::<InterPodAffinityState>(INTER_POD_AFFINITY_STATE_KEY)
+            .unwrap();
+        let web_counts = HashMap::from([("zone_a".to_string(), 1)]);
+        assert_eq!(
+            vec![web_counts.clone()],
+            affinity_state.required_affinity_counts
+        );
+        assert_eq!(vec![web_counts], affinity_state.preferred_affinity_counts);
+
+        // filter and score use counts computed at prefilter even if the cluster changes
+        cluster.assign_pod(labeled_pod("web_2", "web"), "node_3");
+        let snapshot = cluster.snapshot();
+        let filtered_names: Vec<String> = InterPodAffinity {}
+            .filter(&pod, cluster.nodes.values().collect(), &snapshot, &state)
+            .into_iter()
+            .map(|node| node.metadata.name.clone())
+            .collect();
+        assert_eq!(vec!["node_1", "node_2"], filtered_names);
+        assert_eq!(
+            0.0,
+            InterPodAffinity {}.score(&pod, &cluster.nodes["node_3"], &snapshot, &state)
+        );
+    }
+
+    // After placing the pod 75% of cpu and 50% of ram capacity are requested.
+    fn partially_allocated_node_and_pod() -> (Node, Pod) {
+        let mut node = Node::new("node".to_string(), 4000, 4000);
+        node.status.allocatable.cpu = 2000;
+        node.status.allocatable.ram = 3000;
+        (node, Pod::new("pod".to_string(), 1000, 1000, Some(10.0)))
+    }
+
+    #[test]
+    fn test_resource_allocation_scores() {
+        let (node, pod) = partially_allocated_node_and_pod();
+        let snapshot_owner = TestCluster::default();
+        let snapshot = snapshot_owner.snapshot();
+
+        assert_eq!(
+            62.5,
+            MostAllocated::default().score(&pod, &node, &snapshot, &CycleState::default())
+        );
+        let cpu_heavy_weights = ResourceWeights { cpu: 3.0, ram: 1.0 };
+        assert_eq!(
+            68.75,
+            MostAllocated {
+                resources: cpu_heavy_weights.clone()
+            }
+            .score(&pod, &node, &snapshot, &CycleState::default())
+        );
+
+        assert_eq!(
+            87.5,
+            BalancedAllocation::default().score(&pod, &node, &snapshot, &CycleState::default())
+        );
+
+        assert_eq!(
+            62.5,
+            RequestedToCapacityRatio::default().score(
+                &pod,
+                &node,
+                &snapshot,
+                &CycleState::default()
+            )
+        );
+        let shape = vec![
+            UtilizationShapePoint {
+                utilization: 0.0,
+                score: 10.0,
+            },
+            UtilizationShapePoint {
+                utilization: 50.0,
+                score: 10.0,
+            },
+            UtilizationShapePoint {
+                utilization: 100.0,
+                score: 0.0,
+            },
+        ];
+        assert_eq!(
+            75.0,
+            RequestedToCapacityRatio {
+                shape: shape.clone(),
+                resources: Default::default(),
+            }
+            .score(&pod, &node, &snapshot, &CycleState::default())
+        );
+        // cpu score is 50, ram score is 100
+        assert_eq!(
+            62.5,
+            RequestedToCapacityRatio {
+                shape,
+                resources: cpu_heavy_weights,
+            }
+            .score(&pod, &node, &snapshot, &CycleState::default())
+        );
+    }
+
+    #[test]
+    fn test_score_plugin_args() {
+        let create = |name: &str, args: Option<&str>| {
+            let args = args.map(|args| serde_yaml::from_str::<serde_yaml::Value>(args).unwrap());
+            find_score_plugin_factory(name).unwrap()(args.as_ref())
+        };
+
+        assert!(create("MostAllocated", None).is_ok());
+        assert!(create("MostAllocated", Some("resources: {cpu: 2.0}")).is_ok());
+        assert!(create("MostAllocated", Some("resources: {cpu: 0.0, ram: 0.0}")).is_err());
+        assert!(create("MostAllocated", Some("resources: {gpu: 1.0}")).is_err());
+        assert!(create("BalancedAllocation", Some("weights: {cpu: 1.0}")).is_err());
+
+        assert!(create(
+            "RequestedToCapacityRatio",
+            Some("shape: [{utilization: 0, score: 10}, {utilization: 100, score: 0}]")
+        )
+        .is_ok());
+        assert!(create(
+            "RequestedToCapacityRatio",
+            Some("shape: [{utilization: 100, score: 0}, {utilization: 0, score: 10}]")
+        )
+        .is_err());
+        assert!(create(
+            "RequestedToCapacityRatio",
+            Some("shape: [{utilization: 50, score: 100}]")
+        )
+        .is_err());
+        assert!(create("RequestedToCapacityRatio", Some("shape: []")).is_err());
+
+        assert!(create("NodeAffinity", None).is_ok());
+        assert!(create("NodeAffinity", Some("resources: {cpu: 1.0}")).is_err());
+    }
+
+    fn pod_group_member(name: &str, min_member: usize) -> Pod {
+        let mut pod = Pod::new(name.to_string(), 1000, 1000, Some(10.0));
+        pod.metadata.labels = HashMap::from([
+            (POD_GROUP_LABEL.to_string(), "gang".to_string()),
+            (
+                POD_GROUP_MIN_MEMBER_LABEL.to_string(),
+                min_member.to_string(),
+            ),
+        ]);
+        pod
+    }
+
+    fn waiting_for_coscheduling(pod_name: &str, node_name: &str) -> WaitingPod {
+        WaitingPod {
+            node_name: node_name.to_string(),
+            pending_plugins: BTreeSet::from(["Coscheduling".to_string()]),
+            rejection_reason: None,
+            wait_start_time: 0.0,
+            deadline: DEFAULT_POD_GROUP_SCHEDULE_TIMEOUT,
+            timeout_event_id: 0,
+            state: CycleState::default(),
+            queued_pod_info: QueuedPodInfo {
+                timestamp: 0.0,
+                attempts: 1,
+                initial_attempt_timestamp: 0.0,
+                pod_name: Rc::new(pod_name.to_string()),
+                priority: 0,
+                unschedulable_plugins: Default::default(),
+            },
+            pod_schedule_time: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_coscheduling_permit() {
+        let mut cluster = TestCluster::default();
+        cluster.add_node(Node::new("node_1".to_string(), 4000, 4000));
+        let mut waiting_pods = WaitingPods::default();
+        let permit = |pod_name: &str, cluster: &mut TestCluster, waiting_pods: &mut WaitingPods| {
+            cluster.assign_pod(pod_group_member(pod_name, 3), "node_1");
+            Coscheduling {}.permit(
+                &cluster.pods[pod_name],
+                "node_1",
+                &cluster.snapshot(),
+                &CycleState::default(),
+                waiting_pods,
+            )
+        };
+
+        // pods without pod group are not affected
+        assert_eq!(
+            Status::Skip,
+            Coscheduling {}.permit(
+                &labeled_pod("web_1", "web"),
+                "node_1",
+                &cluster.snapshot(),
+                &CycleState::default(),
+                &mut waiting_pods,
+            )
+        );
+
+        for pod_name in ["gang_1", "gang_2"] {
+            assert_eq!(
+                Status::Wait(DEFAULT_POD_GROUP_SCHEDULE_TIMEOUT),
+                permit(pod_name, &mut cluster, &mut waiting_pods)
+            );
+            waiting_pods.insert(
+                pod_name.to_string(),
+                waiting_for_coscheduling(pod_name, "node_1"),
+            );
+        }
+        assert!(waiting_pods.take_resolved().is_empty());
+
+        // the last member allows the whole group
+        assert_eq!(
+            Status::Success,
+            permit("gang_3", &mut cluster, &mut waiting_pods)
+        );
+        let allowed: Vec<String> = waiting_pods
+            .take_resolved()
+            .into_iter()
+            .filter(|(_, waiting_pod)| waiting_pod.is_allowed())
+            .map(|(pod_name, _)| pod_name)
+            .collect();
+        assert_eq!(vec!["gang_1", "gang_2"], allowed);
+    }
+
+    #[test]
+    fn test_coscheduling_unreserve_rejects_pod_group() {
+        let mut cluster = TestCluster::default();
+        let mut waiting_pods = WaitingPods::default();
+        for pod_name in ["gang_1", "gang_2"] {
+            cluster.assign_pod(pod_group_member(pod_name, 3), "node_1");
+            waiting_pods.insert(
+                pod_name.to_string(),
+                waiting_for_coscheduling(pod_name, "node_1"),
+            );
+        }
+        cluster.assign_pod(labeled_pod("web_1", "web"), "node_1");
+        waiting_pods.insert(
+            "web_1".to_string(),
+            waiting_for_coscheduling("web_1", "node_1"),
+        );
+
+        Coscheduling {}.unreserve(
+            &cluster.pods["gang_1"],
+            "node_1",
+            &cluster.snapshot(),
+            &mut CycleState::default(),
+            &mut waiting_pods,
+        );
+        let rejected: Vec<String> = waiting_pods
+            .take_resolved()
+            .into_iter()
+            .map(|(pod_name, _)| pod_name)
+            .collect();
+        assert_eq!(vec!["gang_1", "gang_2"], rejected);
+        assert!(waiting_pods.get("web_1").is_some());
     }
 }
@@ -0,0 +1,401 @@
+//! PostFilter-style preemption which is run by the scheduler when a pod does not fit into any node.
+//! Searches a node where the pod would fit if some pods with lower priority were evicted and picks
+//! the minimal set of such victims.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::core::common::RuntimeResources;
+use crate::core::node::Node;
+use crate::core::pod::{Pod, PreemptionPolicy, QoSClass};
+use crate::core::scheduler::plugin::{pod_matches_node_affinity, pod_tolerates_node_taints};
+
+#[derive(Debug, PartialEq)]
+pub struct PreemptionCandidate {
+    pub node_name: String,
+    /// Names of pods which should be evicted from the node to free place for the preemptor.
+    /// Empty if pods which are already being evicted from the node free enough resources.
+    pub victims: Vec<String>,
+}
+
+fn fits(requests: &RuntimeResources, free: &RuntimeResources) -> bool {
+    requests.cpu <= free.cpu && requests.ram <= free.ram
+}
+
+fn add_resources(free: &mut RuntimeResources, requests: &RuntimeResources) {
+    free.cpu += requests.cpu;
+    free.ram += requests.ram;
+}
+
+fn subtract_resources(free: &mut RuntimeResources, requests: &RuntimeResources) {
+    free.cpu -= requests.cpu;
+    free.ram -= requests.ram;
+}
+
+/// Selects victims on a node in the way kube-scheduler does: firstly all pods with lower priority
+/// are considered to be removed, then pods are reprieved one by one starting from the pod with
+/// the highest priority while the preemptor still fits. Among pods with equal priority the ones
+/// with more protected QoS class are reprieved first.
+/// Returns None if the preemptor does not fit into the node even if all lower priority pods are
+/// evicted.
+fn select_victims_on_node(
+    preemptor: &Pod,
+    node: &Node,
+    assigned_pods: Vec<&Pod>,
+    pending_evictions: &HashSet<String>,
+) -> Option<Vec<String>> {
+    let requests = &preemptor.spec.resources.requests;
+    let mut free = node.status.allocatable.clone();
+    let mut potential_victims: Vec<&Pod> = vec![];
+
+    for pod in assigned_pods.into_iter() {
+        if pending_evictions.contains(&pod.metadata.name) {
+            // Resources of pods being evicted will be freed soon.
+            add_resources(&mut free, &pod.spec.resources.requests);
+        } else if pod.spec.priority < preemptor.spec.priority {
+            add_resources(&mut free, &pod.spec.resources.requests);
+            potential_victims.push(pod);
+        }
+    }
+
+    if !fits(requests, &free) {
+        return None;
+    }
+
+    // Try to reprieve pods with higher priority first.
+    potential_victims.sort_by(|lhs, rhs| {
+        rhs.spec
+            .priority
+            .cmp(&lhs.spec.priority)
+            .then(rhs.status.qos_class.cmp(&lhs.status.qos_class))
+            .then(lhs.metadata.name.cmp(&rhs.metadata.name))
+    });
+
+    let mut victims: Vec<String> = vec![];
+    for pod in potential_victims.into_iter() {
+        let mut free_with_pod = free.clone();
+        subtract_resources(&mut free_with_pod, &pod.spec.resources.requests);
+        if fits(requests, &free_with_pod) {
+            free = free_with_pod;
+            continue;
+        }
+        victims.push(pod.metadata.name.clone());
+    }
+
+    Some(victims)
+}
+
+/// Finds a node for preemption. Among all nodes where the preemptor fits after evictions, the node
+/// is chosen with (in order): the lowest highest priority of victims, the lowest sum of victims'
+/// priorities, the least number of victims, the least protected QoS class of victims. Ties are
+/// resolved in the order of node names.
+///
+/// `pending_evictions` are names of pods which are already requested to be evicted, they are not
+/// chosen as victims again and their resources are considered free.
+pub fn select_preemption_candidate(
+    preemptor: &Pod,
+    nodes: &BTreeMap<String, Node>,
+    pods: &BTreeMap<String, Pod>,
+    assignments: &HashMap<String, BTreeSet<String>>,
+    pending_evictions: &HashSet<String>,
+) -> Option<PreemptionCandidate> {
+    if preemptor.spec.preemption_policy == PreemptionPolicy::Never {
+        return None;
+    }
+
+    let mut best_candidate: Option<(PreemptionCandidate, (i32, i64, usize, Option<QoSClass>))> =
+        None;
+
+    for (node_name, node) in nodes.iter() {
+        // Evictions do not help if the preemptor does not tolerate node taints or does not match
+        // node affinity.
+        if !pod_tolerates_node_taints(preemptor, node)
+            || !pod_matches_node_affinity(preemptor, node)
+        {
+            continue;
+        }
+
+        let assigned_pods = match assignments.get(node_name) {
+            Some(pod_names) => pod_names
+                .iter()
+                .filter_map(|pod_name| pods.get(pod_name))
+                .collect(),
+            None => vec![],
+        };
+
+        let victims =
+            match select_victims_on_node(preemptor, node, assigned_pods, pending_evictions) {
+                Some(victims) => victims,
+                None => continue,
+            };
+
+        let victim_priorities = victims
+            .iter()
+            .map(|pod_name| pods.get(pod_name).unwrap().spec.priority);
+        let rank = (
+            victim_priorities.clone().max().unwrap_or(i32::MIN),
+            victim_priorities
+                .map(|priority| priority as i64)
+                .sum::<i64>(),
+            victims.len(),
+            victims
+                .iter()
+                .map(|pod_name| pods.get(pod_name).unwrap().status.qos_class.clone())
+                .max(),
+        );
+
+        if best_candidate.is_none() || rank < best_candidate.as_ref().unwrap().1 {
+            best_candidate = Some((
+                PreemptionCandidate {
+                    node_name: node_name.clone(),
+                    victims,
+                },
+                rank,
+            ));
+        }
+    }
+
+    best_candidate.map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+    use crate::core::node::Node;
+    use crate::core::pod::{Pod, PreemptionPolicy, QoSClass};
+    use crate::core::scheduler::preemption::{select_preemption_candidate, PreemptionCandidate};
+
+    fn pod_with_priority(name: &str, cpu: u32, ram: u64, priority: i32) -> Pod {
+        let mut pod = Pod::new(name.to_string(), cpu, ram, Some(10.0));
+        pod.spec.priority = priority;
+        pod
+    }
+
+    fn burstable_pod_with_priority(name: &str, cpu: u32, ram: u64, priority: i32) -> Pod {
+        let mut pod = pod_with_priority(name, cpu, ram, priority);
+        pod.spec.resources.limits.cpu = 0;
+        pod.status.qos_class = pod.spec.resources.qos_class();
+        assert_eq!(QoSClass::Burstable, pod.status.qos_class);
+        pod
+    }
+
+    type ClusterState = (
+        BTreeMap<String, Node>,
+        BTreeMap<String, Pod>,
+        HashMap<String, BTreeSet<String>>,
+    );
+
+    // Builds scheduler-like cache state where pods occupy node resources.
+    fn cluster_state(nodes: Vec<Node>, placement: Vec<(&str, Pod)>) -> ClusterState {
+        let mut nodes: BTreeMap<String, Node> = nodes
+            .into_iter()
+            .map(|node| (node.metadata.name.clone(), node))
+            .collect();
+        let mut pods: BTreeMap<String, Pod> = Default::default();
+        let mut assignments: HashMap<String, BTreeSet<String>> = Default::default();
+
+        for (node_name, mut pod) in placement.into_iter() {
+            let node = nodes.get_mut(node_name).unwrap();
+            node.status.allocatable.cpu -= pod.spec.resources.requests.cpu;
+            node.status.allocatable.ram -= pod.spec.resources.requests.ram;
+            pod.status.assigned_node = node_name.to_string();
+            assignments
+                .entry(node_name.to_string())
+                .or_default()
+                .insert(pod.metadata.name.clone());
+            pods.insert(pod.metadata.name.clone(), pod);
+        }
+
+        (nodes, pods, assignments)
+    }
+
+    #[test]
+    fn test_no_preemption_of_pods_with_equal_or_higher_priority() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![Node::new("node_1".to_string(), 4000, 4000)],
+            vec![
+                ("node_1", pod_with_priority("pod_1", 2000, 2000, 10)),
+                ("node_1", pod_with_priority("pod_2", 2000, 2000, 20)),
+            ],
+        );
+        let preemptor = pod_with_priority("preemptor", 2000, 2000, 10);
+
+        assert_eq!(
+            None,
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_preemption_policy_never() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![Node::new("node_1".to_string(), 4000, 4000)],
+            vec![("node_1", pod_with_priority("pod_1", 4000, 4000, 0))],
+        );
+        let mut preemptor = pod_with_priority("preemptor", 2000, 2000, 100);
+        preemptor.spec.preemption_policy = PreemptionPolicy::Never;
+
+        assert_eq!(
+            None,
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_minimal_set_of_victims_is_chosen() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![Node::new("node_1".to_string(), 6000, 6000)],
+            vec![
+                ("node_1", pod_with_priority("pod_1", 2000, 2000, 1)),
+                ("node_1", pod_with_priority("pod_2", 2000, 2000, 5)),
+                ("node_1", pod_with_priority("pod_3", 2000, 2000, 3)),
+            ],
+        );
+        let preemptor = pod_with_priority("preemptor", 4000, 4000, 10);
+
+        // pod_2 with the highest priority is reprieved first
+        assert_eq!(
+            Some(PreemptionCandidate {
+                node_name: "node_1".to_string(),
+                victims: vec!["pod_3".to_string(), "pod_1".to_string()],
+            }),
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_node_with_lowest_priority_victims_is_chosen() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![
+                Node::new("node_1".to_string(), 2000, 2000),
+                Node::new("node_2".to_string(), 2000, 2000),
+                Node::new("node_3".to_string(), 2000, 2000),
+            ],
+            vec![
+                ("node_1", pod_with_priority("pod_1", 2000, 2000, 5)),
+                ("node_2", pod_with_priority("pod_2", 1000, 1000, 1)),
+                ("node_2", pod_with_priority("pod_3", 1000, 1000, 1)),
+                ("node_3", pod_with_priority("pod_4", 2000, 2000, 1)),
+            ],
+        );
+        let preemptor = pod_with_priority("preemptor", 2000, 2000, 10);
+
+        // node_2 and node_3 have the same highest victim priority, but node_3 has less victims
+        // priority sum.
+        assert_eq!(
+            Some(PreemptionCandidate {
+                node_name: "node_3".to_string(),
+                victims: vec!["pod_4".to_string()],
+            }),
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_pending_evictions_are_not_chosen_again() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![Node::new("node_1".to_string(), 2000, 2000)],
+            vec![("node_1", pod_with_priority("pod_1", 2000, 2000, 1))],
+        );
+        let preemptor = pod_with_priority("preemptor", 2000, 2000, 10);
+
+        assert_eq!(
+            Some(PreemptionCandidate {
+                node_name: "node_1".to_string(),
+                victims: vec![],
+            }),
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &HashSet::from(["pod_1".to_string()])
+            )
+        );
+    }
+
+    #[test]
+    fn test_pods_with_more_protected_qos_class_are_reprieved_first() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![Node::new("node_1".to_string(), 4000, 4000)],
+            vec![
+                (
+                    "node_1",
+                    burstable_pod_with_priority("pod_1", 2000, 2000, 1),
+                ),
+                ("node_1", pod_with_priority("pod_2", 2000, 2000, 1)),
+            ],
+        );
+        let preemptor = pod_with_priority("preemptor", 2000, 2000, 10);
+
+        assert_eq!(
+            Some(PreemptionCandidate {
+                node_name: "node_1".to_string(),
+                victims: vec!["pod_1".to_string()],
+            }),
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_node_with_least_protected_victims_is_chosen() {
+        let (nodes, pods, assignments) = cluster_state(
+            vec![
+                Node::new("node_1".to_string(), 2000, 2000),
+                Node::new("node_2".to_string(), 2000, 2000),
+            ],
+            vec![
+                ("node_1", pod_with_priority("pod_1", 2000, 2000, 1)),
+                (
+                    "node_2",
+                    burstable_pod_with_priority("pod_2", 2000, 2000, 1),
+                ),
+            ],
+        );
+        let preemptor = pod_with_priority("preemptor", 2000, 2000, 10);
+
+        assert_eq!(
+            Some(PreemptionCandidate {
+                node_name: "node_2".to_string(),
+                victims: vec!["pod_2".to_string()],
+            }),
+            select_preemption_candidate(
+                &preemptor,
+                &nodes,
+                &pods,
+                &assignments,
+                &Default::default()
+            )
+        );
+    }
+}
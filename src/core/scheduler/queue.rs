@@ -1,6 +1,6 @@
 //! Definitions for helper structs used in scheduler queues.
 
-use std::{cmp::Ordering, rc::Rc};
+use std::{cmp::Ordering, collections::BTreeSet, rc::Rc};
 
 /// Default value (secs) for the maximum time a pod can stay in unschedulablePods. If a pod stays in
 /// unschedulablePods for longer than this value, no matter resources update events happened or not,
@@ -10,6 +10,11 @@ pub const DEFAULT_POD_MAX_IN_UNSCHEDULABLE_PODS_DURATION: f64 = 5.0 * 60.0;
 /// Value (secs) for the running cycle to flush pods that stay for too long.
 pub const POD_FLUSH_INTERVAL: f64 = 30.0;
 
+/// Default values (secs) of the backoff duration after the first failed scheduling attempt and
+/// the maximal backoff duration, the same as in kube-scheduler.
+pub const DEFAULT_POD_INITIAL_BACKOFF_SECONDS: f64 = 1.0;
+pub const DEFAULT_POD_MAX_BACKOFF_SECONDS: f64 = 10.0;
+
 #[derive(Clone)]
 pub struct QueuedPodInfo {
     /// The time pod added to the scheduling queue.
@@ -24,11 +29,33 @@ pub struct QueuedPodInfo {
     pub initial_attempt_timestamp: f64,
     /// Reference to a name of a pod which object is stored in scheduler's objects_cache
     pub pod_name: Rc<String>,
+    /// Priority of a pod copied from its spec. Pods with higher priority are popped first.
+    pub priority: i32,
+    /// Names of plugins which rejected the pod in the last scheduling attempt. They decide which
+    /// cluster events may make the pod schedulable.
+    pub unschedulable_plugins: BTreeSet<String>,
+}
+
+impl QueuedPodInfo {
+    /// Backoff duration doubles with every failed attempt starting from `initial_backoff` until it
+    /// reaches `max_backoff`.
+    pub fn backoff_duration(&self, initial_backoff: f64, max_backoff: f64) -> f64 {
+        let mut duration = initial_backoff;
+        for _ in 1..self.attempts {
+            duration *= 2.0;
+            if duration > max_backoff {
+                return max_backoff;
+            }
+        }
+        duration
+    }
 }
 
 impl Ord for QueuedPodInfo {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.timestamp.total_cmp(&self.timestamp)
+        self.priority
+            .cmp(&other.priority)
+            .then(other.timestamp.total_cmp(&self.timestamp))
     }
 }
 
@@ -74,6 +101,35 @@ impl PartialEq for UnschedulablePodKey {
 
 impl Eq for UnschedulablePodKey {}
 
+/// Key of a pod in backoff queue, pods are ordered by the time their backoff expires.
+#[derive(Clone, Debug)]
+pub struct BackoffPodKey {
+    pub pod_name: Rc<String>,
+    pub backoff_expiration: f64,
+}
+
+impl Ord for BackoffPodKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.backoff_expiration
+            .total_cmp(&other.backoff_expiration)
+            .then(self.pod_name.cmp(&other.pod_name))
+    }
+}
+
+impl PartialOrd for BackoffPodKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for BackoffPodKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.pod_name == other.pod_name && self.backoff_expiration == other.backoff_expiration
+    }
+}
+
+impl Eq for BackoffPodKey {}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -91,6 +147,8 @@ mod tests {
             attempts: 1,
             initial_attempt_timestamp: 1.0,
             pod_name: Rc::new("some_pod".to_string()),
+            priority: 0,
+            unschedulable_plugins: Default::default(),
         };
 
         queue.push(queue_pod_info.clone());
@@ -111,6 +169,40 @@ mod tests {
         assert!(queue.pop().is_none());
     }
 
+    #[test]
+    fn test_queue_pod_info_order_with_priorities() {
+        let mut queue = BinaryHeap::<QueuedPodInfo>::new();
+        let mut queue_pod_info = QueuedPodInfo {
+            timestamp: 1.0,
+            attempts: 1,
+            initial_attempt_timestamp: 1.0,
+            pod_name: Rc::new("low_priority_pod".to_string()),
+            priority: 0,
+            unschedulable_plugins: Default::default(),
+        };
+        queue.push(queue_pod_info.clone());
+
+        queue_pod_info.timestamp = 5.0;
+        queue_pod_info.pod_name = Rc::new("high_priority_pod".to_string());
+        queue_pod_info.priority = 100;
+        queue.push(queue_pod_info.clone());
+
+        queue_pod_info.timestamp = 3.0;
+        queue_pod_info.pod_name = Rc::new("earlier_high_priority_pod".to_string());
+        queue.push(queue_pod_info.clone());
+
+        queue_pod_info.timestamp = 0.5;
+        queue_pod_info.pod_name = Rc::new("negative_priority_pod".to_string());
+        queue_pod_info.priority = -10;
+        queue.push(queue_pod_info.clone());
+
+        assert_eq!("earlier_high_priority_pod", *queue.pop().unwrap().pod_name);
+        assert_eq!("high_priority_pod", *queue.pop().unwrap().pod_name);
+        assert_eq!("low_priority_pod", *queue.pop().unwrap().pod_name);
+        assert_eq!("negative_priority_pod", *queue.pop().unwrap().pod_name);
+        assert!(queue.pop().is_none());
+    }
+
     fn insert_into_queue(
         queue: &mut BTreeMap<UnschedulablePodKey, QueuedPodInfo>,
         info: &QueuedPodInfo,
@@ -133,6 +225,8 @@ mod tests {
             attempts: 1,
             initial_attempt_timestamp: 1.0,
             pod_name: Rc::new("some_pod".to_string()),
+            priority: 0,
+            unschedulable_plugins: Default::default(),
         };
         insert_into_queue(&mut queue, &queue_pod_info);
 
@@ -162,4 +256,24 @@ mod tests {
         assert_eq!(7.0, entries[3].0.insert_timestamp);
         assert_eq!(10.0, entries[4].0.insert_timestamp);
     }
+
+    #[test]
+    fn test_backoff_duration() {
+        let mut queue_pod_info = QueuedPodInfo {
+            timestamp: 1.0,
+            attempts: 1,
+            initial_attempt_timestamp: 1.0,
+            pod_name: Rc::new("some_pod".to_string()),
+            priority: 0,
+            unschedulable_plugins: Default::default(),
+        };
+
+        let backoff_durations: Vec<f64> = (1..=6)
+            .map(|attempts| {
+                queue_pod_info.attempts = attempts;
+                queue_pod_info.backoff_duration(1.0, 10.0)
+            })
+            .collect();
+        assert_eq!(vec![1.0, 2.0, 4.0, 8.0, 10.0, 10.0], backoff_durations);
+    }
 }
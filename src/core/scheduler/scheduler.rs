@@ -2,31 +2,56 @@
 
 use std::cell::RefCell;
 use std::collections::BinaryHeap;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 
 use dslab_core::{cast, log_debug, log_trace, Event, EventHandler, SimulationContext};
 
-use crate::core::common::{ObjectsInfo, RuntimeResources, SimComponentId};
+use crate::core::common::{ObjectsInfo, SimComponentId};
 use crate::core::events::{
-    AddNodeToCache, AssignPodToNodeRequest, FlushUnschedulableQueueLeftover, PodFinishedRunning,
-    PodNotScheduled, PodScheduleRequest, RemoveNodeFromCache, RemovePodFromCache,
-    RunSchedulingCycle,
+    AddNodeToCache, AddPodToCache, AssignPodToNodeRequest, EvictPodRequest,
+    FlushUnschedulableQueueLeftover, PodAssignmentConflict, PodEvictedFromNode, PodFinishedRunning,
+    PodNotScheduled, PodPermitWaitTimeout, PodScheduleRequest, RemoveNodeFromCache,
+    RemovePodFromCache, RunSchedulingCycle, UpdateNodeInCache,
 };
 use crate::core::node::Node;
-use crate::core::pod::Pod;
-use crate::core::scheduler::interface::{PodSchedulingAlgorithm, ScheduleError};
+use crate::core::pod::{EvictionReason, Pod};
+use crate::core::scheduler::extender::ExtenderScheduler;
+use crate::core::scheduler::framework::{
+    ClusterEvent, CycleState, EvaluatedNodes, PermitDecision, QueueingHint, WaitingPod, WaitingPods,
+};
+use crate::core::scheduler::interface::{
+    BatchPodSchedulingAlgorithm, ClusterSnapshot, PodSchedulingAlgorithm, ScheduleError,
+};
+use crate::core::scheduler::kube_scheduler::KubeScheduler;
 use crate::core::scheduler::model::{ConstantTimePerNodeModel, PodSchedulingTimeModel};
+use crate::core::scheduler::plugin::{pod_fits_node_resources, pod_group_of};
 
 use crate::metrics::collector::MetricsCollector;
 
-use crate::config::SimulationConfig;
+use crate::config::SchedulerComponentConfig;
 
 use crate::core::scheduler::queue::{
-    QueuedPodInfo, UnschedulablePodKey, DEFAULT_POD_MAX_IN_UNSCHEDULABLE_PODS_DURATION,
-    POD_FLUSH_INTERVAL,
+    BackoffPodKey, QueuedPodInfo, UnschedulablePodKey,
+    DEFAULT_POD_MAX_IN_UNSCHEDULABLE_PODS_DURATION, POD_FLUSH_INTERVAL,
 };
 
+/// Creates the algorithm of a scheduler component: the external extender if it is configured,
+/// otherwise kube-scheduler with the configured profiles. Returns a description of the problem if
+/// the config is invalid.
+pub fn resolve_pod_scheduling_algorithm(
+    config: &SchedulerComponentConfig,
+) -> Result<Box<dyn PodSchedulingAlgorithm>, String> {
+    if let Some(extender_config) = &config.extender {
+        let extender = ExtenderScheduler::new(extender_config.clone())
+            .map_err(|err| format!("Invalid extender of scheduler {:?}: {}", config.name, err))?;
+        return Ok(Box::new(extender));
+    }
+    let kube_scheduler = KubeScheduler::new(config.scheduler.clone())
+        .map_err(|err| format!("Invalid config of scheduler {:?}: {}", config.name, err))?;
+    Ok(Box::new(kube_scheduler))
+}
+
 pub struct Scheduler {
     api_server: SimComponentId,
 
@@ -36,20 +61,42 @@ pub struct Scheduler {
     assignments: HashMap<String, BTreeSet<String>>,
 
     scheduler_algorithm: Box<dyn PodSchedulingAlgorithm>,
+    /// If set, pods are scheduled in batches by this algorithm instead of `scheduler_algorithm`.
+    batch_scheduler_algorithm: Option<Box<dyn BatchPodSchedulingAlgorithm>>,
 
     pod_scheduling_time_model: Box<dyn PodSchedulingTimeModel>,
-    /// Sorted by timestamp of addition a queue contains information about a pod to schedule.
+    /// Sorted by pod priority and then by timestamp of addition a queue contains information about
+    /// a pod to schedule.
     /// Firstly-added pod is assigned a timestamp of event PodScheduleRequest.
     /// Pods from unschedulable queue are moved to active queue with the timestamp of last adding
     /// to unschedulable queue.
     action_queue: BinaryHeap<QueuedPodInfo>,
     /// Map of pod keys and their queue info which cannot be schedulable at the moment.
     /// Moves to active queue either if DEFAULT_POD_MAX_IN_UNSCHEDULABLE_PODS_DURATION exceeded or
-    /// event of interest (PodFinishedRunning, AddNodeToCache) occurred.
+    /// a cluster event occurred which queueing hints of the scheduling algorithm consider
+    /// relevant for the pod.
     pub unschedulable_pods: BTreeMap<UnschedulablePodKey, QueuedPodInfo>,
+    /// Pods moved out of unschedulable queue before their backoff after the last failed attempt
+    /// expired. They are moved to active queue at the start of a scheduling cycle.
+    pub backoff_queue: BTreeMap<BackoffPodKey, QueuedPodInfo>,
+    /// Names of pods which are requested to be evicted due to preemption, but the scheduler has
+    /// not been informed about completed eviction yet.
+    pending_evictions: HashSet<String>,
+    /// Pods which are reserved on nodes, but wait for permit plugins before binding.
+    waiting_pods: WaitingPods,
+    /// Names of pods assigned by other schedulers. They are kept in cache only to account node
+    /// resources taken by them and are never scheduled by this one.
+    other_schedulers_pods: HashSet<String>,
+    /// Pods of other schedulers which do not fit into their nodes in cache yet. It happens when
+    /// this scheduler has reserved the same resources for its own pods, whose assignments are
+    /// going to be rejected due to conflict. They are added to cache after rolling back.
+    deferred_other_schedulers_pods: BTreeMap<String, Pod>,
+    /// Pods in scheduling queues reported to the metrics collector in the last cycle. Several
+    /// schedulers contribute to the same gauge.
+    reported_pods_in_queues: u64,
 
     ctx: SimulationContext,
-    config: Rc<SimulationConfig>,
+    config: SchedulerComponentConfig,
     metrics_collector: Rc<RefCell<MetricsCollector>>,
 }
 
@@ -58,7 +105,7 @@ impl Scheduler {
         api_server: SimComponentId,
         scheduler_algorithm: Box<dyn PodSchedulingAlgorithm>,
         ctx: SimulationContext,
-        config: Rc<SimulationConfig>,
+        config: SchedulerComponentConfig,
         metrics_collector: Rc<RefCell<MetricsCollector>>,
     ) -> Self {
         Self {
@@ -66,9 +113,16 @@ impl Scheduler {
             objects_cache: Default::default(),
             assignments: Default::default(),
             scheduler_algorithm,
+            batch_scheduler_algorithm: None,
             pod_scheduling_time_model: Box::new(ConstantTimePerNodeModel::default()),
             action_queue: Default::default(),
             unschedulable_pods: Default::default(),
+            backoff_queue: Default::default(),
+            pending_evictions: Default::default(),
+            waiting_pods: Default::default(),
+            other_schedulers_pods: Default::default(),
+            deferred_other_schedulers_pods: Default::default(),
+            reported_pods_in_queues: 0,
             ctx,
             config,
             metrics_collector,
@@ -115,6 +169,20 @@ impl Scheduler {
         self.scheduler_algorithm = scheduler_algorithm
     }
 
+    pub fn set_batch_scheduler_algorithm(
+        &mut self,
+        batch_scheduler_algorithm: Box<dyn BatchPodSchedulingAlgorithm>,
+    ) {
+        self.batch_scheduler_algorithm = Some(batch_scheduler_algorithm)
+    }
+
+    pub fn set_pod_scheduling_time_model(
+        &mut self,
+        pod_scheduling_time_model: Box<dyn PodSchedulingTimeModel>,
+    ) {
+        self.pod_scheduling_time_model = pod_scheduling_time_model
+    }
+
     fn reserve_node_resources(&mut self, pod_name: &str, assigned_node: &str) {
         let pod = self.objects_cache.pods.get(pod_name).unwrap();
         let node = self.objects_cache.nodes.get_mut(assigned_node).unwrap();
@@ -161,26 +229,72 @@ impl Scheduler {
         node.status.allocatable.ram += pod.spec.resources.requests.ram;
     }
 
-    fn schedule_one(&self, pod: &Pod) -> Result<String, ScheduleError> {
+    fn snapshot(&self) -> ClusterSnapshot<'_> {
+        ClusterSnapshot {
+            nodes: &self.objects_cache.nodes,
+            pods: &self.objects_cache.pods,
+            assignments: &self.assignments,
+            pending_evictions: &self.pending_evictions,
+        }
+    }
+
+    fn schedule_one(&self, pod: &Pod, state: &mut CycleState) -> Result<String, ScheduleError> {
         log_trace!(
             self.ctx,
             "Considering {} nodes for scheduling",
             self.objects_cache.nodes.len()
         );
         self.scheduler_algorithm
-            .schedule_one(pod, &self.objects_cache.nodes)
+            .schedule_one(pod, &self.snapshot(), state)
     }
 
-    fn move_pods_to_active_queue(&mut self, unscheduled_pods: Vec<UnschedulablePodKey>) {
+    fn move_pods_to_active_queue(
+        &mut self,
+        unscheduled_pods: Vec<UnschedulablePodKey>,
+        event_time: f64,
+    ) {
         for key in unscheduled_pods.into_iter() {
+            let queued_pod_info = self.unschedulable_pods.remove(&key).unwrap();
             // Check whether pod was removed from RemovePodFromCache event
             if !self.objects_cache.pods.contains_key(&key.pod_name as &str) {
                 continue;
             }
+            self.move_to_active_or_backoff_queue(queued_pod_info, event_time);
+        }
+    }
+
+    /// Pod goes to active queue only if its backoff after the last failed attempt has expired.
+    fn move_to_active_or_backoff_queue(
+        &mut self,
+        mut queued_pod_info: QueuedPodInfo,
+        event_time: f64,
+    ) {
+        let backoff_expiration = queued_pod_info.timestamp
+            + queued_pod_info.backoff_duration(
+                self.config.scheduler.pod_initial_backoff_seconds,
+                self.config.scheduler.pod_max_backoff_seconds,
+            );
+        queued_pod_info.attempts += 1;
+        if backoff_expiration <= event_time {
+            self.action_queue.push(queued_pod_info);
+            return;
+        }
+        self.backoff_queue.insert(
+            BackoffPodKey {
+                pod_name: queued_pod_info.pod_name.clone(),
+                backoff_expiration,
+            },
+            queued_pod_info,
+        );
+    }
 
-            let mut queue_pod_info = self.unschedulable_pods.remove(&key).unwrap();
-            queue_pod_info.attempts += 1;
-            self.action_queue.push(queue_pod_info);
+    /// Moves pods which have completed their backoff to the active queue.
+    fn flush_backoff_queue_completed(&mut self, event_time: f64) {
+        while let Some(entry) = self.backoff_queue.first_entry() {
+            if entry.key().backoff_expiration > event_time {
+                break;
+            }
+            self.action_queue.push(entry.remove());
         }
     }
 
@@ -196,43 +310,37 @@ impl Scheduler {
             }
         }
 
-        self.move_pods_to_active_queue(pods_to_move);
+        self.move_pods_to_active_queue(pods_to_move, event_time);
 
         self.ctx
             .emit_self(FlushUnschedulableQueueLeftover {}, POD_FLUSH_INTERVAL);
     }
 
-    /// Move pods from unschedulable to active queue which satisfy check.
-    /// On events of interest such as some resources on nodes are freed or new nodes are added.
-    /// Assuming `check` returns true if should move current pod.
-    /// Inside lambda's captured state (pod/node) resources decrease on amount of resources of pods
-    /// which are moved.
-    fn move_to_active_queue_if(
-        &mut self,
-        mut check: impl FnMut(&RuntimeResources) -> bool,
-    ) {
-        let mut pods_to_move: Vec<UnschedulablePodKey> = vec![];
-        pods_to_move.reserve(self.unschedulable_pods.len());
-
-        for (key, queued_pod_info) in self.unschedulable_pods.iter() {
-            if check(
-                &self
-                    .objects_cache
-                    .pods
-                    .get(&*queued_pod_info.pod_name)
-                    .unwrap()
-                    .spec
-                    .resources
-                    .requests,
-            ) {
-                pods_to_move.push(key.clone());
-            }
-        }
-
-        self.move_pods_to_active_queue(pods_to_move);
+    /// Returns unschedulable pods which the scheduling algorithm considers worth retrying after the
+    /// cluster event, based on plugins which rejected them.
+    fn pods_to_move_on_cluster_event(&self, event: &ClusterEvent) -> Vec<UnschedulablePodKey> {
+        let snapshot = self.snapshot();
+        self.unschedulable_pods
+            .iter()
+            .filter(|(_, queued_pod_info)| {
+                match self.objects_cache.pods.get(&*queued_pod_info.pod_name) {
+                    Some(pod) => {
+                        self.scheduler_algorithm.queueing_hint(
+                            pod,
+                            &queued_pod_info.unschedulable_plugins,
+                            event,
+                            &snapshot,
+                        ) == QueueingHint::Queue
+                    }
+                    // Removed pods are just dropped from the queue.
+                    None => true,
+                }
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
     }
 
-    fn move_all_to_active_queue(&mut self) {
+    fn move_all_to_active_queue(&mut self, event_time: f64) {
         let mut pods_to_move: Vec<UnschedulablePodKey> = vec![];
         pods_to_move.reserve(self.unschedulable_pods.len());
 
@@ -240,25 +348,48 @@ impl Scheduler {
             pods_to_move.push(key.clone());
         }
 
-        self.move_pods_to_active_queue(pods_to_move);
+        self.move_pods_to_active_queue(pods_to_move, event_time);
     }
 
     fn run_scheduling_cycle(&mut self, scheduling_cycle_event_time: f64) {
-        let mut cycle_sim_duration = 0.0;
+        self.flush_backoff_queue_completed(scheduling_cycle_event_time);
 
         log_debug!(
             self.ctx,
-            "run scheduling cycle, active queue len={:?}, unschedulable queue len={:?}",
+            "run scheduling cycle, active queue len={:?}, backoff queue len={:?}, unschedulable queue len={:?}",
             self.action_queue.len(),
+            self.backoff_queue.len(),
             self.unschedulable_pods.len()
         );
+        let pods_in_queues = self.action_queue.len() as u64
+            + self.backoff_queue.len() as u64
+            + self.unschedulable_pods.len() as u64;
+        self.metrics_collector
+            .borrow_mut()
+            .gauge_metrics
+            .pods_in_scheduling_queues -= self.reported_pods_in_queues;
         self.metrics_collector
             .borrow_mut()
             .gauge_metrics
-            .pods_in_scheduling_queues =
-            self.action_queue.len() as u64 + self.unschedulable_pods.len() as u64;
+            .pods_in_scheduling_queues += pods_in_queues;
+        self.reported_pods_in_queues = pods_in_queues;
+
+        let cycle_sim_duration = if self.batch_scheduler_algorithm.is_some() {
+            self.schedule_pods_in_batch(scheduling_cycle_event_time)
+        } else {
+            self.schedule_pods_one_by_one(scheduling_cycle_event_time)
+        };
+
+        let next_cycle_delay = f64::max(cycle_sim_duration, self.config.scheduling_cycle_interval);
+        self.ctx.emit_self(RunSchedulingCycle {}, next_cycle_delay);
+    }
+
+    /// Runs the scheduling algorithm for pods of the active queue one at a time in the order of
+    /// the queue. Returns simulated duration of the cycle.
+    fn schedule_pods_one_by_one(&mut self, scheduling_cycle_event_time: f64) -> f64 {
+        let mut cycle_sim_duration = 0.0;
 
-        while let Some(mut next_pod) = self.action_queue.pop() {
+        while let Some(next_pod) = self.action_queue.pop() {
             // Check whether pod was removed from RemovePodFromCache event
             if !self
                 .objects_cache
@@ -268,16 +399,23 @@ impl Scheduler {
                 continue;
             }
 
-            let pod_queue_time = scheduling_cycle_event_time - next_pod.initial_attempt_timestamp
-                + cycle_sim_duration;
-
             let pod = self.objects_cache.pods.get(&*next_pod.pod_name).unwrap();
-            let pod_schedule_time = self
-                .pod_scheduling_time_model
-                .simulate_time(pod, &self.objects_cache.nodes);
+            let mut state = CycleState::default();
+            let schedule_result = self.schedule_one(pod, &mut state);
+
+            // Algorithms which do not report evaluated nodes are assumed to process all of them.
+            let evaluated_nodes = state.evaluated_nodes().unwrap_or(EvaluatedNodes {
+                filtered: self.objects_cache.nodes.len(),
+                scored: self.objects_cache.nodes.len(),
+            });
+            let pod_schedule_time = self.pod_scheduling_time_model.simulate_time(
+                pod,
+                &self.objects_cache.nodes,
+                evaluated_nodes,
+            );
             cycle_sim_duration += pod_schedule_time;
 
-            let assigned_node = match self.schedule_one(pod) {
+            let assigned_node = match schedule_result {
                 Ok(assigned_node) => assigned_node,
                 Err(err) => {
                     log_trace!(
@@ -286,21 +424,23 @@ impl Scheduler {
                         next_pod.pod_name,
                         err
                     );
-                    next_pod.timestamp = scheduling_cycle_event_time + cycle_sim_duration;
-                    self.unschedulable_pods.insert(
-                        UnschedulablePodKey {
-                            pod_name: next_pod.pod_name.clone(),
-                            insert_timestamp: next_pod.timestamp,
-                        },
+                    if err == ScheduleError::NoSufficientResources {
+                        self.preempt(
+                            &next_pod.pod_name,
+                            &state,
+                            scheduling_cycle_event_time + cycle_sim_duration,
+                            cycle_sim_duration,
+                        );
+                    }
+                    let unschedulable_plugins = state.unschedulable_plugins().clone();
+                    self.move_to_unschedulable_queue(
                         next_pod,
+                        scheduling_cycle_event_time + cycle_sim_duration,
+                        unschedulable_plugins,
                     );
-                    self.ctx.emit(
-                        PodNotScheduled {
-                            not_scheduled_time: scheduling_cycle_event_time + cycle_sim_duration,
-                            pod_name: pod.metadata.name.clone(),
-                        },
-                        self.api_server,
-                        self.config.sched_to_as_network_delay,
+                    self.process_resolved_waiting_pods(
+                        scheduling_cycle_event_time + cycle_sim_duration,
+                        cycle_sim_duration,
                     );
                     continue;
                 }
@@ -309,43 +449,502 @@ impl Scheduler {
             self.reserve_node_resources(&next_pod.pod_name, &assigned_node);
             self.assign_node_to_pod(&next_pod.pod_name, &assigned_node);
 
-            self.ctx.emit(
-                AssignPodToNodeRequest {
-                    assign_time: scheduling_cycle_event_time + cycle_sim_duration,
-                    pod_name: next_pod.pod_name.to_string(),
-                    node_name: assigned_node,
-                },
-                self.api_server,
-                cycle_sim_duration + self.config.sched_to_as_network_delay,
+            let pod = self.objects_cache.pods.get(&*next_pod.pod_name).unwrap();
+            let reserve_status = self
+                .scheduler_algorithm
+                .reserve(pod, &assigned_node, &mut state);
+            if !reserve_status.is_success() {
+                log_debug!(
+                    self.ctx,
+                    "Reserve of pod {:?} on node {:?} failed: {:?}",
+                    next_pod.pod_name,
+                    assigned_node,
+                    reserve_status
+                );
+                self.unreserve(&next_pod.pod_name, &mut state);
+                self.move_to_unschedulable_queue(
+                    next_pod,
+                    scheduling_cycle_event_time + cycle_sim_duration,
+                    Default::default(),
+                );
+                self.process_resolved_waiting_pods(
+                    scheduling_cycle_event_time + cycle_sim_duration,
+                    cycle_sim_duration,
+                );
+                continue;
+            }
+
+            let snapshot = ClusterSnapshot {
+                nodes: &self.objects_cache.nodes,
+                pods: &self.objects_cache.pods,
+                assignments: &self.assignments,
+                pending_evictions: &self.pending_evictions,
+            };
+            let permit_decision = self.scheduler_algorithm.permit(
+                pod,
+                &assigned_node,
+                &snapshot,
+                &state,
+                &mut self.waiting_pods,
             );
+            match permit_decision {
+                PermitDecision::Allow => {
+                    self.bind(
+                        next_pod,
+                        assigned_node,
+                        &mut state,
+                        pod_schedule_time,
+                        scheduling_cycle_event_time + cycle_sim_duration,
+                        cycle_sim_duration,
+                    );
+                }
+                PermitDecision::Wait { plugins, timeout } => {
+                    log_debug!(
+                        self.ctx,
+                        "Pod {:?} waits on node {:?} for permit plugins {:?}",
+                        next_pod.pod_name,
+                        assigned_node,
+                        plugins
+                    );
+                    let timeout_event_id = self.ctx.emit_self(
+                        PodPermitWaitTimeout {
+                            pod_name: next_pod.pod_name.to_string(),
+                        },
+                        cycle_sim_duration + timeout,
+                    );
+                    self.waiting_pods.insert(
+                        next_pod.pod_name.to_string(),
+                        WaitingPod {
+                            node_name: assigned_node,
+                            pending_plugins: plugins,
+                            rejection_reason: None,
+                            wait_start_time: scheduling_cycle_event_time + cycle_sim_duration,
+                            deadline: scheduling_cycle_event_time + cycle_sim_duration + timeout,
+                            timeout_event_id,
+                            state,
+                            queued_pod_info: next_pod,
+                            pod_schedule_time,
+                        },
+                    );
+                }
+                PermitDecision::Reject(reason) => {
+                    log_debug!(
+                        self.ctx,
+                        "Pod {:?} is rejected by permit plugins: {}",
+                        next_pod.pod_name,
+                        reason
+                    );
+                    self.unreserve(&next_pod.pod_name, &mut state);
+                    self.move_to_unschedulable_queue(
+                        next_pod,
+                        scheduling_cycle_event_time + cycle_sim_duration,
+                        Default::default(),
+                    );
+                }
+            }
 
-            self.metrics_collector
-                .borrow_mut()
-                .accumulated_metrics
-                .increment_pod_scheduling_algorithm_latency(pod_schedule_time);
-            self.metrics_collector
-                .borrow_mut()
-                .accumulated_metrics
-                .increment_pod_queue_time(pod_queue_time);
+            // Permit plugins might allow or reject other waiting pods.
+            self.process_resolved_waiting_pods(
+                scheduling_cycle_event_time + cycle_sim_duration,
+                cycle_sim_duration,
+            );
         }
 
-        let next_cycle_delay = f64::max(cycle_sim_duration, self.config.scheduling_cycle_interval);
-        self.ctx.emit_self(RunSchedulingCycle {}, next_cycle_delay);
+        cycle_sim_duration
     }
 
-    fn reschedule_pod(&mut self, pod_name: String, event_time: f64) {
+    /// Passes all pods of the active queue to the batch scheduling algorithm at once. Assignments
+    /// are sent after the whole batch is processed. Pods which are not assigned or are assigned to
+    /// nodes they do not fit go to unschedulable queue. Returns simulated duration of the cycle.
+    fn schedule_pods_in_batch(&mut self, scheduling_cycle_event_time: f64) -> f64 {
+        let mut batch: Vec<QueuedPodInfo> = vec![];
+        while let Some(next_pod) = self.action_queue.pop() {
+            // Check whether pod was removed from RemovePodFromCache event
+            if self
+                .objects_cache
+                .pods
+                .contains_key(&next_pod.pod_name as &str)
+            {
+                batch.push(next_pod);
+            }
+        }
+        if batch.is_empty() {
+            return 0.0;
+        }
+
+        let pods: Vec<&Pod> = batch
+            .iter()
+            .map(|queued_pod_info| self.get_pod(&queued_pod_info.pod_name))
+            .collect();
+        let mut assignments = self
+            .batch_scheduler_algorithm
+            .as_ref()
+            .unwrap()
+            .schedule_batch(&pods, &self.snapshot());
+
+        // Batch algorithms are considered to evaluate all nodes for every pod.
+        let evaluated_nodes = EvaluatedNodes {
+            filtered: self.objects_cache.nodes.len(),
+            scored: self.objects_cache.nodes.len(),
+        };
+        let pod_schedule_times: Vec<f64> = pods
+            .iter()
+            .map(|pod| {
+                self.pod_scheduling_time_model.simulate_time(
+                    pod,
+                    &self.objects_cache.nodes,
+                    evaluated_nodes,
+                )
+            })
+            .collect();
+        let cycle_sim_duration: f64 = pod_schedule_times.iter().sum();
+        let bind_time = scheduling_cycle_event_time + cycle_sim_duration;
+
+        for (queued_pod_info, pod_schedule_time) in batch.into_iter().zip(pod_schedule_times) {
+            let pod = self.get_pod(&queued_pod_info.pod_name);
+            let assigned_node =
+                assignments
+                    .remove(&*queued_pod_info.pod_name)
+                    .filter(|node_name| match self.objects_cache.nodes.get(node_name) {
+                        Some(node) => pod_fits_node_resources(pod, node),
+                        None => false,
+                    });
+            let Some(assigned_node) = assigned_node else {
+                log_trace!(
+                    self.ctx,
+                    "failed to schedule pod {:?} in batch",
+                    queued_pod_info.pod_name
+                );
+                self.move_to_unschedulable_queue(queued_pod_info, bind_time, Default::default());
+                continue;
+            };
+
+            self.reserve_node_resources(&queued_pod_info.pod_name, &assigned_node);
+            self.assign_node_to_pod(&queued_pod_info.pod_name, &assigned_node);
+            self.send_assignment(
+                queued_pod_info,
+                assigned_node,
+                pod_schedule_time,
+                bind_time,
+                cycle_sim_duration,
+            );
+        }
+
+        cycle_sim_duration
+    }
+
+    /// Returns the pod to unschedulable queue after a failed scheduling attempt.
+    /// `unschedulable_plugins` are plugins which rejected the pod, they are empty if the pod
+    /// failed after a node was assigned to it.
+    fn move_to_unschedulable_queue(
+        &mut self,
+        mut queued_pod_info: QueuedPodInfo,
+        time: f64,
+        unschedulable_plugins: BTreeSet<String>,
+    ) {
+        let pod_name = queued_pod_info.pod_name.to_string();
+        queued_pod_info.timestamp = time;
+        queued_pod_info.unschedulable_plugins = unschedulable_plugins;
+        self.unschedulable_pods.insert(
+            UnschedulablePodKey {
+                pod_name: queued_pod_info.pod_name.clone(),
+                insert_timestamp: queued_pod_info.timestamp,
+            },
+            queued_pod_info,
+        );
+        self.ctx.emit(
+            PodNotScheduled {
+                not_scheduled_time: time,
+                pod_name,
+            },
+            self.api_server,
+            self.config.sched_to_as_network_delay,
+        );
+    }
+
+    /// Rolls back reservation of node resources for the pod which is assigned a node, but not
+    /// bound to it.
+    fn unreserve(&mut self, pod_name: &str, state: &mut CycleState) {
+        let pod = self.objects_cache.pods.get(pod_name).unwrap().clone();
+        let node_name = &pod.status.assigned_node;
+        let snapshot = ClusterSnapshot {
+            nodes: &self.objects_cache.nodes,
+            pods: &self.objects_cache.pods,
+            assignments: &self.assignments,
+            pending_evictions: &self.pending_evictions,
+        };
+        self.scheduler_algorithm.unreserve(
+            &pod,
+            node_name,
+            &snapshot,
+            state,
+            &mut self.waiting_pods,
+        );
+
+        self.release_node_resources(&pod);
+        self.assignments
+            .get_mut(node_name)
+            .unwrap()
+            .remove(pod_name);
         self.objects_cache
             .pods
-            .get_mut(&pod_name)
+            .get_mut(pod_name)
             .unwrap()
             .status
             .assigned_node = Default::default();
+    }
+
+    /// Runs PreBind and Bind extension points and sends assignment to the api server if they
+    /// succeed. `bind_time` is the simulation time of binding and `delay` is its offset from the
+    /// current time.
+    fn bind(
+        &mut self,
+        queued_pod_info: QueuedPodInfo,
+        node_name: String,
+        state: &mut CycleState,
+        pod_schedule_time: f64,
+        bind_time: f64,
+        delay: f64,
+    ) {
+        let pod = self
+            .objects_cache
+            .pods
+            .get(&*queued_pod_info.pod_name)
+            .unwrap();
+        let mut status = self.scheduler_algorithm.pre_bind(pod, &node_name, state);
+        if status.is_success() {
+            status = self.scheduler_algorithm.bind(pod, &node_name, state);
+        }
+        if !status.is_success() {
+            log_debug!(
+                self.ctx,
+                "Binding pod {:?} to node {:?} failed: {:?}",
+                queued_pod_info.pod_name,
+                node_name,
+                status
+            );
+            self.unreserve(&queued_pod_info.pod_name, state);
+            self.move_to_unschedulable_queue(queued_pod_info, bind_time, Default::default());
+            return;
+        }
+
+        self.send_assignment(
+            queued_pod_info,
+            node_name,
+            pod_schedule_time,
+            bind_time,
+            delay,
+        );
+    }
+
+    /// Sends assignment of the pod reserved on the node to the api server and records scheduling
+    /// metrics of the pod.
+    fn send_assignment(
+        &mut self,
+        queued_pod_info: QueuedPodInfo,
+        node_name: String,
+        pod_schedule_time: f64,
+        bind_time: f64,
+        delay: f64,
+    ) {
+        self.ctx.emit(
+            AssignPodToNodeRequest {
+                assign_time: bind_time,
+                pod_name: queued_pod_info.pod_name.to_string(),
+                node_name,
+            },
+            self.api_server,
+            delay + self.config.sched_to_as_network_delay,
+        );
+
+        // Time spent waiting for permit plugins is considered as queue time.
+        let pod_queue_time =
+            bind_time - pod_schedule_time - queued_pod_info.initial_attempt_timestamp;
+        let qos_class = &self
+            .objects_cache
+            .pods
+            .get(&*queued_pod_info.pod_name)
+            .unwrap()
+            .status
+            .qos_class;
+        self.metrics_collector
+            .borrow_mut()
+            .accumulated_metrics
+            .increment_pod_scheduling_algorithm_latency(pod_schedule_time);
+        self.metrics_collector
+            .borrow_mut()
+            .accumulated_metrics
+            .increment_pod_queue_time(pod_queue_time, qos_class);
+    }
+
+    /// Binds waiting pods which are allowed by all permit plugins and returns rejected ones to
+    /// unschedulable queue. Unreserve of rejected pods may reject other waiting pods, so it is
+    /// repeated until there are no resolved pods left.
+    fn process_resolved_waiting_pods(&mut self, time: f64, delay: f64) {
+        loop {
+            let resolved = self.waiting_pods.take_resolved();
+            if resolved.is_empty() {
+                break;
+            }
+            self.record_pod_group_wait_times(&resolved, time);
+            for (pod_name, waiting_pod) in resolved {
+                self.process_resolved_waiting_pod(pod_name, waiting_pod, time, delay);
+            }
+        }
+    }
+
+    /// Pod group wait time is the time between the first pod of the group started waiting at
+    /// permit and the moment the whole group is allowed.
+    fn record_pod_group_wait_times(&self, resolved: &[(String, WaitingPod)], time: f64) {
+        let mut pod_group_wait_times: BTreeMap<&str, f64> = Default::default();
+        for (pod_name, waiting_pod) in resolved.iter() {
+            if !waiting_pod.is_allowed() {
+                continue;
+            }
+            if let Some((pod_group, _)) = pod_group_of(self.get_pod(pod_name)) {
+                let wait_time = pod_group_wait_times.entry(pod_group).or_default();
+                *wait_time = wait_time.max(time - waiting_pod.wait_start_time);
+            }
+        }
+        for wait_time in pod_group_wait_times.into_values() {
+            self.metrics_collector
+                .borrow_mut()
+                .accumulated_metrics
+                .increment_pod_group_wait_time(wait_time);
+        }
+    }
+
+    fn process_resolved_waiting_pod(
+        &mut self,
+        pod_name: String,
+        mut waiting_pod: WaitingPod,
+        time: f64,
+        delay: f64,
+    ) {
+        self.ctx.cancel_event(waiting_pod.timeout_event_id);
+        match waiting_pod.rejection_reason {
+            Some(reason) => {
+                log_debug!(
+                    self.ctx,
+                    "Waiting pod {:?} is rejected: {}",
+                    pod_name,
+                    reason
+                );
+                self.unreserve(&pod_name, &mut waiting_pod.state);
+                self.move_to_unschedulable_queue(
+                    waiting_pod.queued_pod_info,
+                    time,
+                    Default::default(),
+                );
+            }
+            None => {
+                self.bind(
+                    waiting_pod.queued_pod_info,
+                    waiting_pod.node_name,
+                    &mut waiting_pod.state,
+                    waiting_pod.pod_schedule_time,
+                    time,
+                    delay,
+                );
+            }
+        }
+    }
+
+    /// Removes the pod from waiting pods without unreserving it.
+    fn remove_waiting_pod(&mut self, pod_name: &str) {
+        if let Some(waiting_pod) = self.waiting_pods.remove(pod_name) {
+            self.ctx.cancel_event(waiting_pod.timeout_event_id);
+        }
+    }
+
+    /// Runs PostFilter extension point for the pod which does not fit into any node. If there is a
+    /// node where the pod fits after evicting pods with lower priority, eviction requests are sent
+    /// for them. Victims which are not bound yet are rejected at permit extension point instead.
+    /// The pod itself stays in unschedulable queue and is moved to active queue as soon as evicted
+    /// pods release resources.
+    fn preempt(
+        &mut self,
+        pod_name: &str,
+        state: &CycleState,
+        preemption_time: f64,
+        cycle_sim_duration: f64,
+    ) {
+        let pod = self.objects_cache.pods.get(pod_name).unwrap();
+        let candidate = match self
+            .scheduler_algorithm
+            .post_filter(pod, &self.snapshot(), state)
+        {
+            Some(candidate) => candidate,
+            None => return,
+        };
+
+        log_debug!(
+            self.ctx,
+            "Pod {:?} preempts pods {:?} on node {:?} at {:?}",
+            pod_name,
+            candidate.victims,
+            candidate.node_name,
+            preemption_time,
+        );
+
+        for victim in candidate.victims.into_iter() {
+            if self.waiting_pods.get(&victim).is_some() {
+                self.waiting_pods
+                    .reject(&victim, format!("preempted by pod {:?}", pod_name));
+                continue;
+            }
+            self.ctx.emit(
+                EvictPodRequest {
+                    pod_name: victim.clone(),
+                    reason: EvictionReason::Preemption,
+                },
+                self.api_server,
+                cycle_sim_duration + self.config.sched_to_as_network_delay,
+            );
+            self.pending_evictions.insert(victim);
+        }
+    }
+
+    /// Accounts resources taken by the pod assigned by another scheduler.
+    fn add_other_scheduler_pod(&mut self, pod: Pod, event_time: f64) {
+        let pod_name = pod.metadata.name.clone();
+        let node_name = pod.status.assigned_node.clone();
+        // Node could be removed earlier.
+        let node = match self.objects_cache.nodes.get(&node_name) {
+            Some(node) => node,
+            None => return,
+        };
+        if !pod_fits_node_resources(&pod, node) {
+            self.deferred_other_schedulers_pods.insert(pod_name, pod);
+            return;
+        }
+        self.add_pod(pod);
+        self.reserve_node_resources(&pod_name, &node_name);
+        self.assign_node_to_pod(&pod_name, &node_name);
+        self.other_schedulers_pods.insert(pod_name.clone());
+
+        let pods_to_move = self.pods_to_move_on_cluster_event(&ClusterEvent::AssignedPodAdded {
+            pod: self.get_pod(&pod_name),
+            node: self.get_node(&node_name),
+        });
+        self.move_pods_to_active_queue(pods_to_move, event_time);
+    }
+
+    fn reschedule_pod(&mut self, pod_name: String, event_time: f64) {
+        self.pending_evictions.remove(&pod_name);
+        self.remove_waiting_pod(&pod_name);
+
+        let pod = self.objects_cache.pods.get_mut(&pod_name).unwrap();
+        pod.status.assigned_node = Default::default();
+        let priority = pod.spec.priority;
 
         self.action_queue.push(QueuedPodInfo {
             timestamp: event_time,
             attempts: 1,
             initial_attempt_timestamp: event_time,
             pod_name: Rc::new(pod_name),
+            priority,
+            unschedulable_plugins: Default::default(),
         });
     }
 
@@ -358,25 +957,16 @@ impl Scheduler {
                 unfinished_pod_names
             );
             for pod_name in unfinished_pod_names.into_iter() {
+                // Pods of other schedulers are rescheduled by them.
+                if self.other_schedulers_pods.remove(&pod_name) {
+                    self.pending_evictions.remove(&pod_name);
+                    self.objects_cache.pods.remove(&pod_name);
+                    continue;
+                }
                 self.reschedule_pod(pod_name, event_time);
             }
         }
     }
-
-    fn move_to_active_due_to_pod_freed_resources(&mut self, freed: RuntimeResources) {
-        let mut freed_resources = freed;
-        let check = |requested_resources: &RuntimeResources| {
-            if requested_resources.cpu <= freed_resources.cpu
-                && requested_resources.ram <= freed_resources.ram
-            {
-                freed_resources.cpu -= requested_resources.cpu;
-                freed_resources.ram -= requested_resources.ram;
-                return true;
-            }
-            return false;
-        };
-        self.move_to_active_queue_if(check);
-    }
 }
 
 impl EventHandler for Scheduler {
@@ -388,28 +978,49 @@ impl EventHandler for Scheduler {
             FlushUnschedulableQueueLeftover {} => {
                 self.flush_unschedulable_pods_leftover(event.time);
             }
+            PodPermitWaitTimeout { pod_name } => {
+                log_debug!(
+                    self.ctx,
+                    "Pod {:?} is not permitted before timeout, rejecting it",
+                    pod_name
+                );
+                self.waiting_pods
+                    .reject(&pod_name, "permit wait timeout".to_string());
+                self.process_resolved_waiting_pods(event.time, 0.0);
+                // Released resources might be enough for other pods.
+                self.move_all_to_active_queue(event.time);
+            }
             AddNodeToCache { node } => {
-                let mut allocatable = node.status.allocatable.clone();
+                let node_name = node.metadata.name.clone();
                 self.add_node(node);
 
-                if self.config.enable_unscheduled_pods_conditional_move {
-                    let check = |requested_resources: &RuntimeResources| {
-                        if requested_resources.cpu <= allocatable.cpu
-                            && requested_resources.ram <= allocatable.ram
-                        {
-                            allocatable.cpu -= requested_resources.cpu;
-                            allocatable.ram -= requested_resources.ram;
-                            return false;
-                        }
-                        return true;
-                    };
-                    self.move_to_active_queue_if(check);
-                } else {
-                    self.move_all_to_active_queue();
-                }
+                let pods_to_move = self.pods_to_move_on_cluster_event(&ClusterEvent::NodeAdded(
+                    self.get_node(&node_name),
+                ));
+                self.move_pods_to_active_queue(pods_to_move, event.time);
+            }
+            UpdateNodeInCache { node } => {
+                let node_name = node.metadata.name.clone();
+                // Node could be removed earlier.
+                let cached_node = match self.objects_cache.nodes.get_mut(&node_name) {
+                    Some(cached_node) => cached_node,
+                    None => return,
+                };
+                // Allocatable resources are tracked by the scheduler itself according to its
+                // assignments, so take everything except them.
+                cached_node.metadata = node.metadata;
+                cached_node.spec = node.spec;
+                cached_node.status.conditions = node.status.conditions;
+
+                // Some unschedulable pods may tolerate changed taints now.
+                let pods_to_move = self.pods_to_move_on_cluster_event(&ClusterEvent::NodeUpdated(
+                    self.get_node(&node_name),
+                ));
+                self.move_pods_to_active_queue(pods_to_move, event.time);
             }
             PodScheduleRequest { pod } => {
                 let pod_name = pod.metadata.name.clone();
+                let priority = pod.spec.priority;
                 self.add_pod(pod);
 
                 self.action_queue.push(QueuedPodInfo {
@@ -417,6 +1028,8 @@ impl EventHandler for Scheduler {
                     attempts: 1,
                     initial_attempt_timestamp: event.time,
                     pod_name: Rc::new(pod_name),
+                    priority,
+                    unschedulable_plugins: Default::default(),
                 });
             }
             PodFinishedRunning {
@@ -424,7 +1037,16 @@ impl EventHandler for Scheduler {
                 node_name,
                 ..
             } => {
-                let pod = self.objects_cache.pods.remove(&pod_name).unwrap();
+                self.deferred_other_schedulers_pods.remove(&pod_name);
+                // Pod of another scheduler might be not added to cache if its node had been
+                // removed from it.
+                let pod = match self.objects_cache.pods.get(&pod_name) {
+                    Some(pod) if pod.status.assigned_node == node_name => pod.clone(),
+                    _ => return,
+                };
+                self.objects_cache.pods.remove(&pod_name);
+                self.other_schedulers_pods.remove(&pod_name);
+                self.pending_evictions.remove(&pod_name);
 
                 self.assignments
                     .get_mut(&node_name)
@@ -432,19 +1054,107 @@ impl EventHandler for Scheduler {
                     .remove(&pod_name);
                 self.release_node_resources(&pod);
 
-                if self.config.enable_unscheduled_pods_conditional_move {
-                    self.move_to_active_due_to_pod_freed_resources(pod.spec.resources.requests.clone());
+                let pods_to_move =
+                    self.pods_to_move_on_cluster_event(&ClusterEvent::AssignedPodDeleted {
+                        pod: &pod,
+                        node: self.get_node(&node_name),
+                    });
+                self.move_pods_to_active_queue(pods_to_move, event.time);
+            }
+            PodEvictedFromNode {
+                pod_name,
+                node_name,
+                ..
+            } => {
+                self.pending_evictions.remove(&pod_name);
+                self.deferred_other_schedulers_pods.remove(&pod_name);
+                // Pod could be removed or rescheduled due to node removal earlier.
+                let pod = match self.objects_cache.pods.get(&pod_name) {
+                    Some(pod) if pod.status.assigned_node == node_name => pod.clone(),
+                    _ => return,
+                };
+
+                self.assignments
+                    .get_mut(&node_name)
+                    .unwrap()
+                    .remove(&pod_name);
+                self.release_node_resources(&pod);
+                // Pods of other schedulers are put back to the queue by them.
+                if self.other_schedulers_pods.remove(&pod_name) {
+                    self.objects_cache.pods.remove(&pod_name);
                 } else {
-                    self.move_all_to_active_queue();
+                    self.reschedule_pod(pod_name, event.time);
                 }
+
+                let pods_to_move =
+                    self.pods_to_move_on_cluster_event(&ClusterEvent::AssignedPodDeleted {
+                        pod: &pod,
+                        node: self.get_node(&node_name),
+                    });
+                self.move_pods_to_active_queue(pods_to_move, event.time);
+            }
+            AddPodToCache { pod } => {
+                self.add_other_scheduler_pod(pod, event.time);
+            }
+            PodAssignmentConflict {
+                pod_name,
+                node_name,
+            } => {
+                // Pod could be removed or rescheduled due to node removal earlier.
+                match self.objects_cache.pods.get(&pod_name) {
+                    Some(pod) if pod.status.assigned_node == node_name => {}
+                    _ => return,
+                };
+                log_debug!(
+                    self.ctx,
+                    "Assignment of pod {:?} to node {:?} is rejected due to conflict, retrying",
+                    pod_name,
+                    node_name
+                );
+                self.unreserve(&pod_name, &mut CycleState::default());
+
+                let deferred_pods: Vec<String> = self
+                    .deferred_other_schedulers_pods
+                    .iter()
+                    .filter(|(_, pod)| pod.status.assigned_node == node_name)
+                    .map(|(pod_name, _)| pod_name.clone())
+                    .collect();
+                for deferred_pod_name in deferred_pods.into_iter() {
+                    let pod = self
+                        .deferred_other_schedulers_pods
+                        .remove(&deferred_pod_name)
+                        .unwrap();
+                    self.add_other_scheduler_pod(pod, event.time);
+                }
+
+                // Cache is stale, so the pod is retried after backoff when the assignment which
+                // won the conflict is likely added to cache.
+                let priority = self.get_pod(&pod_name).spec.priority;
+                self.move_to_active_or_backoff_queue(
+                    QueuedPodInfo {
+                        timestamp: event.time,
+                        attempts: 1,
+                        initial_attempt_timestamp: event.time,
+                        pod_name: Rc::new(pod_name),
+                        priority,
+                        unschedulable_plugins: Default::default(),
+                    },
+                    event.time,
+                );
             }
             RemoveNodeFromCache { node_name } => {
                 self.objects_cache.nodes.remove(&node_name).unwrap();
+                self.deferred_other_schedulers_pods
+                    .retain(|_, pod| pod.status.assigned_node != node_name);
                 self.reschedule_unfinished_pods(&node_name, event.time);
             }
             RemovePodFromCache { pod_name } => {
                 // Remove request might come after finish request. So we check whether pod is still
                 // in objects cache. If it's finished earlier than it's removed from cache.
+                self.pending_evictions.remove(&pod_name);
+                self.remove_waiting_pod(&pod_name);
+                self.other_schedulers_pods.remove(&pod_name);
+                self.deferred_other_schedulers_pods.remove(&pod_name);
                 if let Some(pod) = self.objects_cache.pods.remove(&pod_name) {
                     // Pod is still not finished - should clean up info about it.
                     let assigned_node_name = &pod.status.assigned_node;
@@ -459,13 +1169,12 @@ impl EventHandler for Scheduler {
                             .unwrap()
                             .remove(&pod_name);
 
-                        if self.config.enable_unscheduled_pods_conditional_move {
-                            self.move_to_active_due_to_pod_freed_resources(
-                                pod.spec.resources.requests.clone(),
-                            );
-                        } else {
-                            self.move_all_to_active_queue();
-                        }                   
+                        let pods_to_move =
+                            self.pods_to_move_on_cluster_event(&ClusterEvent::AssignedPodDeleted {
+                                pod: &pod,
+                                node: self.get_node(assigned_node_name),
+                            });
+                        self.move_pods_to_active_queue(pods_to_move, event.time);
                     }
                     // Otherwise, pod is in one of scheduling queues. So when we process popping
                     // from queue - just skip it with the help of checking existence in objects cache.
@@ -485,6 +1194,7 @@ mod tests {
 
     use crate::core::node::Node;
     use crate::core::pod::Pod;
+    use crate::core::scheduler::framework::CycleState;
     use crate::core::scheduler::interface::ScheduleError;
     use crate::core::scheduler::kube_scheduler::{default_kube_scheduler_config, KubeScheduler};
     use crate::core::scheduler::scheduler::Scheduler;
@@ -498,11 +1208,9 @@ mod tests {
 
         Scheduler::new(
             0,
-            Box::new(KubeScheduler {
-                config: default_kube_scheduler_config(),
-            }),
+            Box::new(KubeScheduler::new(default_kube_scheduler_config()).unwrap()),
             fake_sim.create_context("scheduler"),
-            Rc::new(default_test_simulation_config(None)),
+            default_test_simulation_config(None).default_scheduler_component(),
             Rc::new(RefCell::new(MetricsCollector::new())),
         )
     }
@@ -524,7 +1232,10 @@ mod tests {
         let scheduler = create_scheduler();
         let pod = Pod::new("pod_1".to_string(), 4000, 16000, Some(5.0));
         assert_eq!(
-            scheduler.schedule_one(&pod).err().unwrap(),
+            scheduler
+                .schedule_one(&pod, &mut CycleState::default())
+                .err()
+                .unwrap(),
             ScheduleError::NoNodesInCluster
         );
     }
@@ -536,7 +1247,10 @@ mod tests {
         let node = Node::new("node1".to_string(), 3000, 8589934592);
         register_nodes(&mut scheduler, vec![node]);
         assert_eq!(
-            scheduler.schedule_one(&pod).err().unwrap(),
+            scheduler
+                .schedule_one(&pod, &mut CycleState::default())
+                .err()
+                .unwrap(),
             ScheduleError::RequestedResourcesAreZeros
         );
     }
@@ -548,7 +1262,10 @@ mod tests {
         let node = Node::new("node1".to_string(), 3000, 8589934592);
         register_nodes(&mut scheduler, vec![node]);
         assert_eq!(
-            scheduler.schedule_one(&pod).err().unwrap(),
+            scheduler
+                .schedule_one(&pod, &mut CycleState::default())
+                .err()
+                .unwrap(),
             ScheduleError::NoSufficientResources
         );
     }
@@ -569,7 +1286,10 @@ mod tests {
         // node3 - max score - choose it for scheduling
         register_nodes(&mut scheduler, vec![node1, node2, node3.clone()]);
         assert_eq!(
-            *scheduler.schedule_one(&pod).ok().unwrap(),
+            *scheduler
+                .schedule_one(&pod, &mut CycleState::default())
+                .ok()
+                .unwrap(),
             node3.metadata.name
         );
     }
@@ -588,15 +1308,36 @@ mod tests {
             &mut scheduler,
             vec![pod1.clone(), pod2.clone(), pod3.clone(), pod4.clone()],
         );
-        assert_eq!(&*scheduler.schedule_one(&pod1).ok().unwrap(), node_name);
+        assert_eq!(
+            &*scheduler
+                .schedule_one(&pod1, &mut CycleState::default())
+                .ok()
+                .unwrap(),
+            node_name
+        );
         scheduler.reserve_node_resources(&pod1.metadata.name, node_name);
-        assert_eq!(&*scheduler.schedule_one(&pod2).ok().unwrap(), node_name);
+        assert_eq!(
+            &*scheduler
+                .schedule_one(&pod2, &mut CycleState::default())
+                .ok()
+                .unwrap(),
+            node_name
+        );
         scheduler.reserve_node_resources(&pod2.metadata.name, node_name);
-        assert_eq!(&*scheduler.schedule_one(&pod3).ok().unwrap(), node_name);
+        assert_eq!(
+            &*scheduler
+                .schedule_one(&pod3, &mut CycleState::default())
+                .ok()
+                .unwrap(),
+            node_name
+        );
         scheduler.reserve_node_resources(&pod3.metadata.name, node_name);
         // there is no place left on node for the fourth pod
         assert_eq!(
-            scheduler.schedule_one(&pod4).err().unwrap(),
+            scheduler
+                .schedule_one(&pod4, &mut CycleState::default())
+                .err()
+                .unwrap(),
             ScheduleError::NoSufficientResources
         );
     }
@@ -0,0 +1,190 @@
+//! Implements descheduler which evicts running pods to let the scheduler place them better, e.g.
+//! after new nodes are added to the cluster.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use dslab_core::{cast, log_debug, log_info, Event, EventHandler, SimulationContext};
+use serde::Deserialize;
+
+use crate::config::SimulationConfig;
+use crate::core::common::SimComponentId;
+use crate::core::events::{
+    DeschedulerRequest, DeschedulerResponse, EvictPodRequest, RunDeschedulerCycle,
+};
+use crate::core::pod::EvictionReason;
+
+use crate::descheduler::interface::{ClusterState, DeschedulerStrategy};
+use crate::descheduler::strategy::resolve_descheduler_strategy;
+
+/// Every `scan_interval` seconds descheduler requests nodes and assigned pods from persistent
+/// storage and runs configured strategies on them one after another.
+///
+/// Pods chosen by strategies are evicted from nodes with `EvictPodRequest`, then they go back to
+/// the scheduling queue like pods re-created by their controllers. The scheduler is free to place
+/// them to the same node again. Since the state comes from persistent storage with some delay, pods
+/// could have finished or been removed by the time of eviction, then they are not evicted.
+pub struct Descheduler {
+    api_server: SimComponentId,
+
+    /// Last time when descheduler started cycle with sending request to persistent storage.
+    last_cycle_time: f64,
+
+    /// Strategies with their names in the order they are run.
+    strategies: Vec<(String, Box<dyn DeschedulerStrategy>)>,
+
+    ctx: SimulationContext,
+    config: Rc<SimulationConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct DeschedulerConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    #[serde(default = "scan_interval_default")]
+    pub scan_interval: f64,
+    /// Strategies which are run in the listed order, names should be unique.
+    #[serde(default)]
+    pub strategies: Vec<DeschedulerStrategyConfig>,
+}
+
+/// Strategy is resolved by name in `resolve_descheduler_strategy`.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct DeschedulerStrategyConfig {
+    pub name: String,
+    // Arguments of configurable strategies, their format depends on the strategy.
+    #[serde(default)]
+    pub args: Option<serde_yaml::Value>,
+}
+
+impl Default for DeschedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+            scan_interval: scan_interval_default(),
+            strategies: Default::default(),
+        }
+    }
+}
+
+fn enabled_default() -> bool {
+    false // disabled by default
+}
+fn scan_interval_default() -> f64 {
+    60.0 // 60 seconds
+}
+
+impl Descheduler {
+    /// Returns a description of the problem if strategies are misconfigured.
+    pub fn new(
+        api_server: SimComponentId,
+        ctx: SimulationContext,
+        config: Rc<SimulationConfig>,
+    ) -> Result<Self, String> {
+        let mut strategy_names: HashSet<&str> = Default::default();
+        let mut strategies = vec![];
+        for strategy_config in config.descheduler.strategies.iter() {
+            if !strategy_names.insert(&strategy_config.name) {
+                return Err(format!(
+                    "descheduler strategy {:?} is listed several times",
+                    strategy_config.name
+                ));
+            }
+            let strategy =
+                resolve_descheduler_strategy(&strategy_config.name, strategy_config.args.as_ref())
+                    .map_err(|err| {
+                        format!(
+                            "Invalid config of descheduler strategy {:?}: {}",
+                            strategy_config.name, err
+                        )
+                    })?;
+            strategies.push((strategy_config.name.clone(), strategy));
+        }
+
+        Ok(Self {
+            api_server,
+            last_cycle_time: 0.0,
+            strategies,
+            ctx,
+            config,
+        })
+    }
+
+    pub fn start(&mut self) {
+        log_info!(
+            self.ctx,
+            "Descheduler started running every {} seconds",
+            self.config.descheduler.scan_interval
+        );
+        self.ctx.emit_self_now(RunDeschedulerCycle {});
+    }
+
+    /// Does not simulate descheduler cycle time, supposing it works instantly.
+    fn run_descheduler_cycle(&mut self, event_time: f64) {
+        self.last_cycle_time = event_time;
+        self.ctx.emit(
+            DeschedulerRequest {},
+            self.api_server,
+            self.config.as_to_descheduler_network_delay,
+        );
+        // Cycle is rescheduled after the response with cluster state is processed.
+    }
+
+    fn evict_pod_request(&mut self, pod_name: String, strategy: &str) {
+        log_debug!(
+            self.ctx,
+            "Evicting pod {:?} due to strategy {}",
+            pod_name,
+            strategy
+        );
+
+        self.ctx.emit(
+            EvictPodRequest {
+                pod_name,
+                reason: EvictionReason::Descheduling {
+                    strategy: strategy.to_string(),
+                },
+            },
+            self.api_server,
+            self.config.as_to_descheduler_network_delay,
+        );
+    }
+
+    fn run_strategies(&mut self, mut state: ClusterState) {
+        let mut evictions: Vec<(String, usize)> = vec![];
+        for (strategy_idx, (_, strategy)) in self.strategies.iter().enumerate() {
+            for pod_name in strategy.pods_to_evict(&state) {
+                state.remove_pod(&pod_name);
+                evictions.push((pod_name, strategy_idx));
+            }
+        }
+
+        for (pod_name, strategy_idx) in evictions {
+            let strategy_name = self.strategies[strategy_idx].0.clone();
+            self.evict_pod_request(pod_name, &strategy_name);
+        }
+    }
+}
+
+impl EventHandler for Descheduler {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            RunDeschedulerCycle {} => {
+                self.run_descheduler_cycle(event.time);
+            }
+            DeschedulerResponse {
+                nodes,
+                assigned_pods,
+            } => {
+                self.run_strategies(ClusterState::new(nodes, assigned_pods));
+
+                let mut delay = self.config.descheduler.scan_interval;
+                if event.time - self.last_cycle_time > self.config.descheduler.scan_interval {
+                    // schedule now as response waiting took longer than scan interval
+                    delay = 0.0;
+                }
+                self.ctx.emit_self(RunDeschedulerCycle {}, delay);
+            }
+        })
+    }
+}
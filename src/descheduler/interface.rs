@@ -0,0 +1,72 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::node::Node;
+use crate::core::pod::Pod;
+
+/// Placement of pods known to persistent storage at the start of a descheduler cycle. Pods chosen
+/// for eviction by a strategy are removed from it before the next strategy is run.
+#[derive(Default)]
+pub struct ClusterState {
+    /// Map from node name to the node with resources left after assignments.
+    pub nodes: BTreeMap<String, Node>,
+    /// Map of pod names and pods which are assigned to nodes.
+    pub pods: BTreeMap<String, Pod>,
+    /// Map from node name to names of pods assigned to that node.
+    pub assignments: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ClusterState {
+    pub fn new(nodes: Vec<Node>, assigned_pods: Vec<Pod>) -> Self {
+        let mut state = Self {
+            nodes: nodes
+                .into_iter()
+                .map(|node| (node.metadata.name.clone(), node))
+                .collect(),
+            ..Default::default()
+        };
+        for node_name in state.nodes.keys() {
+            state
+                .assignments
+                .insert(node_name.clone(), Default::default());
+        }
+        for pod in assigned_pods {
+            state
+                .assignments
+                .entry(pod.status.assigned_node.clone())
+                .or_default()
+                .insert(pod.metadata.name.clone());
+            state.pods.insert(pod.metadata.name.clone(), pod);
+        }
+        state
+    }
+
+    /// Pods assigned to the node.
+    pub fn pods_on_node<'a>(&'a self, node_name: &str) -> impl Iterator<Item = &'a Pod> + 'a {
+        self.assignments
+            .get(node_name)
+            .into_iter()
+            .flatten()
+            .map(|pod_name| &self.pods[pod_name])
+    }
+
+    /// Removes the evicted pod and releases its resources on the node.
+    pub fn remove_pod(&mut self, pod_name: &str) {
+        let Some(pod) = self.pods.remove(pod_name) else {
+            return;
+        };
+        if let Some(node_pods) = self.assignments.get_mut(&pod.status.assigned_node) {
+            node_pods.remove(pod_name);
+        }
+        if let Some(node) = self.nodes.get_mut(&pod.status.assigned_node) {
+            node.status.allocatable.cpu += pod.spec.resources.requests.cpu;
+            node.status.allocatable.ram += pod.spec.resources.requests.ram;
+        }
+    }
+}
+
+/// Strategy of choosing running pods to evict, so that the scheduler places them again
+/// hopefully better. It is configured by name in descheduler config.
+pub trait DeschedulerStrategy {
+    /// Returns names of pods to evict.
+    fn pods_to_evict(&self, state: &ClusterState) -> Vec<String>;
+}
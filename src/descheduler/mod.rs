@@ -0,0 +1,3 @@
+pub mod descheduler;
+pub mod interface;
+pub mod strategy;
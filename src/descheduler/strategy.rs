@@ -0,0 +1,393 @@
+//! Implementations of descheduler strategies similar to the ones of kubernetes-sigs/descheduler.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::core::node::Node;
+use crate::core::pod::Pod;
+use crate::core::scheduler::plugin::{
+    pod_fits_node_resources, pod_group_of, pod_matches_node_affinity,
+};
+use crate::descheduler::interface::{ClusterState, DeschedulerStrategy};
+
+/// Creates the strategy with the name from its args, None args mean defaults.
+pub fn resolve_descheduler_strategy(
+    name: &str,
+    args: Option<&serde_yaml::Value>,
+) -> Result<Box<dyn DeschedulerStrategy>, String> {
+    match name {
+        "LowNodeUtilization" => {
+            let strategy = match args {
+                Some(args) => serde_yaml::from_value::<LowNodeUtilization>(args.clone())
+                    .map_err(|err| err.to_string())?,
+                None => LowNodeUtilization::default(),
+            };
+            strategy.validate()?;
+            Ok(Box::new(strategy))
+        }
+        "RemoveDuplicates" => without_args::<RemoveDuplicates>(args),
+        "RemovePodsViolatingNodeAffinity" => without_args::<RemovePodsViolatingNodeAffinity>(args),
+        _ => Err(format!("unknown descheduler strategy {:?}", name)),
+    }
+}
+
+fn without_args<S: DeschedulerStrategy + Default + 'static>(
+    args: Option<&serde_yaml::Value>,
+) -> Result<Box<dyn DeschedulerStrategy>, String> {
+    match args {
+        Some(_) => Err("strategy does not accept args".to_string()),
+        None => Ok(Box::new(S::default())),
+    }
+}
+
+/// Shares of node capacity of cpu and ram.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceThresholds {
+    pub cpu: f64,
+    pub ram: f64,
+}
+
+fn thresholds_default() -> ResourceThresholds {
+    ResourceThresholds { cpu: 0.2, ram: 0.2 }
+}
+
+fn target_thresholds_default() -> ResourceThresholds {
+    ResourceThresholds { cpu: 0.5, ram: 0.5 }
+}
+
+/// Shares of node capacity of cpu and ram requested by pods assigned to the node.
+fn requested_shares(node: &Node) -> (f64, f64) {
+    let share = |capacity: u64, allocatable: u64| {
+        if capacity == 0 {
+            return 0.0;
+        }
+        capacity.saturating_sub(allocatable) as f64 / capacity as f64
+    };
+    let capacity = &node.status.capacity;
+    let allocatable = &node.status.allocatable;
+    (
+        share(capacity.cpu as u64, allocatable.cpu as u64),
+        share(capacity.ram, allocatable.ram),
+    )
+}
+
+// LowNodeUtilization evicts pods from overutilized nodes, which requests exceed target thresholds
+// for cpu or ram, if there are underutilized nodes, which requests are below thresholds for both
+// resources. Pods are evicted until overutilized nodes get back to target thresholds, as long as
+// underutilized nodes have room for them without exceeding target thresholds.
+// Pods with lower priority are evicted first.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LowNodeUtilization {
+    #[serde(default = "thresholds_default")]
+    pub thresholds: ResourceThresholds,
+    #[serde(default = "target_thresholds_default")]
+    pub target_thresholds: ResourceThresholds,
+}
+
+impl Default for LowNodeUtilization {
+    fn default() -> Self {
+        Self {
+            thresholds: thresholds_default(),
+            target_thresholds: target_thresholds_default(),
+        }
+    }
+}
+
+impl LowNodeUtilization {
+    fn validate(&self) -> Result<(), String> {
+        let valid = |threshold: f64, target_threshold: f64| {
+            0.0 <= threshold && threshold <= target_threshold && target_threshold <= 1.0
+        };
+        if !valid(self.thresholds.cpu, self.target_thresholds.cpu)
+            || !valid(self.thresholds.ram, self.target_thresholds.ram)
+        {
+            return Err(format!(
+                "thresholds {:?} should not exceed target thresholds {:?} within [0, 1]",
+                self.thresholds, self.target_thresholds
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl DeschedulerStrategy for LowNodeUtilization {
+    fn pods_to_evict(&self, state: &ClusterState) -> Vec<String> {
+        let mut underutilized_nodes: Vec<&Node> = vec![];
+        let mut overutilized_nodes: Vec<(&Node, f64)> = vec![];
+        for node in state.nodes.values() {
+            let (cpu_share, ram_share) = requested_shares(node);
+            if cpu_share < self.thresholds.cpu && ram_share < self.thresholds.ram {
+                underutilized_nodes.push(node);
+            } else if cpu_share > self.target_thresholds.cpu
+                || ram_share > self.target_thresholds.ram
+            {
+                overutilized_nodes.push((node, cpu_share + ram_share));
+            }
+        }
+        if underutilized_nodes.is_empty() || overutilized_nodes.is_empty() {
+            return vec![];
+        }
+
+        // Resources which underutilized nodes may take until they reach target thresholds.
+        let (mut cpu_available, mut ram_available) = (0.0, 0.0);
+        for node in underutilized_nodes {
+            let (cpu_share, ram_share) = requested_shares(node);
+            let capacity = &node.status.capacity;
+            cpu_available += (self.target_thresholds.cpu - cpu_share) * capacity.cpu as f64;
+            ram_available += (self.target_thresholds.ram - ram_share) * capacity.ram as f64;
+        }
+
+        // The most utilized nodes are drained first.
+        overutilized_nodes.sort_by(|(_, lhs), (_, rhs)| rhs.total_cmp(lhs));
+        let mut pods_to_evict = vec![];
+        for (node, _) in overutilized_nodes {
+            let capacity = &node.status.capacity;
+            let cpu_target = self.target_thresholds.cpu * capacity.cpu as f64;
+            let ram_target = self.target_thresholds.ram * capacity.ram as f64;
+            let mut cpu_requested = capacity.cpu.saturating_sub(node.status.allocatable.cpu) as f64;
+            let mut ram_requested = capacity.ram.saturating_sub(node.status.allocatable.ram) as f64;
+
+            let mut pods: Vec<&Pod> = state.pods_on_node(&node.metadata.name).collect();
+            pods.sort_by_key(|pod| pod.spec.priority);
+            for pod in pods {
+                if cpu_requested <= cpu_target && ram_requested <= ram_target {
+                    break;
+                }
+                let requests = &pod.spec.resources.requests;
+                if requests.cpu as f64 > cpu_available || requests.ram as f64 > ram_available {
+                    continue;
+                }
+                cpu_available -= requests.cpu as f64;
+                ram_available -= requests.ram as f64;
+                cpu_requested -= requests.cpu as f64;
+                ram_requested -= requests.ram as f64;
+                pods_to_evict.push(pod.metadata.name.clone());
+            }
+        }
+        pods_to_evict
+    }
+}
+
+// RemoveDuplicates spreads pods of the same pod group among nodes. If a node runs several pods of
+// a group and more than the average number of group pods per node rounded up, the extra pods are
+// evicted. Pods without the pod group label are not evicted.
+#[derive(Default)]
+pub struct RemoveDuplicates {}
+
+impl DeschedulerStrategy for RemoveDuplicates {
+    fn pods_to_evict(&self, state: &ClusterState) -> Vec<String> {
+        if state.nodes.is_empty() {
+            return vec![];
+        }
+
+        // Map from pod group to names of its pods on each node.
+        let mut pod_groups: BTreeMap<&str, BTreeMap<&str, Vec<&str>>> = Default::default();
+        for pod in state.pods.values() {
+            if let Some((pod_group, _)) = pod_group_of(pod) {
+                pod_groups
+                    .entry(pod_group)
+                    .or_default()
+                    .entry(&pod.status.assigned_node)
+                    .or_default()
+                    .push(&pod.metadata.name);
+            }
+        }
+
+        let mut pods_to_evict = vec![];
+        for node_pods in pod_groups.values() {
+            let group_size: usize = node_pods.values().map(|pods| pods.len()).sum();
+            let upper_average = group_size.div_ceil(state.nodes.len());
+            for pods in node_pods.values() {
+                if pods.len() > 1 && pods.len() > upper_average {
+                    pods_to_evict.extend(pods[upper_average..].iter().map(|pod| pod.to_string()));
+                }
+            }
+        }
+        pods_to_evict
+    }
+}
+
+// RemovePodsViolatingNodeAffinity evicts pods which node selector or required node affinity is not
+// satisfied by their node anymore, e.g. after node labels have changed. A pod is evicted only if
+// some other node satisfies it and has enough resources for it.
+#[derive(Default)]
+pub struct RemovePodsViolatingNodeAffinity {}
+
+impl DeschedulerStrategy for RemovePodsViolatingNodeAffinity {
+    fn pods_to_evict(&self, state: &ClusterState) -> Vec<String> {
+        state
+            .pods
+            .values()
+            .filter(|pod| match state.nodes.get(&pod.status.assigned_node) {
+                Some(node) => !pod_matches_node_affinity(pod, node),
+                None => false,
+            })
+            .filter(|pod| {
+                state.nodes.values().any(|node| {
+                    node.metadata.name != pod.status.assigned_node
+                        && pod_matches_node_affinity(pod, node)
+                        && pod_fits_node_resources(pod, node)
+                })
+            })
+            .map(|pod| pod.metadata.name.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::core::node::Node;
+    use crate::core::pod::Pod;
+    use crate::core::scheduler::plugin::POD_GROUP_LABEL;
+    use crate::descheduler::interface::ClusterState;
+    use crate::descheduler::strategy::resolve_descheduler_strategy;
+
+    const RAM: u64 = 17179869184;
+
+    fn assigned_pod(name: &str, cpu: u32, node_name: &str) -> Pod {
+        let mut pod = Pod::new(name.to_string(), cpu, 1073741824, None);
+        pod.status.assigned_node = node_name.to_string();
+        pod
+    }
+
+    // Builds the state as persistent storage has it, with resources of pods taken from nodes.
+    fn cluster_state(mut nodes: Vec<Node>, pods: Vec<Pod>) -> ClusterState {
+        for node in nodes.iter_mut() {
+            for pod in pods
+                .iter()
+                .filter(|pod| pod.status.assigned_node == node.metadata.name)
+            {
+                node.status.allocatable.cpu -= pod.spec.resources.requests.cpu;
+                node.status.allocatable.ram -= pod.spec.resources.requests.ram;
+            }
+        }
+        ClusterState::new(nodes, pods)
+    }
+
+    #[test]
+    fn test_low_node_utilization() {
+        let strategy = resolve_descheduler_strategy(
+            "LowNodeUtilization",
+            Some(
+                &serde_yaml::from_str(
+                    r#"
+            thresholds: {cpu: 0.2, ram: 0.2}
+            target_thresholds: {cpu: 0.5, ram: 0.5}
+            "#,
+                )
+                .unwrap(),
+            ),
+        )
+        .unwrap();
+
+        let mut high_priority_pod = assigned_pod("pod_1", 2000, "node_1");
+        high_priority_pod.spec.priority = 10;
+        let mut state = cluster_state(
+            vec![
+                Node::new("node_1".to_string(), 10000, RAM),
+                Node::new("node_2".to_string(), 10000, RAM),
+                Node::new("node_3".to_string(), 10000, RAM),
+            ],
+            vec![
+                high_priority_pod,
+                assigned_pod("pod_2", 2000, "node_1"),
+                assigned_pod("pod_3", 2000, "node_1"),
+                assigned_pod("pod_4", 2000, "node_1"),
+                assigned_pod("pod_5", 4000, "node_3"),
+            ],
+        );
+
+        // node_1 is overutilized and gets back to the target after evicting two pods with the
+        // lowest priority.
+        assert_eq!(vec!["pod_2", "pod_3"], strategy.pods_to_evict(&state));
+
+        // No underutilized nodes are left to take pods.
+        state
+            .nodes
+            .get_mut("node_2")
+            .unwrap()
+            .status
+            .allocatable
+            .cpu = 7000;
+        assert!(strategy.pods_to_evict(&state).is_empty());
+
+        assert!(resolve_descheduler_strategy(
+            "LowNodeUtilization",
+            Some(&serde_yaml::from_str("thresholds: {cpu: 0.6, ram: 0.2}").unwrap()),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let strategy = resolve_descheduler_strategy("RemoveDuplicates", None).unwrap();
+        let group_pod = |name: &str, node_name: &str| {
+            let mut pod = assigned_pod(name, 1000, node_name);
+            pod.metadata
+                .labels
+                .insert(POD_GROUP_LABEL.to_string(), "web".to_string());
+            pod
+        };
+        let state = cluster_state(
+            vec![
+                Node::new("node_1".to_string(), 10000, RAM),
+                Node::new("node_2".to_string(), 10000, RAM),
+            ],
+            vec![
+                group_pod("web_0", "node_1"),
+                group_pod("web_1", "node_1"),
+                group_pod("web_2", "node_1"),
+                group_pod("web_3", "node_2"),
+                assigned_pod("pod_1", 1000, "node_1"),
+                assigned_pod("pod_2", 1000, "node_1"),
+            ],
+        );
+
+        // Two pods of the group on each node are balanced enough.
+        assert_eq!(vec!["web_2"], strategy.pods_to_evict(&state));
+        assert!(resolve_descheduler_strategy(
+            "RemoveDuplicates",
+            Some(&serde_yaml::from_str("{}").unwrap())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_remove_pods_violating_node_affinity() {
+        let strategy =
+            resolve_descheduler_strategy("RemovePodsViolatingNodeAffinity", None).unwrap();
+        let zone_node = |name: &str, zone: &str, cpu: u32| {
+            let mut node = Node::new(name.to_string(), cpu, RAM);
+            node.metadata
+                .labels
+                .insert("zone".to_string(), zone.to_string());
+            node
+        };
+        let zone_pod = |name: &str, cpu: u32, zone: &str, node_name: &str| {
+            let mut pod = assigned_pod(name, cpu, node_name);
+            pod.spec.node_selector = HashMap::from([("zone".to_string(), zone.to_string())]);
+            pod
+        };
+        let state = cluster_state(
+            vec![
+                zone_node("node_1", "zone_a", 10000),
+                zone_node("node_2", "zone_b", 10000),
+                zone_node("node_3", "zone_c", 2000),
+            ],
+            vec![
+                zone_pod("pod_1", 1000, "zone_b", "node_1"),
+                zone_pod("pod_2", 1000, "zone_a", "node_1"),
+                // No node of zone_c has room for the pod.
+                zone_pod("pod_3", 4000, "zone_c", "node_1"),
+            ],
+        );
+
+        assert_eq!(vec!["pod_1"], strategy.pods_to_evict(&state));
+        assert!(resolve_descheduler_strategy("RemovePodsViolatingEverything", None).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Distributions of times between node failures and of node repair times.
+
+use std::path::PathBuf;
+
+use dslab_core::SimulationContext;
+use serde::Deserialize;
+
+/// Distributions of time in seconds.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub enum FailureTimeDistribution {
+    Exponential {
+        mean: f64,
+    },
+    Weibull {
+        shape: f64,
+        scale: f64,
+    },
+    /// Times are taken uniformly from the observed ones, listed one per line in CSV file without
+    /// header.
+    Empirical {
+        path: String,
+    },
+}
+
+/// Sampler of the distribution, which reads observed times of empirical distribution beforehand.
+pub enum FailureTimeSampler {
+    Exponential { mean: f64 },
+    Weibull { shape: f64, scale: f64 },
+    Empirical { times: Vec<f64> },
+}
+
+impl FailureTimeSampler {
+    pub fn new(distribution: &FailureTimeDistribution) -> Result<Self, String> {
+        match *distribution {
+            FailureTimeDistribution::Exponential { mean } => {
+                if mean <= 0.0 {
+                    return Err(format!("mean should be positive, got {}", mean));
+                }
+                Ok(Self::Exponential { mean })
+            }
+            FailureTimeDistribution::Weibull { shape, scale } => {
+                if shape <= 0.0 || scale <= 0.0 {
+                    return Err(format!(
+                        "shape and scale should be positive, got {} and {}",
+                        shape, scale
+                    ));
+                }
+                Ok(Self::Weibull { shape, scale })
+            }
+            FailureTimeDistribution::Empirical { ref path } => {
+                let trace_str = std::fs::read_to_string(PathBuf::from(path))
+                    .map_err(|err| format!("cannot read {:?}: {}", path, err))?;
+                Ok(Self::Empirical {
+                    times: read_times_from_str(&trace_str)?,
+                })
+            }
+        }
+    }
+
+    /// Samples time with the simulation's random generator, so results are reproducible with the
+    /// same seed.
+    pub fn sample(&self, ctx: &SimulationContext) -> f64 {
+        match self {
+            Self::Exponential { mean } => -mean * (1.0 - ctx.rand()).ln(),
+            // Inverse transform of the Weibull distribution function.
+            Self::Weibull { shape, scale } => scale * (-(1.0 - ctx.rand()).ln()).powf(1.0 / shape),
+            Self::Empirical { times } => times[ctx.gen_range(0..times.len())],
+        }
+    }
+}
+
+fn read_times_from_str(trace_str: &str) -> Result<Vec<f64>, String> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(trace_str.as_bytes());
+
+    let mut times = vec![];
+    for row in csv_reader.deserialize() {
+        let time: f64 = row.map_err(|err| err.to_string())?;
+        if time < 0.0 {
+            return Err(format!("time should not be negative, got {}", time));
+        }
+        times.push(time);
+    }
+    if times.is_empty() {
+        return Err("empirical distribution should have observations".to_string());
+    }
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_times_from_str, FailureTimeDistribution, FailureTimeSampler};
+
+    #[test]
+    fn test_read_empirical_times() {
+        assert_eq!(
+            vec![60.0, 3600.0, 0.5],
+            read_times_from_str("60\n3600\n0.5\n").unwrap()
+        );
+        assert!(read_times_from_str("").is_err());
+        assert!(read_times_from_str("60\n-1\n").is_err());
+        assert!(read_times_from_str("60\nnever\n").is_err());
+    }
+
+    #[test]
+    fn test_invalid_distributions() {
+        assert!(
+            FailureTimeSampler::new(&FailureTimeDistribution::Exponential { mean: 0.0 }).is_err()
+        );
+        assert!(FailureTimeSampler::new(&FailureTimeDistribution::Weibull {
+            shape: -1.0,
+            scale: 10.0
+        })
+        .is_err());
+        assert!(
+            FailureTimeSampler::new(&FailureTimeDistribution::Empirical {
+                path: "/nonexistent/repair_times.csv".to_string()
+            })
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Implements failure injector which fails nodes at random times and repairs them afterwards, to
+//! stress-test the cluster beyond failures recorded in traces.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use dslab_core::event::EventId;
+use dslab_core::{cast, log_debug, log_info, Event, EventHandler, SimulationContext};
+use serde::Deserialize;
+
+use crate::config::SimulationConfig;
+use crate::core::common::SimComponentId;
+use crate::core::events::{
+    FailNodeRequest, InjectNodeFailure, InjectNodeRecovery, RecoverNodeRequest,
+    RegisterNodeForFailures, UnregisterNodeForFailures,
+};
+use crate::core::node::{Node, NodeFailureType};
+use crate::failure_injector::distribution::{FailureTimeDistribution, FailureTimeSampler};
+
+/// Every node of the cluster belongs to the first node group which selector matches its labels,
+/// nodes without a group never fail. Time to failure of a node is sampled when it is added to the
+/// cluster and after each repair, time to repair is sampled when it fails.
+///
+/// Failures and recoveries are sent to api server as `FailNodeRequest` and `RecoverNodeRequest`
+/// without network delay, as they happen to nodes rather than come from some cluster component.
+/// All times are sampled with the seeded random generator of the simulation.
+pub struct FailureInjector {
+    api_server: SimComponentId,
+
+    /// Node groups with samplers of time to failure and time to repair in the order of config.
+    node_groups: Vec<(
+        FailureNodeGroupConfig,
+        FailureTimeSampler,
+        FailureTimeSampler,
+    )>,
+    /// Map from node name to its node group index and the pending failure or recovery event.
+    nodes: HashMap<String, (usize, EventId)>,
+
+    ctx: SimulationContext,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct FailureInjectorConfig {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// Node groups which are matched against node labels in the listed order.
+    #[serde(default)]
+    pub node_groups: Vec<FailureNodeGroupConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct FailureNodeGroupConfig {
+    pub name: String,
+    /// Labels which node should have to belong to the group, empty selector matches all nodes.
+    #[serde(default)]
+    pub node_selector: HashMap<String, String>,
+    #[serde(default = "failure_type_default")]
+    pub failure_type: NodeFailureType,
+    pub time_to_failure: FailureTimeDistribution,
+    pub time_to_repair: FailureTimeDistribution,
+}
+
+impl Default for FailureInjectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: enabled_default(),
+            node_groups: Default::default(),
+        }
+    }
+}
+
+fn enabled_default() -> bool {
+    false // disabled by default
+}
+fn failure_type_default() -> NodeFailureType {
+    NodeFailureType::Hard
+}
+
+impl FailureNodeGroupConfig {
+    fn matches(&self, node: &Node) -> bool {
+        self.node_selector
+            .iter()
+            .all(|(key, value)| node.metadata.labels.get(key) == Some(value))
+    }
+}
+
+impl FailureInjector {
+    /// Returns a description of the problem if node groups are misconfigured.
+    pub fn new(
+        api_server: SimComponentId,
+        ctx: SimulationContext,
+        config: Rc<SimulationConfig>,
+    ) -> Result<Self, String> {
+        let mut group_names: HashSet<&str> = Default::default();
+        let mut node_groups = vec![];
+        for group_config in config.failure_injector.node_groups.iter() {
+            if !group_names.insert(&group_config.name) {
+                return Err(format!(
+                    "failure injector node group {:?} is listed several times",
+                    group_config.name
+                ));
+            }
+            let sampler = |distribution: &FailureTimeDistribution| {
+                FailureTimeSampler::new(distribution).map_err(|err| {
+                    format!(
+                        "Invalid config of failure injector node group {:?}: {}",
+                        group_config.name, err
+                    )
+                })
+            };
+            node_groups.push((
+                group_config.clone(),
+                sampler(&group_config.time_to_failure)?,
+                sampler(&group_config.time_to_repair)?,
+            ));
+        }
+
+        Ok(Self {
+            api_server,
+            node_groups,
+            nodes: Default::default(),
+            ctx,
+        })
+    }
+
+    pub fn start(&mut self) {
+        log_info!(
+            self.ctx,
+            "Failure injector started with {} node groups",
+            self.node_groups.len()
+        );
+    }
+
+    /// Starts failing the node if it belongs to some node group.
+    pub fn add_node(&mut self, node: &Node) {
+        let Some(group_idx) = self
+            .node_groups
+            .iter()
+            .position(|(group_config, _, _)| group_config.matches(node))
+        else {
+            return;
+        };
+        let node_name = node.metadata.name.clone();
+        let event_id = self.schedule_failure(&node_name, group_idx);
+        self.nodes.insert(node_name, (group_idx, event_id));
+    }
+
+    fn remove_node(&mut self, node_name: &str) {
+        if let Some((_, event_id)) = self.nodes.remove(node_name) {
+            self.ctx.cancel_event(event_id);
+        }
+    }
+
+    fn schedule_failure(&self, node_name: &str, group_idx: usize) -> EventId {
+        let time_to_failure = self.node_groups[group_idx].1.sample(&self.ctx);
+        self.ctx.emit_self(
+            InjectNodeFailure {
+                node_name: node_name.to_string(),
+            },
+            time_to_failure,
+        )
+    }
+
+    fn fail_node(&mut self, node_name: String) {
+        let Some(&(group_idx, _)) = self.nodes.get(&node_name) else {
+            return;
+        };
+        let (group_config, _, time_to_repair) = &self.node_groups[group_idx];
+        log_debug!(
+            self.ctx,
+            "Injecting {:?} failure of node {:?} from group {:?}",
+            group_config.failure_type,
+            node_name,
+            group_config.name
+        );
+
+        self.ctx.emit_now(
+            FailNodeRequest {
+                node_name: node_name.clone(),
+                failure_type: group_config.failure_type.clone(),
+            },
+            self.api_server,
+        );
+        let event_id = self.ctx.emit_self(
+            InjectNodeRecovery {
+                node_name: node_name.clone(),
+            },
+            time_to_repair.sample(&self.ctx),
+        );
+        self.nodes.insert(node_name, (group_idx, event_id));
+    }
+
+    fn recover_node(&mut self, node_name: String) {
+        let Some(&(group_idx, _)) = self.nodes.get(&node_name) else {
+            return;
+        };
+        log_debug!(self.ctx, "Injecting recovery of node {:?}", node_name);
+
+        self.ctx.emit_now(
+            RecoverNodeRequest {
+                node_name: node_name.clone(),
+            },
+            self.api_server,
+        );
+        let event_id = self.schedule_failure(&node_name, group_idx);
+        self.nodes.insert(node_name, (group_idx, event_id));
+    }
+}
+
+impl EventHandler for FailureInjector {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            RegisterNodeForFailures { node } => {
+                self.add_node(&node);
+            }
+            UnregisterNodeForFailures { node_name } => {
+                self.remove_node(&node_name);
+            }
+            InjectNodeFailure { node_name } => {
+                self.fail_node(node_name);
+            }
+            InjectNodeRecovery { node_name } => {
+                self.recover_node(node_name);
+            }
+        })
+    }
+}
@@ -1,6 +1,9 @@
 pub mod autoscalers;
 pub mod config;
+pub mod controllers;
 pub mod core;
+pub mod descheduler;
+pub mod failure_injector;
 pub mod metrics;
 pub mod simulation_callbacks;
 pub mod simulator;
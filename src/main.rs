@@ -95,7 +95,13 @@ fn main() {
             Box::new(serde_yaml::from_str::<GenericWorkloadTrace>(&workload_trace_yaml).unwrap());
     }
 
-    let mut kubernetriks_simulation = KubernetriksSimulation::new(config);
+    let mut kubernetriks_simulation = match KubernetriksSimulation::new(config) {
+        Ok(kubernetriks_simulation) => kubernetriks_simulation,
+        Err(err) => {
+            eprintln!("Failed to create simulation: {}", err);
+            std::process::exit(1);
+        }
+    };
     kubernetriks_simulation.initialize(cluster_trace.as_mut(), workload_trace.as_mut());
     info!("Running simulation...");
     kubernetriks_simulation.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
@@ -1,7 +1,12 @@
 //! Implements centralized storage for metrics. Any component may access this component to
 //! report metrics about pods, nodes, etc.
 
-use std::{cell::RefCell, collections::HashMap, fs::File, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    rc::Rc,
+};
 
 use average::{concatenate, Estimate, Max, Mean, Min, Variance};
 use csv::Writer;
@@ -10,6 +15,7 @@ use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::core::{
     api_server::KubeApiServer,
     events::{RecordGaugeMetricsCycle, RunPodMetricsCollectionCycle},
+    pod::QoSClass,
 };
 
 concatenate!(
@@ -84,6 +90,17 @@ pub struct InternalMetrics {
     pub terminated_pods: u64,
 }
 
+/// Metrics of pods of one QoS class.
+#[derive(Default)]
+pub struct QoSClassMetrics {
+    /// The number of successfully finished pods.
+    pub pods_succeeded: u64,
+    /// The number of failed pods.
+    pub pods_failed: u64,
+    /// Estimations for the pod queue time, see `AccumulatedMetrics::pod_queue_time_stats`.
+    pub pod_queue_time_stats: EstimatorWrapper,
+}
+
 pub struct AccumulatedMetrics {
     /// The number of created nodes in trace. Calculated before simulation starts.
     pub total_nodes_in_trace: u64,
@@ -96,8 +113,31 @@ pub struct AccumulatedMetrics {
     pub pods_unschedulable: u64,
     /// The number of failed pods which started but eventually not finished due to some reasons.
     pub pods_failed: u64,
+    /// The number of failed pods which were killed for using more memory than their limits.
+    pub pods_oom_killed: u64,
     /// The number of removed pods due to the events in the trace.
     pub pods_removed: u64,
+    /// The number of pod restarts on the same node according to pod restart policies. Restarted
+    /// pods are not terminated, so they are counted as succeeded or failed only once.
+    pub pod_restarts: u64,
+    /// The number of running pods which were evicted from nodes by the scheduler to free place for
+    /// pods with higher priority. Preempted pods are not terminated but scheduled again.
+    pub pods_preempted: u64,
+    /// The number of running pods which were evicted from nodes due to NoExecute taints they do not
+    /// tolerate. Such pods are scheduled again as well.
+    pub pods_evicted_by_taints: u64,
+    /// The number of running pods which were evicted from drained nodes after the grace period.
+    /// Such pods are scheduled again as well.
+    pub pods_evicted_by_drain: u64,
+    /// The number of running pods which were evicted from nodes under memory pressure. Such pods
+    /// are scheduled again as well.
+    pub pods_evicted_by_node_pressure: u64,
+    /// The number of pod assignments rejected by persistent storage, because another scheduler
+    /// had taken the node resources earlier. Such pods are scheduled again.
+    pub pod_assignment_conflicts: u64,
+    /// Map from descheduler strategy name to the number of running pods evicted due to it. Such
+    /// pods are scheduled again as well.
+    pub pods_evicted_by_descheduler: BTreeMap<String, u64>,
 
     /// Estimations for the pod running duration.
     pub pod_duration_stats: EstimatorWrapper,
@@ -112,6 +152,14 @@ pub struct AccumulatedMetrics {
     /// scheduling error.
     pub pod_queue_time_stats: EstimatorWrapper,
 
+    /// Estimations for the time between the first pod of a pod group started waiting for the rest
+    /// of the group with reserved resources and the moment the whole group is allowed to bind.
+    /// Considers only pod groups scheduled with Coscheduling plugin.
+    pub pod_group_wait_time_stats: EstimatorWrapper,
+
+    /// Map from QoS class to metrics of pods of the class.
+    pub qos_class_metrics: BTreeMap<QoSClass, QoSClassMetrics>,
+
     // Auto scaler metrics
     /// Total number of scaled up nodes
     pub total_scaled_up_nodes: u64,
@@ -137,10 +185,27 @@ impl AccumulatedMetrics {
             pods_succeeded: 0,
             pods_unschedulable: 0,
             pods_failed: 0,
+            pods_oom_killed: 0,
             pods_removed: 0,
+            pod_restarts: 0,
+            pods_preempted: 0,
+            pods_evicted_by_taints: 0,
+            pods_evicted_by_drain: 0,
+            pods_evicted_by_node_pressure: 0,
+            pod_assignment_conflicts: 0,
+            pods_evicted_by_descheduler: Default::default(),
             pod_duration_stats: EstimatorWrapper::new(),
             pod_scheduling_algorithm_latency_stats: EstimatorWrapper::new(),
             pod_queue_time_stats: EstimatorWrapper::new(),
+            pod_group_wait_time_stats: EstimatorWrapper::new(),
+            qos_class_metrics: [
+                QoSClass::Guaranteed,
+                QoSClass::Burstable,
+                QoSClass::BestEffort,
+            ]
+            .into_iter()
+            .map(|qos_class| (qos_class, Default::default()))
+            .collect(),
             total_scaled_up_nodes: 0,
             total_scaled_down_nodes: 0,
             total_scaled_up_pods: 0,
@@ -161,8 +226,19 @@ impl AccumulatedMetrics {
         self.pod_scheduling_algorithm_latency_stats.add(value);
     }
 
-    pub fn increment_pod_queue_time(&mut self, value: f64) {
+    pub fn increment_pod_queue_time(&mut self, value: f64, qos_class: &QoSClass) {
         self.pod_queue_time_stats.add(value);
+        self.qos_class_metrics(qos_class)
+            .pod_queue_time_stats
+            .add(value);
+    }
+
+    pub fn qos_class_metrics(&mut self, qos_class: &QoSClass) -> &mut QoSClassMetrics {
+        self.qos_class_metrics.entry(qos_class.clone()).or_default()
+    }
+
+    pub fn increment_pod_group_wait_time(&mut self, value: f64) {
+        self.pod_group_wait_time_stats.add(value);
     }
 }
 
@@ -1,6 +1,6 @@
 use prettytable::{row, Table};
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, fs::File, io::Write, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fs::File, io::Write, rc::Rc};
 
 use crate::metrics::collector::MetricsCollector;
 
@@ -38,7 +38,39 @@ pub fn print_metrics_as_pretty_table(
     aggregated_table.add_row(row!["Pods succeeded", metrics.pods_succeeded]);
     aggregated_table.add_row(row!["Pods unschedulable", metrics.pods_unschedulable]);
     aggregated_table.add_row(row!["Pods failed", metrics.pods_failed]);
+    aggregated_table.add_row(row!["Pods OOM killed", metrics.pods_oom_killed]);
     aggregated_table.add_row(row!["Pods removed", metrics.pods_removed]);
+    aggregated_table.add_row(row!["Pod restarts", metrics.pod_restarts]);
+    aggregated_table.add_row(row!["Pods preempted", metrics.pods_preempted]);
+    aggregated_table.add_row(row![
+        "Pods evicted by taints",
+        metrics.pods_evicted_by_taints
+    ]);
+    aggregated_table.add_row(row!["Pods evicted by drain", metrics.pods_evicted_by_drain]);
+    aggregated_table.add_row(row![
+        "Pods evicted by node pressure",
+        metrics.pods_evicted_by_node_pressure
+    ]);
+    aggregated_table.add_row(row![
+        "Pod assignment conflicts",
+        metrics.pod_assignment_conflicts
+    ]);
+    for (strategy, evicted) in metrics.pods_evicted_by_descheduler.iter() {
+        aggregated_table.add_row(row![
+            format!("Pods evicted by descheduler ({})", strategy),
+            evicted
+        ]);
+    }
+    for (qos_class, qos_class_metrics) in metrics.qos_class_metrics.iter() {
+        aggregated_table.add_row(row![
+            format!("Pods succeeded ({:?})", qos_class),
+            qos_class_metrics.pods_succeeded
+        ]);
+        aggregated_table.add_row(row![
+            format!("Pods failed ({:?})", qos_class),
+            qos_class_metrics.pods_failed
+        ]);
+    }
     aggregated_table.add_row(row!["Total scaled up nodes", metrics.total_scaled_up_nodes]);
     aggregated_table.add_row(row![
         "Total scaled down nodes",
@@ -75,6 +107,23 @@ pub fn print_metrics_as_pretty_table(
         metrics.pod_queue_time_stats.mean(),
         metrics.pod_queue_time_stats.population_variance()
     ]);
+    for (qos_class, qos_class_metrics) in metrics.qos_class_metrics.iter() {
+        let stats = &qos_class_metrics.pod_queue_time_stats;
+        stats_table.add_row(row![
+            format!("Pod queue time ({:?})", qos_class),
+            stats.min(),
+            stats.max(),
+            stats.mean(),
+            stats.population_variance()
+        ]);
+    }
+    stats_table.add_row(row![
+        "Pod group wait time",
+        metrics.pod_group_wait_time_stats.min(),
+        metrics.pod_group_wait_time_stats.max(),
+        metrics.pod_group_wait_time_stats.mean(),
+        metrics.pod_group_wait_time_stats.population_variance()
+    ]);
 
     let _ = aggregated_table.print(&mut metrics_file);
     let _ = stats_table.print(&mut metrics_file);
@@ -93,7 +142,17 @@ struct Counters {
     pods_succeeded: u64,
     pods_unschedulable: u64,
     pods_failed: u64,
+    pods_oom_killed: u64,
     pods_removed: u64,
+    pod_restarts: u64,
+    pods_preempted: u64,
+    pods_evicted_by_taints: u64,
+    pods_evicted_by_drain: u64,
+    pods_evicted_by_node_pressure: u64,
+    pod_assignment_conflicts: u64,
+    pods_evicted_by_descheduler: BTreeMap<String, u64>,
+    pods_succeeded_by_qos_class: BTreeMap<String, u64>,
+    pods_failed_by_qos_class: BTreeMap<String, u64>,
     total_scaled_up_nodes: u64,
     total_scaled_down_nodes: u64,
     total_scaled_up_pods: u64,
@@ -105,6 +164,8 @@ struct Timings {
     pod_duration: TimingsStats,
     pod_schedule_time: TimingsStats,
     pod_queue_time: TimingsStats,
+    pod_queue_time_by_qos_class: BTreeMap<String, TimingsStats>,
+    pod_group_wait_time: TimingsStats,
 }
 
 #[derive(Serialize)]
@@ -129,7 +190,29 @@ pub fn print_metrics_as_json(
             pods_succeeded: metrics.pods_succeeded,
             pods_unschedulable: metrics.pods_unschedulable,
             pods_failed: metrics.pods_failed,
+            pods_oom_killed: metrics.pods_oom_killed,
             pods_removed: metrics.pods_removed,
+            pod_restarts: metrics.pod_restarts,
+            pods_preempted: metrics.pods_preempted,
+            pods_evicted_by_taints: metrics.pods_evicted_by_taints,
+            pods_evicted_by_drain: metrics.pods_evicted_by_drain,
+            pods_evicted_by_node_pressure: metrics.pods_evicted_by_node_pressure,
+            pod_assignment_conflicts: metrics.pod_assignment_conflicts,
+            pods_evicted_by_descheduler: metrics.pods_evicted_by_descheduler.clone(),
+            pods_succeeded_by_qos_class: metrics
+                .qos_class_metrics
+                .iter()
+                .map(|(qos_class, qos_class_metrics)| {
+                    (format!("{:?}", qos_class), qos_class_metrics.pods_succeeded)
+                })
+                .collect(),
+            pods_failed_by_qos_class: metrics
+                .qos_class_metrics
+                .iter()
+                .map(|(qos_class, qos_class_metrics)| {
+                    (format!("{:?}", qos_class), qos_class_metrics.pods_failed)
+                })
+                .collect(),
             total_scaled_up_nodes: metrics.total_scaled_up_nodes,
             total_scaled_down_nodes: metrics.total_scaled_down_nodes,
             total_scaled_up_pods: metrics.total_scaled_up_pods,
@@ -156,6 +239,28 @@ pub fn print_metrics_as_json(
                 mean: metrics.pod_queue_time_stats.mean(),
                 variance: metrics.pod_queue_time_stats.population_variance(),
             },
+            pod_queue_time_by_qos_class: metrics
+                .qos_class_metrics
+                .iter()
+                .map(|(qos_class, qos_class_metrics)| {
+                    let stats = &qos_class_metrics.pod_queue_time_stats;
+                    (
+                        format!("{:?}", qos_class),
+                        TimingsStats {
+                            min: stats.min(),
+                            max: stats.max(),
+                            mean: stats.mean(),
+                            variance: stats.population_variance(),
+                        },
+                    )
+                })
+                .collect(),
+            pod_group_wait_time: TimingsStats {
+                min: metrics.pod_group_wait_time_stats.min(),
+                max: metrics.pod_group_wait_time_stats.max(),
+                mean: metrics.pod_group_wait_time_stats.mean(),
+                variance: metrics.pod_group_wait_time_stats.population_variance(),
+            },
         },
     };
 
@@ -2,6 +2,7 @@
 
 use log::info;
 use std::cmp::max;
+use std::collections::BTreeMap;
 use std::time::Instant;
 use std::{cell::RefCell, rc::Rc};
 
@@ -15,6 +16,9 @@ use crate::autoscalers::horizontal_pod_autoscaler::horizontal_pod_autoscaler::{
 };
 
 use crate::config::SimulationConfig;
+use crate::controllers::node_lifecycle_controller::NodeLifecycleController;
+use crate::descheduler::descheduler::Descheduler;
+use crate::failure_injector::failure_injector::FailureInjector;
 
 use crate::core::api_server::KubeApiServer;
 use crate::core::common::SimulationEvent;
@@ -23,9 +27,9 @@ use crate::core::node::{Node, NodeConditionType};
 use crate::core::node_component::{NodeComponent, NodeRuntime};
 use crate::core::node_component_pool::NodeComponentPool;
 use crate::core::persistent_storage::PersistentStorage;
-use crate::core::scheduler::interface::PodSchedulingAlgorithm;
-use crate::core::scheduler::kube_scheduler::{default_kube_scheduler_config, KubeScheduler};
-use crate::core::scheduler::scheduler::Scheduler;
+use crate::core::scheduler::interface::{BatchPodSchedulingAlgorithm, PodSchedulingAlgorithm};
+use crate::core::scheduler::model::resolve_pod_scheduling_time_model;
+use crate::core::scheduler::scheduler::{resolve_pod_scheduling_algorithm, Scheduler};
 
 use crate::metrics::collector::MetricsCollector;
 
@@ -39,9 +43,14 @@ pub struct KubernetriksSimulation {
     pub api_server: Rc<RefCell<KubeApiServer>>,
     pub persistent_storage: Rc<RefCell<PersistentStorage>>,
     pub scheduler: Rc<RefCell<Scheduler>>,
+    /// Scheduler components besides the default one by their names.
+    pub additional_schedulers: BTreeMap<String, Rc<RefCell<Scheduler>>>,
 
     pub cluster_autoscaler: Option<Rc<RefCell<ClusterAutoscaler>>>,
     pub horizontal_pod_autoscaler: Option<Rc<RefCell<HorizontalPodAutoscaler>>>,
+    pub descheduler: Option<Rc<RefCell<Descheduler>>>,
+    pub failure_injector: Option<Rc<RefCell<FailureInjector>>>,
+    pub node_lifecycle_controller: Option<Rc<RefCell<NodeLifecycleController>>>,
 
     pub metrics_collector: Rc<RefCell<MetricsCollector>>,
 }
@@ -65,7 +74,8 @@ fn max_nodes_in_trace(trace: &Vec<(f64, Box<dyn SimulationEvent>)>) -> usize {
 }
 
 impl KubernetriksSimulation {
-    pub fn new(config: Rc<SimulationConfig>) -> Self {
+    /// Returns a description of the problem if the config is invalid.
+    pub fn new(config: Rc<SimulationConfig>) -> Result<Self, String> {
         info!(
             "Creating kubernetriks simulation {:?} with config: {:?}",
             config.sim_name, config
@@ -137,15 +147,74 @@ impl KubernetriksSimulation {
             ));
         }
 
+        let mut descheduler = None;
+        let mut descheduler_id = None;
+
+        if config.descheduler.enabled {
+            let descheduler_component_name = "descheduler";
+            descheduler = Some(Rc::new(RefCell::new(Descheduler::new(
+                kube_api_server_context.id(),
+                sim.create_context(descheduler_component_name),
+                config.clone(),
+            )?)));
+            descheduler_id = Some(sim.add_handler(
+                descheduler_component_name,
+                descheduler.as_ref().unwrap().clone(),
+            ));
+        }
+
+        let mut failure_injector = None;
+        let mut failure_injector_id = None;
+
+        if config.failure_injector.enabled {
+            let failure_injector_component_name = "failure_injector";
+            failure_injector = Some(Rc::new(RefCell::new(FailureInjector::new(
+                kube_api_server_context.id(),
+                sim.create_context(failure_injector_component_name),
+                config.clone(),
+            )?)));
+            failure_injector_id = Some(sim.add_handler(
+                failure_injector_component_name,
+                failure_injector.as_ref().unwrap().clone(),
+            ));
+        }
+
+        let mut node_lifecycle_controller = None;
+        let mut node_lifecycle_controller_id = None;
+
+        if config.node_lifecycle_controller.enabled {
+            let node_lifecycle_controller_component_name = "node_lifecycle_controller";
+            node_lifecycle_controller = Some(Rc::new(RefCell::new(NodeLifecycleController::new(
+                kube_api_server_context.id(),
+                sim.create_context(node_lifecycle_controller_component_name),
+                config.clone(),
+            ))));
+            node_lifecycle_controller_id = Some(sim.add_handler(
+                node_lifecycle_controller_component_name,
+                node_lifecycle_controller.as_ref().unwrap().clone(),
+            ));
+        }
+
         let api_server = Rc::new(RefCell::new(KubeApiServer::new(
             persistent_storage_context.id(),
             cluster_autoscaler_id,
             horizontal_pod_autoscaler_id,
+            descheduler_id,
             kube_api_server_context,
             config.clone(),
             metrics_collector.clone(),
         )));
         let api_server_id = sim.add_handler(api_server_component_name, api_server.clone());
+        if let Some(failure_injector_id) = failure_injector_id {
+            api_server
+                .borrow_mut()
+                .set_failure_injector(failure_injector_id);
+        }
+        if let Some(node_lifecycle_controller_id) = node_lifecycle_controller_id {
+            api_server
+                .borrow_mut()
+                .set_node_lifecycle_controller(node_lifecycle_controller_id);
+        }
 
         metrics_collector
             .borrow_mut()
@@ -160,17 +229,31 @@ impl KubernetriksSimulation {
             .borrow_mut()
             .start_gauge_metrics_recording();
 
-        let default_scheduler_impl = Box::new(KubeScheduler {
-            config: default_kube_scheduler_config(),
-        });
+        let default_scheduler_config = config.default_scheduler_component();
+        let default_scheduler_impl = resolve_pod_scheduling_algorithm(&default_scheduler_config)?;
+
+        let default_scheduling_time_model = resolve_pod_scheduling_time_model(
+            config.scheduling_time_model.clone(),
+            &config.scheduler,
+            sim.create_context("pod_scheduling_time_model"),
+        )
+        .map_err(|err| {
+            format!(
+                "Invalid scheduling time model of scheduler {:?}: {}",
+                default_scheduler_config.name, err
+            )
+        })?;
 
         let scheduler = Rc::new(RefCell::new(Scheduler::new(
             api_server_id,
             default_scheduler_impl,
             scheduler_context,
-            config.clone(),
+            default_scheduler_config,
             metrics_collector.clone(),
         )));
+        scheduler
+            .borrow_mut()
+            .set_pod_scheduling_time_model(default_scheduling_time_model);
         let scheduler_id = sim.add_handler(scheduler_component_name, scheduler.clone());
 
         let persistent_storage = Rc::new(RefCell::new(PersistentStorage::new(
@@ -180,21 +263,81 @@ impl KubernetriksSimulation {
             config.clone(),
             metrics_collector.clone(),
         )));
+
+        let mut additional_schedulers = BTreeMap::new();
+        let mut served_scheduler_names: Vec<String> =
+            config.scheduler.profiles.keys().cloned().collect();
+        for component_config in config.additional_scheduler_components().into_iter() {
+            let component_name = component_config.name.clone();
+            let mut scheduler_names: Vec<String> = component_config
+                .scheduler
+                .profiles
+                .keys()
+                .cloned()
+                .collect();
+            scheduler_names.sort();
+            for scheduler_name in scheduler_names.iter() {
+                if served_scheduler_names.contains(scheduler_name) {
+                    return Err(format!(
+                        "Scheduler name {:?} is served by several scheduler components",
+                        scheduler_name
+                    ));
+                }
+                served_scheduler_names.push(scheduler_name.clone());
+            }
+
+            let scheduler_impl = resolve_pod_scheduling_algorithm(&component_config)?;
+            let scheduling_time_model = resolve_pod_scheduling_time_model(
+                component_config.scheduling_time_model.clone(),
+                &component_config.scheduler,
+                sim.create_context(format!("{}_pod_scheduling_time_model", component_name)),
+            )
+            .map_err(|err| {
+                format!(
+                    "Invalid scheduling time model of scheduler {:?}: {}",
+                    component_name, err
+                )
+            })?;
+            let network_delay = component_config.ps_to_sched_network_delay;
+            let additional_scheduler = Rc::new(RefCell::new(Scheduler::new(
+                api_server_id,
+                scheduler_impl,
+                sim.create_context(&component_name),
+                component_config,
+                metrics_collector.clone(),
+            )));
+            additional_scheduler
+                .borrow_mut()
+                .set_pod_scheduling_time_model(scheduling_time_model);
+            let additional_scheduler_id =
+                sim.add_handler(&component_name, additional_scheduler.clone());
+
+            persistent_storage.borrow_mut().add_scheduler(
+                additional_scheduler_id,
+                scheduler_names,
+                network_delay,
+            );
+            additional_schedulers.insert(component_name, additional_scheduler);
+        }
         sim.add_handler(
             persistent_storage_component_name,
             persistent_storage.clone(),
         );
 
-        KubernetriksSimulation {
+        Ok(KubernetriksSimulation {
             config,
             sim,
             api_server,
             persistent_storage,
             scheduler,
+            additional_schedulers,
             metrics_collector,
             cluster_autoscaler,
             horizontal_pod_autoscaler,
-        }
+            descheduler,
+            failure_injector,
+            node_lifecycle_controller,
+        })
     }
 
     pub fn initialize(&mut self, cluster_trace: &mut dyn Trace, workload_trace: &mut dyn Trace) {
@@ -252,7 +395,7 @@ impl KubernetriksSimulation {
             client.emit(event, self.api_server.borrow().ctx.id(), ts);
         }
 
-        self.scheduler.borrow_mut().start();
+        self.start_schedulers();
 
         if self.config.cluster_autoscaler.enabled {
             self.cluster_autoscaler
@@ -272,6 +415,25 @@ impl KubernetriksSimulation {
         } else {
             info!("Horizontal pod autoscaler is disabled");
         }
+        if self.config.descheduler.enabled {
+            self.descheduler.as_mut().unwrap().borrow_mut().start();
+        } else {
+            info!("Descheduler is disabled");
+        }
+        if self.config.failure_injector.enabled {
+            self.failure_injector.as_mut().unwrap().borrow_mut().start();
+        } else {
+            info!("Failure injector is disabled");
+        }
+        if self.config.node_lifecycle_controller.enabled {
+            self.node_lifecycle_controller
+                .as_mut()
+                .unwrap()
+                .borrow_mut()
+                .start();
+        } else {
+            info!("Node lifecycle controller is disabled");
+        }
     }
 
     pub fn add_node(&mut self, mut node: Node) {
@@ -290,12 +452,22 @@ impl KubernetriksSimulation {
             api_server: self.api_server.borrow().ctx.id(),
             node: node.clone(),
             config: self.config.clone(),
+            pod_group_sizes: self.api_server.borrow().pod_group_sizes(),
         });
+        node_component.borrow_mut().start_heartbeats();
+        node_component.borrow_mut().start_pressure_checks();
+        node_component.borrow_mut().start_limits_checks();
         self.api_server
             .borrow_mut()
             .add_node_component(node_component.clone());
-        // add to scheduler
+        // add to schedulers
         self.scheduler.borrow_mut().add_node(node.clone());
+        for scheduler in self.additional_schedulers.values() {
+            scheduler.borrow_mut().add_node(node.clone());
+        }
+        if let Some(failure_injector) = self.failure_injector.as_ref() {
+            failure_injector.borrow_mut().add_node(&node);
+        }
 
         self.sim.add_handler(node_name, node_component);
     }
@@ -352,6 +524,23 @@ impl KubernetriksSimulation {
             .set_scheduler_algorithm(scheduler_algorithm)
     }
 
+    /// Makes the default scheduler place pods in batches by the algorithm.
+    pub fn set_batch_scheduler_algorithm(
+        &mut self,
+        batch_scheduler_algorithm: Box<dyn BatchPodSchedulingAlgorithm>,
+    ) {
+        self.scheduler
+            .borrow_mut()
+            .set_batch_scheduler_algorithm(batch_scheduler_algorithm)
+    }
+
+    fn start_schedulers(&mut self) {
+        self.scheduler.borrow_mut().start();
+        for scheduler in self.additional_schedulers.values() {
+            scheduler.borrow_mut().start();
+        }
+    }
+
     pub fn run_with_callbacks(&mut self, mut callbacks: Box<dyn SimulationCallbacks>) {
         callbacks.on_simulation_start(self);
 
@@ -373,7 +562,7 @@ impl KubernetriksSimulation {
 
     pub fn run_until_no_events(&mut self) {
         // Run simulation until completion of all events and measure time.
-        self.scheduler.borrow_mut().start();
+        self.start_schedulers();
 
         let t = Instant::now();
         self.sim.step_until_no_events();
@@ -69,6 +69,8 @@ pub fn default_test_simulation_config(with_suffix: Option<&str>) -> SimulationCo
     as_to_node_network_delay: 0.150
     as_to_ca_network_delay: 0.30
     as_to_hpa_network_delay: 0.40
+    as_to_descheduler_network_delay: 0.30
+    as_to_node_lifecycle_controller_network_delay: 0.20
     "#
     .to_string();
 
@@ -79,6 +81,103 @@ pub fn default_test_simulation_config(with_suffix: Option<&str>) -> SimulationCo
     serde_yaml::from_str::<SimulationConfig>(&default).unwrap()
 }
 
+/// Returns a `CreateNode` event of a generic cluster trace in YAML, events are appended to
+/// `events:`.
+pub fn create_node_event(timestamp: f64, name: &str, cpu: u32, ram: u64) -> String {
+    format!(
+        r#"
+  - timestamp: {}
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: {}
+          status:
+            capacity:
+              cpu: {}
+              ram: {}"#,
+        timestamp, name, cpu, ram
+    )
+}
+
+/// Pod created by `create_pod_event`. Usage is simulated with constant models if set.
+#[derive(Clone, Debug)]
+pub struct TestPod<'a> {
+    pub name: &'a str,
+    pub requests: (u32, u64),
+    pub limits: (u32, u64),
+    pub cpu_usage: Option<u32>,
+    pub ram_usage: Option<u64>,
+    pub running_duration: f64,
+    pub failure_time: Option<f64>,
+}
+
+impl Default for TestPod<'_> {
+    fn default() -> Self {
+        Self {
+            name: "pod",
+            requests: (1000, 1073741824),
+            limits: (1000, 1073741824),
+            cpu_usage: None,
+            ram_usage: None,
+            running_duration: 100.0,
+            failure_time: None,
+        }
+    }
+}
+
+/// Returns a `CreatePod` event of a generic workload trace in YAML, events are appended to
+/// `events:`.
+pub fn create_pod_event(timestamp: f64, pod: TestPod) -> String {
+    let mut event = format!(
+        r#"
+  - timestamp: {}
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: {}
+          spec:
+            running_duration: {}
+            resources:
+              requests:
+                cpu: {}
+                ram: {}
+              limits:
+                cpu: {}
+                ram: {}"#,
+        timestamp,
+        pod.name,
+        pod.running_duration,
+        pod.requests.0,
+        pod.requests.1,
+        pod.limits.0,
+        pod.limits.1
+    );
+    if pod.cpu_usage.is_some() || pod.ram_usage.is_some() {
+        event += "\n              usage_model_config:";
+    }
+    let usage_models = [
+        ("cpu_config", pod.cpu_usage.map(|usage| usage as u64)),
+        ("ram_config", pod.ram_usage),
+    ];
+    for (model, usage) in usage_models {
+        if let Some(usage) = usage {
+            event += &format!(
+                r#"
+                {}:
+                  model_name: constant
+                  config: "usage: {}""#,
+                model, usage
+            );
+        }
+    }
+    if let Some(failure_time) = pod.failure_time {
+        event += &format!("\n            failure_time: {}", failure_time);
+    }
+    event
+}
+
 pub fn init_logger() {
     let mut env_logger_builder = env_logger::builder();
     env_logger_builder.filter_level(log::LevelFilter::Trace);
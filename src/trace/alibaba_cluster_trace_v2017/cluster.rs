@@ -7,8 +7,8 @@ use crate::trace::alibaba_cluster_trace_v2017::common::{CPU_BASE, DENORMALIZATIO
 use crate::{
     core::{
         common::SimulationEvent,
-        events::{CreateNodeRequest, RemoveNodeRequest},
-        node::Node,
+        events::{CreateNodeRequest, FailNodeRequest, RecoverNodeRequest},
+        node::{Node, NodeFailureType},
     },
     trace::interface::Trace,
 };
@@ -29,8 +29,9 @@ struct MachineEvent {
     /// batch jobs should not be placed in the machines, but existing services and jobs may still
     /// function normally."
     ///
-    /// Currently our simulator does not support such behavior, so in case of soft or hard error
-    /// we emit RemoveNodeRequest only to terminate such nodes, so workload would be rescheduled.
+    /// `softerror` fails the node with soft failure, so it is not ready for new pods while running
+    /// ones keep running. `harderror` fails the node with hard failure, which also fails its
+    /// running pods. The following `add` of a failed machine recovers the node.
     event_type: String,
     event_detail: Option<String>,
     number_of_cpus: Option<i64>,
@@ -60,12 +61,22 @@ impl Trace for AlibabaClusterTraceV2017 {
         swap(&mut events, &mut self.machine_events);
 
         let mut created_nodes: HashSet<String> = Default::default();
-        let mut removed_nodes: HashSet<String> = Default::default();
+        let mut failed_nodes: HashSet<String> = Default::default();
 
         for machine_event in events.into_iter() {
             let node_name = format!("alibaba_node_{}", machine_event.machine_id);
             if machine_event.event_type == "add" {
-                created_nodes.insert(node_name.clone());
+                if failed_nodes.remove(&node_name) {
+                    converted_events.push((
+                        machine_event.timestamp as f64,
+                        Box::new(RecoverNodeRequest { node_name }),
+                    ));
+                    continue;
+                }
+                if !created_nodes.insert(node_name.clone()) {
+                    // already exists and works - skip
+                    continue;
+                }
                 let cpu = machine_event.number_of_cpus.unwrap();
                 let ram = machine_event.normalized_memory.unwrap();
                 let converted_cpu = (cpu * CPU_BASE) as u32; // in millicores
@@ -79,14 +90,22 @@ impl Trace for AlibabaClusterTraceV2017 {
             } else if machine_event.event_type == "softerror"
                 || machine_event.event_type == "harderror"
             {
-                if removed_nodes.contains(&node_name) || !created_nodes.contains(&node_name) {
-                    // already removed or does not exist - skip
+                if !created_nodes.contains(&node_name) {
+                    // does not exist - skip
                     continue;
                 }
-                removed_nodes.insert(node_name.clone());
+                let failure_type = if machine_event.event_type == "softerror" {
+                    NodeFailureType::Soft
+                } else {
+                    NodeFailureType::Hard
+                };
+                failed_nodes.insert(node_name.clone());
                 converted_events.push((
                     machine_event.timestamp as f64,
-                    Box::new(RemoveNodeRequest { node_name }),
+                    Box::new(FailNodeRequest {
+                        node_name,
+                        failure_type,
+                    }),
                 ));
             } else {
                 panic!(
@@ -129,6 +148,8 @@ fn read_machine_events_from_file(trace_path: PathBuf) -> Vec<MachineEvent> {
 mod tests {
     use std::path::PathBuf;
 
+    use crate::core::events::{CreateNodeRequest, FailNodeRequest, RecoverNodeRequest};
+    use crate::core::node::NodeFailureType;
     use crate::trace::interface::Trace;
 
     use super::{read_machine_events_from_str, AlibabaClusterTraceV2017, MachineEvent};
@@ -198,4 +219,32 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_errors_fail_and_add_recovers_node() {
+        let mut trace = AlibabaClusterTraceV2017 {
+            machine_events: read_machine_events_from_str(
+                r#"
+0,1,add,,64,0.5,0.5
+10,1,softerror,agent_fail,,,
+20,1,add,,64,0.5,0.5
+30,1,harderror,disk_fail,,,
+40,2,harderror,disk_fail,,,
+"#,
+            ),
+        };
+        let events = trace.convert_to_simulator_events();
+        assert_eq!(4, events.len());
+
+        assert!(events[0].1.downcast_ref::<CreateNodeRequest>().is_some());
+        let soft_failure = events[1].1.downcast_ref::<FailNodeRequest>().unwrap();
+        assert_eq!("alibaba_node_1", soft_failure.node_name);
+        assert_eq!(NodeFailureType::Soft, soft_failure.failure_type);
+        let recovery = events[2].1.downcast_ref::<RecoverNodeRequest>().unwrap();
+        assert_eq!("alibaba_node_1", recovery.node_name);
+        // Errors of machines which were never added are skipped.
+        let hard_failure = events[3].1.downcast_ref::<FailNodeRequest>().unwrap();
+        assert_eq!(30.0, events[3].0);
+        assert_eq!(NodeFailureType::Hard, hard_failure.failure_type);
+    }
 }
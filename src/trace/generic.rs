@@ -7,9 +7,11 @@ use serde::Deserialize;
 use crate::autoscalers::horizontal_pod_autoscaler::interface::PodGroup;
 use crate::core::common::SimulationEvent;
 use crate::core::events::{
-    CreateNodeRequest, CreatePodGroupRequest, CreatePodRequest, RemoveNodeRequest, RemovePodRequest,
+    CordonNodeRequest, CreateNodeRequest, CreatePodGroupRequest, CreatePodRequest,
+    DrainNodeRequest, FailNodeRequest, RecoverNodeRequest, RemoveNodeRequest, RemovePodRequest,
+    TaintNodeRequest, UncordonNodeRequest, UntaintNodeRequest,
 };
-use crate::core::node::Node;
+use crate::core::node::{Node, NodeFailureType, Taint, TaintEffect};
 use crate::core::pod::Pod;
 use crate::trace::interface::Trace;
 
@@ -49,8 +51,48 @@ pub struct ClusterEvent {
 
 #[derive(Debug, Deserialize, PartialEq)]
 pub enum ClusterEventType {
-    CreateNode { node: Node },
-    RemoveNode { node_name: String },
+    CreateNode {
+        node: Node,
+    },
+    RemoveNode {
+        node_name: String,
+    },
+    TaintNode {
+        node_name: String,
+        taint: Taint,
+    },
+    UntaintNode {
+        node_name: String,
+        key: String,
+        effect: TaintEffect,
+    },
+    FailNode {
+        node_name: String,
+        failure_type: NodeFailureType,
+    },
+    RecoverNode {
+        node_name: String,
+    },
+    CordonNode {
+        node_name: String,
+    },
+    UncordonNode {
+        node_name: String,
+    },
+    DrainNode {
+        node_name: String,
+        /// Time in seconds running pods are given to finish before eviction.
+        #[serde(default = "default_drain_grace_period")]
+        grace_period: f64,
+        /// Whether to remove the node from the cluster after it is drained.
+        #[serde(default)]
+        remove_node: bool,
+    },
+}
+
+// Default termination grace period of pods in k8s.
+fn default_drain_grace_period() -> f64 {
+    30.0
 }
 
 impl Trace for GenericWorkloadTrace {
@@ -100,6 +142,50 @@ impl Trace for GenericClusterTrace {
                 }
                 ClusterEventType::RemoveNode { node_name } => converted_events
                     .push((event.timestamp, Box::new(RemoveNodeRequest { node_name }))),
+                ClusterEventType::TaintNode { node_name, taint } => converted_events.push((
+                    event.timestamp,
+                    Box::new(TaintNodeRequest { node_name, taint }),
+                )),
+                ClusterEventType::UntaintNode {
+                    node_name,
+                    key,
+                    effect,
+                } => converted_events.push((
+                    event.timestamp,
+                    Box::new(UntaintNodeRequest {
+                        node_name,
+                        key,
+                        effect,
+                    }),
+                )),
+                ClusterEventType::FailNode {
+                    node_name,
+                    failure_type,
+                } => converted_events.push((
+                    event.timestamp,
+                    Box::new(FailNodeRequest {
+                        node_name,
+                        failure_type,
+                    }),
+                )),
+                ClusterEventType::RecoverNode { node_name } => converted_events
+                    .push((event.timestamp, Box::new(RecoverNodeRequest { node_name }))),
+                ClusterEventType::CordonNode { node_name } => converted_events
+                    .push((event.timestamp, Box::new(CordonNodeRequest { node_name }))),
+                ClusterEventType::UncordonNode { node_name } => converted_events
+                    .push((event.timestamp, Box::new(UncordonNodeRequest { node_name }))),
+                ClusterEventType::DrainNode {
+                    node_name,
+                    grace_period,
+                    remove_node,
+                } => converted_events.push((
+                    event.timestamp,
+                    Box::new(DrainNodeRequest {
+                        node_name,
+                        grace_period,
+                        remove_node,
+                    }),
+                )),
             }
         }
         converted_events.sort_by(|lhs, rhs| lhs.0.partial_cmp(&rhs.0).unwrap());
@@ -254,6 +340,15 @@ mod tests {
                                     usage_model_config: None,
                                 },
                                 running_duration: Some(21.0),
+                                priority: 0,
+                                preemption_policy: Default::default(),
+                                tolerations: Default::default(),
+                                node_selector: Default::default(),
+                                affinity: Default::default(),
+                                topology_spread_constraints: Default::default(),
+                                restart_policy: Default::default(),
+                                failure_probability: None,
+                                failure_time: None,
                             },
                             status: Default::default(),
                         },
@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::{Pod, PodConditionType};
+use dslab_kubernetriks::core::scheduler::interface::{
+    BatchPodSchedulingAlgorithm, ClusterSnapshot,
+};
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+// Places pods to the first node with enough cpu left and records sizes of batches.
+struct FirstFitBatchScheduler {
+    batch_sizes: Rc<RefCell<Vec<usize>>>,
+}
+
+impl BatchPodSchedulingAlgorithm for FirstFitBatchScheduler {
+    fn schedule_batch(&self, pods: &[&Pod], snapshot: &ClusterSnapshot) -> HashMap<String, String> {
+        self.batch_sizes.borrow_mut().push(pods.len());
+
+        let mut cpu_left: BTreeMap<&str, u32> = snapshot
+            .nodes
+            .iter()
+            .map(|(name, node)| (name as &str, node.status.allocatable.cpu))
+            .collect();
+        let mut assignments = HashMap::new();
+        for pod in pods {
+            let requested_cpu = pod.spec.resources.requests.cpu;
+            if let Some((node_name, cpu)) =
+                cpu_left.iter_mut().find(|(_, cpu)| **cpu >= requested_cpu)
+            {
+                *cpu -= requested_cpu;
+                assignments.insert(pod.metadata.name.clone(), node_name.to_string());
+            }
+        }
+        assignments
+    }
+}
+
+// Assigns all pods to the same node regardless of its resources.
+struct SingleNodeBatchScheduler {}
+
+impl BatchPodSchedulingAlgorithm for SingleNodeBatchScheduler {
+    fn schedule_batch(
+        &self,
+        pods: &[&Pod],
+        _snapshot: &ClusterSnapshot,
+    ) -> HashMap<String, String> {
+        pods.iter()
+            .map(|pod| (pod.metadata.name.clone(), "node_1".to_string()))
+            .collect()
+    }
+}
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    let pod_events: String = ["pod_1", "pod_2"]
+        .iter()
+        .map(|name| {
+            format!(
+                r#"
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: {}
+          spec:
+            resources:
+              requests:
+                cpu: 3000
+                ram: 1073741824
+              limits:
+                cpu: 3000
+                ram: 1073741824
+            running_duration: 10.0"#,
+                name
+            )
+        })
+        .collect();
+    serde_yaml::from_str(&format!("events:{}", pod_events)).unwrap()
+}
+
+fn run_simulation(batch_scheduler: Box<dyn BatchPodSchedulingAlgorithm>) -> KubernetriksSimulation {
+    let config = Rc::new(default_test_simulation_config(None));
+    let mut kube_sim = KubernetriksSimulation::new(config).unwrap();
+    kube_sim.set_batch_scheduler_algorithm(batch_scheduler);
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace());
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+    kube_sim
+}
+
+fn condition_time(
+    kube_sim: &KubernetriksSimulation,
+    pod_name: &str,
+    condition_type: PodConditionType,
+) -> f64 {
+    kube_sim.persistent_storage.borrow().succeeded_pods[pod_name]
+        .get_condition(condition_type)
+        .unwrap()
+        .last_transition_time
+}
+
+#[test]
+fn test_batch_scheduler_gets_all_pods_of_cycle() {
+    let batch_sizes: Rc<RefCell<Vec<usize>>> = Default::default();
+    let kube_sim = run_simulation(Box::new(FirstFitBatchScheduler {
+        batch_sizes: batch_sizes.clone(),
+    }));
+
+    assert_eq!(vec![2], *batch_sizes.borrow());
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "node_2",
+        persistent_storage.succeeded_pods["pod_2"]
+            .status
+            .assigned_node
+    );
+    drop(persistent_storage);
+    assert!(condition_time(&kube_sim, "pod_1", PodConditionType::PodRunning) < 15.0);
+    assert!(condition_time(&kube_sim, "pod_2", PodConditionType::PodRunning) < 15.0);
+}
+
+#[test]
+fn test_batch_assignment_to_full_node_is_retried() {
+    let kube_sim = run_simulation(Box::new(SingleNodeBatchScheduler {}));
+
+    // Only the first pod of the batch fits node_1, the second one waits until it is released.
+    assert!(condition_time(&kube_sim, "pod_1", PodConditionType::PodRunning) < 15.0);
+    assert!(
+        condition_time(&kube_sim, "pod_2", PodConditionType::PodRunning)
+            > condition_time(&kube_sim, "pod_1", PodConditionType::PodSucceeded)
+    );
+}
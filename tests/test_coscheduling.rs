@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::config::SimulationConfig;
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn coscheduling_config() -> SimulationConfig {
+    default_test_simulation_config(Some(
+        r#"
+    scheduler:
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          score:
+          - name: LeastAllocatedResources
+          reserve:
+          - name: Coscheduling
+          permit:
+          - name: Coscheduling
+    "#,
+    ))
+}
+
+fn get_cluster_trace(node_cpu: u32) -> GenericClusterTrace {
+    serde_yaml::from_str(&format!(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: {}
+              ram: 17179869184
+  "#,
+        node_cpu
+    ))
+    .unwrap()
+}
+
+fn gang_pod_event(name: &str, timestamp: f64) -> String {
+    format!(
+        r#"
+  - timestamp: {}
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: {}
+            labels:
+              pod_group: gang
+              pod_group_min_member: "3"
+              pod_group_schedule_timeout: "30"
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 1073741824
+              limits:
+                cpu: 2000
+                ram: 1073741824
+            running_duration: 10.0"#,
+        timestamp, name
+    )
+}
+
+#[test]
+fn test_pod_group_is_started_when_all_members_are_placed() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}{}",
+        gang_pod_event("gang_1", 5.0),
+        gang_pod_event("gang_2", 5.0),
+        gang_pod_event("gang_3", 25.0),
+    ))
+    .unwrap();
+
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(coscheduling_config())).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(8000), &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let start_time = |pod_name: &str| {
+        persistent_storage_borrowed.succeeded_pods[pod_name]
+            .get_condition(PodConditionType::PodRunning)
+            .unwrap()
+            .last_transition_time
+    };
+    // First members wait with reserved resources until the last one comes.
+    assert!(start_time("gang_1") > 25.0);
+    assert_eq!(start_time("gang_1"), start_time("gang_2"));
+    assert_eq!(start_time("gang_1"), start_time("gang_3"));
+
+    let metrics_collector_borrowed = kube_sim.metrics_collector.borrow();
+    let pod_group_wait_time_stats = &metrics_collector_borrowed
+        .accumulated_metrics
+        .pod_group_wait_time_stats;
+    // Members are placed in scheduling cycles at 10.0 and 30.0.
+    assert_eq!(20.0, pod_group_wait_time_stats.max());
+    assert_eq!(20.0, pod_group_wait_time_stats.min());
+}
+
+#[test]
+fn test_pod_group_releases_resources_on_timeout() {
+    // Only two members of the group fit into the node. They hold the whole node until timeout,
+    // then the independent pod with higher priority takes it.
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        r#"events:{}{}{}
+  - timestamp: 15
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: independent_pod
+          spec:
+            resources:
+              requests:
+                cpu: 4000
+                ram: 1073741824
+              limits:
+                cpu: 4000
+                ram: 1073741824
+            running_duration: 10.0
+            priority: 10
+  "#,
+        gang_pod_event("gang_1", 5.0),
+        gang_pod_event("gang_2", 5.0),
+        gang_pod_event("gang_3", 5.0),
+    ))
+    .unwrap();
+
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(coscheduling_config())).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(4000), &mut workload_trace);
+    kube_sim.step_until_time(100.0);
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let independent_pod_start_time = persistent_storage_borrowed.succeeded_pods["independent_pod"]
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    // Members are placed at 10.0 and rejected after 30 seconds of waiting.
+    assert!(independent_pod_start_time > 40.0);
+    for pod_name in ["gang_1", "gang_2", "gang_3"] {
+        let pod = persistent_storage_borrowed.get_pod(pod_name).unwrap();
+        assert!(pod.get_condition(PodConditionType::PodRunning).is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::{Pod, PodConditionType};
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::{
+    create_pod_event, default_test_simulation_config, TestPod,
+};
+
+fn running_time(pod: &Pod) -> f64 {
+    let start_time = pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let finish_time = pod
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    finish_time - start_time
+}
+
+#[test]
+fn test_pods_share_cpu_of_overcommitted_node() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                cpu_usage: Some(4000),
+                running_duration: 100.0,
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            55.0,
+            TestPod {
+                name: "pod_2",
+                cpu_usage: Some(4000),
+                running_duration: 25.0,
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let mut config = default_test_simulation_config(None);
+    config.cpu_contention.enabled = true;
+    let config = Rc::new(config);
+    let mut kube_sim = KubernetriksSimulation::new(config.clone()).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_succeeded);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    // Both pods need the whole node cpu, so the second one runs at half speed.
+    let pod_2 = &persistent_storage.succeeded_pods["pod_2"];
+    assert!((running_time(pod_2) - 50.0).abs() < 1e-9);
+    // The first pod runs at half speed while the second one runs and until the node learns that
+    // it has finished, then it runs at full speed again.
+    let pod_1 = &persistent_storage.succeeded_pods["pod_1"];
+    let expected_running_time = 125.0 + 0.5 * config.as_to_node_network_delay;
+    assert!((running_time(pod_1) - expected_running_time).abs() < 1e-9);
+}
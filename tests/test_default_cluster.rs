@@ -16,7 +16,8 @@ fn make_default_node(name: String, cpu: u32, ram: u64) -> Node {
 
 #[test]
 fn test_config_default_cluster_is_none() {
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize_default_cluster();
 
     check_count_of_nodes_in_components_equals_to(0, &mut kube_sim);
@@ -45,7 +46,7 @@ fn test_config_default_cluster_with_no_name_prefix() {
               ram: 18589934592
     "#,
     ));
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
     kube_sim.initialize_default_cluster();
 
     check_count_of_nodes_in_components_equals_to(30, &kube_sim);
@@ -98,7 +99,7 @@ fn test_config_default_cluster_no_node_count() {
     "#,
     ));
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
     kube_sim.initialize_default_cluster();
 
     check_count_of_nodes_in_components_equals_to(4, &kube_sim);
@@ -147,7 +148,7 @@ fn test_config_default_cluster_has_name_prefix() {
     "#,
     ));
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
     kube_sim.initialize_default_cluster();
 
     check_count_of_nodes_in_components_equals_to(4, &kube_sim);
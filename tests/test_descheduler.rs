@@ -0,0 +1,121 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+// The second node is added after all pods are placed to the first one.
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 20
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    let pod_events: String = (0..4)
+        .map(|idx| {
+            format!(
+                r#"
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_{}
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 1073741824
+              limits:
+                cpu: 2000
+                ram: 1073741824
+            running_duration: 100.0"#,
+                idx
+            )
+        })
+        .collect();
+    serde_yaml::from_str(&format!("events:{}", pod_events)).unwrap()
+}
+
+#[test]
+fn test_low_node_utilization_rebalances_pods() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    descheduler:
+      enabled: true
+      scan_interval: 30.0
+      strategies:
+      - name: LowNodeUtilization
+        args:
+          thresholds: {cpu: 0.2, ram: 0.2}
+          target_thresholds: {cpu: 0.5, ram: 0.5}
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace());
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    assert_eq!(
+        Some(&2),
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_evicted_by_descheduler
+            .get("LowNodeUtilization")
+    );
+
+    // Evicted pods are placed to the new node and do not bounce back, as both nodes are at target
+    // thresholds after that.
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let evicted_pods: Vec<&str> = persistent_storage
+        .succeeded_pods
+        .values()
+        .filter(|pod| pod.get_condition(PodConditionType::PodEvicted).is_some())
+        .map(|pod| pod.status.assigned_node.as_str())
+        .collect();
+    assert_eq!(vec!["node_2", "node_2"], evicted_pods);
+    assert_eq!(4, persistent_storage.succeeded_pods.len());
+}
+
+#[test]
+fn test_duplicate_descheduler_strategy_is_rejected() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    descheduler:
+      enabled: true
+      strategies:
+      - name: LowNodeUtilization
+      - name: LowNodeUtilization
+    "#,
+    ));
+    let err = KubernetriksSimulation::new(Rc::new(config)).err().unwrap();
+    assert!(err.contains("\"LowNodeUtilization\" is listed several times"));
+}
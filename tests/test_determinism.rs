@@ -1,7 +1,8 @@
 use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 use dslab_kubernetriks::config::SimulationConfig;
-use dslab_kubernetriks::core::{node::Node, pod::Pod};
+use dslab_kubernetriks::core::node::{Node, Taint, TaintEffect};
+use dslab_kubernetriks::core::pod::{Pod, PodConditionType};
 use dslab_kubernetriks::metrics::collector::MetricsCollector;
 use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
 use dslab_kubernetriks::simulator::KubernetriksSimulation;
@@ -71,7 +72,7 @@ fn run_simulation() -> Rc<RefCell<MetricsCollector>> {
     let mut config: SimulationConfig = default_test_simulation_config(None);
     // fixing seed
     config.seed = 46;
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
 
     let mut cluster_trace = generate_cluster_trace(&mut kube_sim);
     let mut workload_trace = generate_workload_trace(&mut kube_sim);
@@ -124,3 +125,78 @@ pub fn test_simulation_determinism() {
         );
     }
 }
+
+// Pods intolerant to the taint are evicted from the first node at the same time and spread over
+// two other nodes in the order they are scheduled again. Returns assigned node and eviction time
+// of every pod.
+fn run_taint_eviction_simulation() -> BTreeMap<String, (String, f64)> {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+
+    let mut cluster_trace = GenericClusterTrace { events: Vec::new() };
+    for (timestamp, name, cpu) in [
+        (0.0, "node_1", 8000),
+        (20.0, "node_2", 2000),
+        (20.0, "node_3", 2000),
+    ] {
+        cluster_trace.events.push(ClusterEvent {
+            timestamp,
+            event_type: ClusterEventType::CreateNode {
+                node: Node::new(name.to_string(), cpu, 17179869184),
+            },
+        });
+    }
+    cluster_trace.events.push(ClusterEvent {
+        timestamp: 30.0,
+        event_type: ClusterEventType::TaintNode {
+            node_name: "node_1".to_string(),
+            taint: Taint {
+                key: "maintenance".to_string(),
+                value: Default::default(),
+                effect: TaintEffect::NoExecute,
+            },
+        },
+    });
+
+    let mut workload_trace = GenericWorkloadTrace { events: Vec::new() };
+    for idx in 0..8 {
+        workload_trace.events.push(WorkloadEvent {
+            timestamp: 1.0,
+            event_type: WorkloadEventType::CreatePod {
+                pod: Pod::new(format!("pod_{}", idx), 500, 1073741824, Some(100.0)),
+            },
+        });
+    }
+
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    persistent_storage
+        .succeeded_pods
+        .iter()
+        .map(|(name, pod)| {
+            let eviction_time = pod
+                .get_condition(PodConditionType::PodEvicted)
+                .unwrap()
+                .last_transition_time;
+            (
+                name.clone(),
+                (pod.status.assigned_node.clone(), eviction_time),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_taint_eviction_determinism() {
+    let first_assignments = run_taint_eviction_simulation();
+    assert_eq!(8, first_assignments.len());
+    assert!(first_assignments
+        .values()
+        .all(|(node_name, _)| node_name != "node_1"));
+
+    for _ in 0..10 {
+        assert_eq!(first_assignments, run_taint_eviction_simulation());
+    }
+}
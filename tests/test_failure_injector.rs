@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: flaky_node
+            labels:
+              flaky: "true"
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: reliable_node
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    let pod_events: String = (0..40)
+        .map(|idx| {
+            format!(
+                r#"
+  - timestamp: {}
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_{}
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 1073741824
+              limits:
+                cpu: 2000
+                ram: 1073741824
+            running_duration: 40.0"#,
+                idx * 10,
+                idx
+            )
+        })
+        .collect();
+    serde_yaml::from_str(&format!("events:{}", pod_events)).unwrap()
+}
+
+// Returns names of failed pods and their nodes.
+fn run_simulation(repair_times_path: &str) -> BTreeSet<(String, String)> {
+    let config = default_test_simulation_config(Some(&format!(
+        r#"
+    failure_injector:
+      enabled: true
+      node_groups:
+      - name: flaky_nodes
+        node_selector:
+          flaky: "true"
+        failure_type: Hard
+        time_to_failure: !Weibull
+          shape: 1.5
+          scale: 60.0
+        time_to_repair: !Empirical
+          path: {}
+    "#,
+        repair_times_path
+    )));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace());
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        persistent_storage.failed_pods.len() as u64,
+        metrics.pods_failed
+    );
+    assert_eq!(40, metrics.pods_failed + metrics.pods_succeeded);
+    persistent_storage
+        .failed_pods
+        .values()
+        .map(|pod| (pod.metadata.name.clone(), pod.status.assigned_node.clone()))
+        .collect()
+}
+
+#[test]
+fn test_injected_failures_are_reproducible() {
+    let repair_times_path = std::env::temp_dir().join("kubernetriks_test_repair_times.csv");
+    std::fs::write(&repair_times_path, "5\n10\n30\n").unwrap();
+    let repair_times_path = repair_times_path.to_str().unwrap();
+
+    let failed_pods = run_simulation(repair_times_path);
+    assert!(!failed_pods.is_empty());
+    // Only nodes of the node group fail.
+    assert!(failed_pods
+        .iter()
+        .all(|(_, node_name)| node_name == "flaky_node"));
+
+    assert_eq!(failed_pods, run_simulation(repair_times_path));
+}
+
+#[test]
+fn test_invalid_node_group_is_rejected() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    failure_injector:
+      enabled: true
+      node_groups:
+      - name: flaky_nodes
+        time_to_failure: !Exponential
+          mean: 0.0
+        time_to_repair: !Exponential
+          mean: 10.0
+    "#,
+    ));
+    let err = KubernetriksSimulation::new(Rc::new(config)).err().unwrap();
+    assert!(err.contains("node group \"flaky_nodes\": mean should be positive"));
+}
@@ -84,7 +84,7 @@ fn test_pod_group_created_and_scaled_by_cpu_utilization() {
     let mut workload_trace = get_workload_trace();
     let mut cluster_trace = get_cluster_trace();
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
 
     // HPA actions at 60, 120, 180, 240, ..., 1080
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    let mut trace = "events:".to_string();
+    for (name, zone) in [
+        ("node_1", "zone_a"),
+        ("node_2", "zone_a"),
+        ("node_3", "zone_b"),
+        ("node_4", "zone_c"),
+    ] {
+        trace += &format!(
+            r#"
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: {}
+            labels:
+              zone: {}
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+            name, zone
+        );
+    }
+    serde_yaml::from_str(&trace).unwrap()
+}
+
+#[test]
+fn test_pod_group_replicas_are_spread_across_zones_by_anti_affinity() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePodGroup
+        pod_group:
+          name: pod_group_1
+          initial_pod_count: 4
+          max_pod_count: 4
+          pod_template:
+            metadata:
+              name: pod_group_1
+            spec:
+              resources:
+                requests:
+                  cpu: 1000
+                  ram: 1073741824
+                limits:
+                  cpu: 1000
+                  ram: 1073741824
+              affinity:
+                pod_anti_affinity:
+                  required:
+                  - label_selector:
+                      match_labels:
+                        pod_group: pod_group_1
+                    topology_key: zone
+          target_resources_usage:
+            cpu_utilization: 0.6
+          resources_usage_model_config:
+            cpu_config:
+              model_name: constant
+              config: |
+                usage: 0.5
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
+    kube_sim.step_until_time(100.0);
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let assigned_nodes: Vec<String> = (0..4)
+        .map(|idx| {
+            persistent_storage_borrowed
+                .get_pod(&format!("pod_group_1_{}", idx))
+                .unwrap()
+                .status
+                .assigned_node
+                .clone()
+        })
+        .filter(|node_name| !node_name.is_empty())
+        .collect();
+
+    // there are only three zones, so one replica stays pending
+    assert_eq!(3, assigned_nodes.len());
+    let zones: HashSet<&str> = assigned_nodes
+        .iter()
+        .map(|node_name| match node_name.as_str() {
+            "node_1" | "node_2" => "zone_a",
+            "node_3" => "zone_b",
+            _ => "zone_c",
+        })
+        .collect();
+    assert_eq!(3, zones.len());
+}
+
+#[test]
+fn test_pod_is_placed_to_zone_of_pods_it_has_affinity_to() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: db_pod
+            labels:
+              app: db
+          spec:
+            resources:
+              requests:
+                cpu: 6000
+                ram: 1073741824
+              limits:
+                cpu: 6000
+                ram: 1073741824
+            running_duration: 100.0
+            affinity:
+              node_affinity:
+                required:
+                - match_expressions:
+                  - key: zone
+                    operator: In
+                    values:
+                    - zone_b
+  - timestamp: 20
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: cache_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            affinity:
+              pod_affinity:
+                preferred:
+                - weight: 50
+                  pod_affinity_term:
+                    label_selector:
+                      match_labels:
+                        app: db
+                    topology_key: zone
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_3",
+        persistent_storage_borrowed.succeeded_pods["db_pod"]
+            .status
+            .assigned_node
+    );
+    // node_3 is the most loaded node, but the preferred affinity outweighs resources score
+    assert_eq!(
+        "node_3",
+        persistent_storage_borrowed.succeeded_pods["cache_pod"]
+            .status
+            .assigned_node
+    );
+}
@@ -0,0 +1,167 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::config::SimulationConfig;
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn multiple_schedulers_config(batch_scheduling_cycle_interval: f64) -> SimulationConfig {
+    default_test_simulation_config(Some(&format!(
+        r#"
+    additional_schedulers:
+    - name: batch_scheduler
+      scheduling_cycle_interval: {}
+      sched_to_as_network_delay: 0.5
+      scheduler:
+        profiles:
+        - scheduler_name: batch
+          plugins:
+            filter:
+            - name: Fit
+            score:
+            - name: LeastAllocatedResources
+    "#,
+        batch_scheduling_cycle_interval
+    )))
+}
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn pod_event(name: &str, scheduler_name: &str, cpu: u32) -> String {
+    format!(
+        r#"
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: {}
+            labels:
+              scheduler_name: {}
+          spec:
+            resources:
+              requests:
+                cpu: {}
+                ram: 1073741824
+              limits:
+                cpu: {}
+                ram: 1073741824
+            running_duration: 10.0"#,
+        name, scheduler_name, cpu, cpu
+    )
+}
+
+fn run_simulation(
+    config: SimulationConfig,
+    workload_trace: &mut GenericWorkloadTrace,
+) -> KubernetriksSimulation {
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+    kube_sim
+}
+
+fn start_time(kube_sim: &KubernetriksSimulation, pod_name: &str) -> f64 {
+    kube_sim.persistent_storage.borrow().succeeded_pods[pod_name]
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time
+}
+
+#[test]
+fn test_pods_are_routed_by_scheduler_name() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        pod_event("default_pod", "default_scheduler", 2000),
+        pod_event("batch_pod", "batch", 2000),
+    ))
+    .unwrap();
+
+    let kube_sim = run_simulation(multiple_schedulers_config(30.0), &mut workload_trace);
+
+    // Each scheduler places its pod in its own cycle.
+    assert!(start_time(&kube_sim, "default_pod") < 15.0);
+    assert!(start_time(&kube_sim, "batch_pod") > 30.0);
+    assert_eq!(
+        1,
+        kube_sim.additional_schedulers["batch_scheduler"]
+            .borrow()
+            .node_count()
+    );
+    assert_eq!(
+        0,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pod_assignment_conflicts
+    );
+}
+
+#[test]
+fn test_conflicting_assignment_is_retried() {
+    // Both schedulers see the free node in the cycle at 10.0, but only one pod fits into it.
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        pod_event("default_pod", "default_scheduler", 6000),
+        pod_event("batch_pod", "batch", 6000),
+    ))
+    .unwrap();
+
+    let kube_sim = run_simulation(multiple_schedulers_config(10.0), &mut workload_trace);
+
+    // Assignment of the batch scheduler comes later due to the longer network delay.
+    assert_eq!(
+        1,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pod_assignment_conflicts
+    );
+    let default_pod_finish_time = kube_sim.persistent_storage.borrow().succeeded_pods
+        ["default_pod"]
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    assert!(start_time(&kube_sim, "default_pod") < 15.0);
+    assert!(start_time(&kube_sim, "batch_pod") > default_pod_finish_time);
+}
+
+#[test]
+fn test_scheduler_name_served_by_several_components_is_rejected() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    additional_schedulers:
+    - name: batch_scheduler
+      scheduler:
+        profiles:
+        - scheduler_name: default_scheduler
+          plugins:
+            filter:
+            - name: Fit
+    "#,
+    ));
+    let err = KubernetriksSimulation::new(Rc::new(config)).err().unwrap();
+    assert!(err.contains("\"default_scheduler\" is served by several scheduler components"));
+}
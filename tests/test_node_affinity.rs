@@ -0,0 +1,214 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    // The biggest node has no labels, so it is preferred by LeastAllocatedResources plugin
+    // without constraints.
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+            labels:
+              zone: zone_a
+              disk: ssd
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+            labels:
+              zone: zone_b
+              disk: ssd
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_3
+            labels:
+              zone: zone_b
+              disk: hdd
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_4
+          status:
+            capacity:
+              cpu: 64000
+              ram: 68719476736
+  "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_pods_are_placed_according_to_node_affinity() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: ssd_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            node_selector:
+              disk: ssd
+            affinity:
+              node_affinity:
+                preferred:
+                - weight: 10
+                  preference:
+                    match_expressions:
+                    - key: zone
+                      operator: In
+                      values:
+                      - zone_b
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: not_zone_a_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            affinity:
+              node_affinity:
+                required:
+                - match_expressions:
+                  - key: zone
+                    operator: Exists
+                  - key: zone
+                    operator: NotIn
+                    values:
+                    - zone_a
+                  - key: disk
+                    operator: NotIn
+                    values:
+                    - ssd
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: unconstrained_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let assigned_node = |pod_name: &str| {
+        persistent_storage_borrowed.succeeded_pods[pod_name]
+            .status
+            .assigned_node
+            .clone()
+    };
+    assert_eq!("node_2", assigned_node("ssd_pod"));
+    assert_eq!("node_3", assigned_node("not_zone_a_pod"));
+    assert_eq!("node_4", assigned_node("unconstrained_pod"));
+}
+
+#[test]
+fn test_pod_group_template_carries_node_selector() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePodGroup
+        pod_group:
+          name: pod_group_1
+          initial_pod_count: 4
+          max_pod_count: 4
+          pod_template:
+            metadata:
+              name: pod_group_1
+            spec:
+              resources:
+                requests:
+                  cpu: 1000
+                  ram: 1073741824
+                limits:
+                  cpu: 1000
+                  ram: 1073741824
+              node_selector:
+                zone: zone_b
+          target_resources_usage:
+            cpu_utilization: 0.6
+          resources_usage_model_config:
+            cpu_config:
+              model_name: constant
+              config: |
+                usage: 0.5
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
+    kube_sim.step_until_time(30.0);
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    for idx in 0..4 {
+        let pod = persistent_storage_borrowed
+            .get_pod(&format!("pod_group_1_{}", idx))
+            .unwrap();
+        assert!(pod.status.assigned_node == "node_2" || pod.status.assigned_node == "node_3");
+    }
+}
@@ -41,7 +41,7 @@ fn test_node_creation_from_trace_and_default_cluster() {
     .unwrap();
     let mut workload_trace: GenericWorkloadTrace = Default::default();
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
 
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
 
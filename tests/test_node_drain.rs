@@ -0,0 +1,137 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::{
+    create_node_event, create_pod_event, default_test_simulation_config, TestPod,
+};
+
+#[test]
+fn test_cordoned_node_does_not_accept_pods_until_uncordoned() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        r#"
+  events:{}
+  - timestamp: 1
+    event_type:
+      !CordonNode
+        node_name: node_1
+  - timestamp: 50
+    event_type:
+      !UncordonNode
+        node_name: node_1
+  "#,
+        create_node_event(0.0, "node_1", 8000, 17179869184)
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}",
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                running_duration: 10.0,
+                ..Default::default()
+            }
+        )
+    ))
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.succeeded_pods["pod_1"];
+    assert_eq!("node_1", pod.status.assigned_node);
+    assert!(
+        pod.get_condition(PodConditionType::PodRunning)
+            .unwrap()
+            .last_transition_time
+            > 50.0
+    );
+    assert!(
+        !persistent_storage
+            .get_node("node_1")
+            .unwrap()
+            .spec
+            .unschedulable
+    );
+}
+
+#[test]
+fn test_drained_node_evicts_pods_after_grace_period_and_is_removed() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        r#"
+  events:{}{}
+  - timestamp: 30
+    event_type:
+      !DrainNode
+        node_name: node_1
+        grace_period: 10.0
+        remove_node: true
+  "#,
+        create_node_event(0.0, "node_1", 8000, 17179869184),
+        create_node_event(20.0, "node_2", 8000, 17179869184),
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        // Finishes within the grace period.
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                running_duration: 25.0,
+                ..Default::default()
+            }
+        ),
+        // Is evicted and placed to the other node.
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_2",
+                running_duration: 100.0,
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let config = Rc::new(default_test_simulation_config(None));
+    let mut kube_sim = KubernetriksSimulation::new(config.clone()).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_succeeded);
+    assert_eq!(1, metrics.pods_evicted_by_drain);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    let pod_2 = &persistent_storage.succeeded_pods["pod_2"];
+    assert_eq!("node_2", pod_2.status.assigned_node);
+    // Drain request goes through persistent storage to cordon the node first.
+    let eviction_time =
+        30.0 + 2.0 * config.as_to_ps_network_delay + config.as_to_node_network_delay + 10.0;
+    assert!(
+        (eviction_time
+            - pod_2
+                .get_condition(PodConditionType::PodEvicted)
+                .unwrap()
+                .last_transition_time)
+            .abs()
+            < 1e-9
+    );
+
+    assert!(persistent_storage.get_node("node_1").is_none());
+    assert_eq!(1, persistent_storage.node_count());
+}
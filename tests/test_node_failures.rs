@@ -0,0 +1,163 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::node::NodeConditionType;
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::{
+    create_node_event, create_pod_event, default_test_simulation_config, TestPod,
+};
+
+#[test]
+fn test_hard_failure_fails_running_pods() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        r#"
+  events:{}
+  - timestamp: 20
+    event_type:
+      !FailNode
+        node_name: node_1
+        failure_type: Hard
+  - timestamp: 50
+    event_type:
+      !RecoverNode
+        node_name: node_1
+  "#,
+        create_node_event(0.0, "node_1", 8000, 17179869184)
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}{}",
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                running_duration: 50.0,
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_2",
+                running_duration: 50.0,
+                ..Default::default()
+            }
+        ),
+        // Created while the node is failed, so it waits for the recovery.
+        create_pod_event(
+            30.0,
+            TestPod {
+                name: "pod_3",
+                running_duration: 50.0,
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let config = Rc::new(default_test_simulation_config(None));
+    let mut kube_sim = KubernetriksSimulation::new(config.clone()).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_failed);
+    assert_eq!(1, metrics.pods_succeeded);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    for pod_name in ["pod_1", "pod_2"] {
+        let failed_pod = &persistent_storage.failed_pods[pod_name];
+        // Pods fail when the request reaches the node.
+        assert_eq!(
+            20.0 + config.as_to_node_network_delay,
+            failed_pod
+                .get_condition(PodConditionType::PodFailed)
+                .unwrap()
+                .last_transition_time
+        );
+    }
+    let pod_3 = &persistent_storage.succeeded_pods["pod_3"];
+    assert!(
+        pod_3
+            .get_condition(PodConditionType::PodRunning)
+            .unwrap()
+            .last_transition_time
+            > 50.0
+    );
+
+    let node = persistent_storage.get_node("node_1").unwrap();
+    assert!(node.spec.taints.is_empty());
+    assert_eq!(
+        "True",
+        node.status
+            .conditions
+            .iter()
+            .find(|condition| condition.condition_type == NodeConditionType::NodeReady)
+            .unwrap()
+            .status
+    );
+}
+
+#[test]
+fn test_soft_failure_keeps_pods_running() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        r#"
+  events:{}{}
+  - timestamp: 10
+    event_type:
+      !FailNode
+        node_name: node_1
+        failure_type: Soft
+  "#,
+        create_node_event(0.0, "node_1", 8000, 17179869184),
+        create_node_event(15.0, "node_2", 8000, 17179869184),
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                running_duration: 50.0,
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            20.0,
+            TestPod {
+                name: "pod_2",
+                running_duration: 50.0,
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(0, metrics.pods_failed);
+    assert_eq!(2, metrics.pods_succeeded);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    // Failed node is not ready for new pods.
+    assert_eq!(
+        "node_2",
+        persistent_storage.succeeded_pods["pod_2"]
+            .status
+            .assigned_node
+    );
+}
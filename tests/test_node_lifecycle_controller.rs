@@ -0,0 +1,197 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::node::{NodeConditionType, NODE_UNREACHABLE_TAINT_KEY};
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+// The first node stops sending heartbeats due to soft failure and recovers much later, the second
+// node is added after the pod is placed to the first one.
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 10
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  - timestamp: 20
+    event_type:
+      !FailNode
+        node_name: node_1
+        failure_type: Soft
+  - timestamp: 500
+    event_type:
+      !RecoverNode
+        node_name: node_1
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_1
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 1073741824
+              limits:
+                cpu: 2000
+                ram: 1073741824
+            running_duration: 1000.0
+  "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_pods_are_evicted_from_unreachable_node() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    node_lifecycle_controller:
+      enabled: true
+      node_status_update_frequency: 10.0
+      node_monitor_period: 5.0
+      node_monitor_grace_period: 40.0
+      default_toleration_seconds: 60.0
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace());
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.succeeded_pods["pod_1"];
+    assert_eq!("node_2", pod.status.assigned_node);
+    // The node is considered unreachable in the grace period after the last heartbeat and the pod
+    // is evicted when its default toleration runs out.
+    let eviction_time = pod
+        .get_condition(PodConditionType::PodEvicted)
+        .unwrap()
+        .last_transition_time;
+    assert!(eviction_time > 20.0 + 40.0 + 60.0);
+    assert!(eviction_time < 20.0 + 40.0 + 60.0 + 20.0);
+    assert_eq!(
+        1,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_evicted_by_taints
+    );
+
+    // Heartbeats are sent again after recovery.
+    let node = persistent_storage.get_node("node_1").unwrap();
+    assert!(node
+        .spec
+        .taints
+        .iter()
+        .all(|taint| taint.key != NODE_UNREACHABLE_TAINT_KEY));
+    let ready_condition = node
+        .status
+        .conditions
+        .iter()
+        .find(|condition| condition.condition_type == NodeConditionType::NodeReady)
+        .unwrap();
+    assert_eq!("True", ready_condition.status);
+    assert!(ready_condition.last_transition_time > 500.0);
+}
+
+#[test]
+fn test_initial_pods_of_pod_group_tolerate_unreachable_node() {
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePodGroup
+        pod_group:
+          name: pod_group_1
+          initial_pod_count: 2
+          max_pod_count: 2
+          pod_template:
+            metadata:
+              name: pod_group_1
+            spec:
+              resources:
+                requests:
+                  cpu: 1000
+                  ram: 1073741824
+                limits:
+                  cpu: 1000
+                  ram: 1073741824
+          target_resources_usage:
+            cpu_utilization: 0.6
+          resources_usage_model_config:
+            ram_config:
+              model_name: constant
+              config: "usage: 1073741824"
+  "#,
+    )
+    .unwrap();
+    let config = default_test_simulation_config(Some(
+        r#"
+    node_lifecycle_controller:
+      enabled: true
+      node_status_update_frequency: 10.0
+      node_monitor_period: 5.0
+      node_monitor_grace_period: 40.0
+      default_toleration_seconds: 60.0
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
+
+    let pods_evicted_by_taints = |kube_sim: &KubernetriksSimulation| {
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_evicted_by_taints
+    };
+    // The node is tainted as unreachable, but pods of the group still tolerate it like other pods.
+    kube_sim.step_until_time(20.0 + 40.0 + 40.0);
+    assert_eq!(0, pods_evicted_by_taints(&kube_sim));
+
+    kube_sim.step_until_time(20.0 + 40.0 + 60.0 + 30.0);
+    assert_eq!(2, pods_evicted_by_taints(&kube_sim));
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    for pod_name in ["pod_group_1_0", "pod_group_1_1"] {
+        assert_eq!(
+            "node_2",
+            persistent_storage
+                .get_pod(pod_name)
+                .unwrap()
+                .status
+                .assigned_node
+        );
+    }
+}
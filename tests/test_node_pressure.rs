@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::node::{
+    NodeConditionType, NODE_CPU_PRESSURE_TAINT_KEY, NODE_MEMORY_PRESSURE_TAINT_KEY,
+};
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::{
+    create_node_event, create_pod_event, default_test_simulation_config, TestPod,
+};
+
+fn run_simulation(cluster_trace: &str, workload_trace: &str) -> KubernetriksSimulation {
+    let mut cluster_trace: GenericClusterTrace =
+        serde_yaml::from_str(&format!("events:{}", cluster_trace)).unwrap();
+    let mut workload_trace: GenericWorkloadTrace =
+        serde_yaml::from_str(&format!("events:{}", workload_trace)).unwrap();
+
+    let mut config = default_test_simulation_config(None);
+    config.node_pressure.enabled = true;
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    // Pressure checks never stop, so simulation is run for a time enough for all pods to finish.
+    kube_sim.step_until_time(300.0);
+    kube_sim
+}
+
+#[test]
+fn test_node_under_memory_pressure_evicts_pod_exceeding_requests() {
+    let kube_sim = run_simulation(
+        &format!(
+            "{}{}",
+            create_node_event(0.0, "node_1", 8000, 17179869184),
+            create_node_event(15.0, "node_2", 8000, 68719476736)
+        ),
+        &format!(
+            "{}{}",
+            create_pod_event(
+                5.0,
+                TestPod {
+                    name: "pod_1",
+                    cpu_usage: Some(1000),
+                    ram_usage: Some(1073741824),
+                    ..Default::default()
+                }
+            ),
+            // Uses 15Gi of 16Gi node together with the first pod.
+            create_pod_event(
+                5.0,
+                TestPod {
+                    name: "pod_2",
+                    cpu_usage: Some(1000),
+                    ram_usage: Some(16106127360),
+                    ..Default::default()
+                }
+            ),
+        ),
+    );
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_succeeded);
+    assert_eq!(1, metrics.pods_evicted_by_node_pressure);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "node_2",
+        persistent_storage.succeeded_pods["pod_2"]
+            .status
+            .assigned_node
+    );
+
+    // Pressure is gone after the eviction.
+    let node = persistent_storage.get_node("node_1").unwrap();
+    assert_eq!(
+        "False",
+        node.get_condition(NodeConditionType::MemoryPressure)
+            .unwrap()
+            .status
+    );
+    assert!(node
+        .spec
+        .taints
+        .iter()
+        .all(|taint| taint.key != NODE_MEMORY_PRESSURE_TAINT_KEY));
+}
+
+#[test]
+fn test_pods_are_not_placed_to_node_under_cpu_pressure() {
+    let kube_sim = run_simulation(
+        &format!(
+            "{}{}",
+            create_node_event(0.0, "node_1", 8000, 17179869184),
+            create_node_event(50.0, "node_2", 8000, 17179869184)
+        ),
+        &format!(
+            "{}{}",
+            create_pod_event(
+                5.0,
+                TestPod {
+                    name: "pod_1",
+                    cpu_usage: Some(8000),
+                    ram_usage: Some(1073741824),
+                    ..Default::default()
+                }
+            ),
+            create_pod_event(
+                30.0,
+                TestPod {
+                    name: "pod_2",
+                    cpu_usage: Some(1000),
+                    ram_usage: Some(1073741824),
+                    ..Default::default()
+                }
+            ),
+        ),
+    );
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_succeeded);
+    // Cpu is compressible, so pods are not evicted.
+    assert_eq!(0, metrics.pods_evicted_by_node_pressure);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "node_2",
+        persistent_storage.succeeded_pods["pod_2"]
+            .status
+            .assigned_node
+    );
+
+    let node = persistent_storage.get_node("node_1").unwrap();
+    assert_eq!(
+        "False",
+        node.get_condition(NodeConditionType::CPUPressure)
+            .unwrap()
+            .status
+    );
+    assert!(node
+        .spec
+        .taints
+        .iter()
+        .all(|taint| taint.key != NODE_CPU_PRESSURE_TAINT_KEY));
+}
@@ -0,0 +1,143 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace(restart_policy: &str, failure_spec: &str) -> GenericWorkloadTrace {
+    serde_yaml::from_str(&format!(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_1
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 1073741824
+              limits:
+                cpu: 2000
+                ram: 1073741824
+            running_duration: 30.0
+            restart_policy: {}{}
+  "#,
+        restart_policy, failure_spec
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_failed_pod_is_not_restarted_with_never_policy() {
+    let mut cluster_trace = get_cluster_trace();
+    let mut workload_trace = get_workload_trace(
+        "Never",
+        r#"
+            failure_probability: 1.0
+            failure_time: 20.0"#,
+    );
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(1, metrics.pods_failed);
+    assert_eq!(0, metrics.pods_succeeded);
+    assert_eq!(0, metrics.pod_restarts);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.failed_pods["pod_1"];
+    let start_time = pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let failure_time = pod
+        .get_condition(PodConditionType::PodFailed)
+        .unwrap()
+        .last_transition_time;
+    assert!((failure_time - start_time - 20.0).abs() < 1e-9);
+    assert_eq!(0, pod.status.restart_count);
+}
+
+#[test]
+fn test_failed_pod_is_restarted_until_success_with_on_failure_policy() {
+    let mut cluster_trace = get_cluster_trace();
+    let mut workload_trace = get_workload_trace(
+        "OnFailure",
+        r#"
+            failure_probability: 0.5
+            failure_time: 20.0"#,
+    );
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(0, metrics.pods_failed);
+    assert_eq!(1, metrics.pods_succeeded);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.succeeded_pods["pod_1"];
+    assert_eq!(metrics.pod_restarts, pod.status.restart_count as u64);
+    // The last run is successful and starts on restart if there were any.
+    let start_time = pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let finish_time = pod
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    assert!((finish_time - start_time - 30.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_pod_is_restarted_with_crash_loop_backoff_with_always_policy() {
+    let mut cluster_trace = get_cluster_trace();
+    let mut workload_trace = get_workload_trace("Always", "");
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    // Pod starts at about 10, runs 30 seconds and restarts after 10, 20 and 40 seconds of backoff,
+    // the next restart after 80 seconds is beyond the simulated time.
+    kube_sim.step_until_time(200.0);
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(0, metrics.pods_succeeded);
+    assert_eq!(3, metrics.pod_restarts);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = persistent_storage.get_pod("pod_1").unwrap();
+    assert_eq!(3, pod.status.restart_count);
+    assert_eq!("node_1", pod.status.assigned_node);
+}
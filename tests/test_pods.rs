@@ -73,7 +73,8 @@ fn get_workload_trace() -> GenericWorkloadTrace {
 
 #[test]
 fn test_pod_arrived_before_a_node() {
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
         &r#"
     events:
@@ -183,7 +184,8 @@ fn test_many_pods_running_one_at_a_time_at_slow_node() {
     )
     .unwrap();
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize(&mut get_cluster_trace(), &mut workload_trace);
     kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
 
@@ -219,7 +221,8 @@ fn test_many_pods_running_one_at_a_time_at_slow_node() {
 
 #[test]
 fn test_node_fits_all_pods() {
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
 
     let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
         &r#"
@@ -314,7 +317,8 @@ fn test_node_fits_all_pods() {
 
 #[test]
 fn test_node_remove_while_pods_were_running() {
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
 
     let mut workload_trace: GenericWorkloadTrace = get_workload_trace();
 
@@ -366,7 +370,8 @@ fn test_node_remove_while_pods_were_running() {
 
 #[test]
 fn test_node_removed_at_the_same_time_as_assignment() {
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
 
     let mut workload_trace: GenericWorkloadTrace = get_workload_trace();
 
@@ -408,7 +413,8 @@ fn test_pod_removals() {
         },
     });
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
 
     kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
@@ -478,7 +484,8 @@ fn test_pod_removal_concurrently_with_node_removal() {
         },
     });
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
     kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
 
@@ -533,7 +540,8 @@ fn test_removed_pod_frees_place_for_other_pod() {
         },
     });
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
 
     kube_sim.step_for_duration(100.0);
@@ -605,7 +613,8 @@ fn test_pod_removed_after_it_was_finished() {
         },
     });
 
-    let mut kube_sim = KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None)));
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
     kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
     kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
 
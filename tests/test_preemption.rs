@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 30
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: trace_node_42
+          status:
+            capacity:
+              cpu: 2000
+              ram: 4294967296
+  "#,
+    )
+    .unwrap()
+}
+
+// Pods which occupy the whole node, the high priority one comes at `high_priority_pod_timestamp`.
+fn get_workload_trace(high_priority_pod_timestamp: f64) -> GenericWorkloadTrace {
+    serde_yaml::from_str(&format!(
+        r#"
+  events:
+  - timestamp: 41
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: low_priority_pod
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 4294967296
+              limits:
+                cpu: 2000
+                ram: 4294967296
+            running_duration: 100.0
+  - timestamp: {}
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: high_priority_pod
+          spec:
+            resources:
+              requests:
+                cpu: 2000
+                ram: 4294967296
+              limits:
+                cpu: 2000
+                ram: 4294967296
+            running_duration: 50.0
+            priority: 100
+  "#,
+        high_priority_pod_timestamp
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_high_priority_pod_is_popped_first() {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    // Both pods are in the active queue at the scheduling cycle at 50.0
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace(45.0));
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let low_priority_pod = persistent_storage_borrowed
+        .succeeded_pods
+        .get("low_priority_pod")
+        .unwrap();
+    let high_priority_pod = persistent_storage_borrowed
+        .succeeded_pods
+        .get("high_priority_pod")
+        .unwrap();
+
+    assert!(
+        high_priority_pod
+            .get_condition(PodConditionType::PodSucceeded)
+            .unwrap()
+            .last_transition_time
+            <= low_priority_pod
+                .get_condition(PodConditionType::PodRunning)
+                .unwrap()
+                .last_transition_time
+    );
+    assert!(low_priority_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .is_none());
+    assert_eq!(
+        0,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_preempted
+    );
+}
+
+#[test]
+fn test_high_priority_pod_preempts_running_pod() {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    // Low priority pod is already running when high priority one comes.
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace(55.0));
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics_collector_borrowed = kube_sim.metrics_collector.borrow();
+    assert_eq!(
+        1,
+        metrics_collector_borrowed
+            .accumulated_metrics
+            .pods_preempted
+    );
+    assert_eq!(
+        2,
+        metrics_collector_borrowed
+            .accumulated_metrics
+            .pods_succeeded
+    );
+    assert_eq!(
+        0,
+        metrics_collector_borrowed.accumulated_metrics.pods_removed
+    );
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let low_priority_pod = persistent_storage_borrowed
+        .succeeded_pods
+        .get("low_priority_pod")
+        .unwrap();
+    let high_priority_pod = persistent_storage_borrowed
+        .succeeded_pods
+        .get("high_priority_pod")
+        .unwrap();
+
+    let eviction_time = low_priority_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .unwrap()
+        .last_transition_time;
+    let high_priority_pod_start_time = high_priority_pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let high_priority_pod_finish_time = high_priority_pod
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    let low_priority_pod_restart_time = low_priority_pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let low_priority_pod_finish_time = low_priority_pod
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+
+    // preemption happens in scheduling cycle at 60.0
+    assert!(eviction_time > 60.0 && eviction_time < high_priority_pod_start_time);
+    // evicted pod is placed again when the high priority pod finishes and runs its full duration
+    assert!(low_priority_pod_restart_time >= high_priority_pod_finish_time);
+    assert_eq!(
+        100.0,
+        low_priority_pod_finish_time - low_priority_pod_restart_time
+    );
+}
+
+#[test]
+fn test_no_preemption_without_post_filter_plugin() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    scheduler:
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          score:
+          - name: LeastAllocatedResources
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace(55.0));
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    assert_eq!(
+        0,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_preempted
+    );
+
+    // high priority pod waits until the low priority one finishes
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let low_priority_pod_finish_time = persistent_storage_borrowed.succeeded_pods
+        ["low_priority_pod"]
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    let high_priority_pod_start_time = persistent_storage_borrowed.succeeded_pods
+        ["high_priority_pod"]
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    assert!(high_priority_pod_start_time > low_priority_pod_finish_time);
+}
@@ -0,0 +1,159 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::QoSClass;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::{
+    create_node_event, create_pod_event, default_test_simulation_config, TestPod,
+};
+
+#[test]
+fn test_pods_of_each_qos_class_are_counted_separately() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        "events:{}",
+        create_node_event(0.0, "node_1", 8000, 17179869184)
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}{}{}",
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "guaranteed",
+                ram_usage: Some(1073741824),
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "burstable",
+                limits: (2000, 2147483648),
+                ram_usage: Some(1073741824),
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "best_effort",
+                requests: (0, 0),
+                limits: (0, 0),
+                ram_usage: Some(0),
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "failed_best_effort",
+                requests: (0, 0),
+                limits: (0, 0),
+                ram_usage: Some(0),
+                failure_time: Some(10.0),
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        QoSClass::Guaranteed,
+        persistent_storage.succeeded_pods["guaranteed"]
+            .status
+            .qos_class
+    );
+    assert_eq!(
+        QoSClass::Burstable,
+        persistent_storage.succeeded_pods["burstable"]
+            .status
+            .qos_class
+    );
+    assert_eq!(
+        QoSClass::BestEffort,
+        persistent_storage.succeeded_pods["best_effort"]
+            .status
+            .qos_class
+    );
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(3, metrics.pods_succeeded);
+    assert_eq!(1, metrics.pods_failed);
+    let expected = [
+        (QoSClass::Guaranteed, 1, 0),
+        (QoSClass::Burstable, 1, 0),
+        (QoSClass::BestEffort, 1, 1),
+    ];
+    for (qos_class, pods_succeeded, pods_failed) in expected.into_iter() {
+        let qos_class_metrics = &metrics.qos_class_metrics[&qos_class];
+        assert_eq!(pods_succeeded, qos_class_metrics.pods_succeeded);
+        assert_eq!(pods_failed, qos_class_metrics.pods_failed);
+        // Every class has scheduled pods, so the queue time is recorded for it.
+        assert!(qos_class_metrics.pod_queue_time_stats.mean() >= 0.0);
+    }
+}
+
+#[test]
+fn test_node_under_memory_pressure_evicts_less_protected_pod_first() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        create_node_event(0.0, "node_1", 8000, 17179869184),
+        create_node_event(15.0, "node_2", 8000, 68719476736)
+    ))
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        // Uses much more memory than it requests, but is protected by its class.
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_1",
+                ram_usage: Some(16106127360),
+                ..Default::default()
+            }
+        ),
+        create_pod_event(
+            5.0,
+            TestPod {
+                name: "pod_2",
+                limits: (1000, 2147483648),
+                ram_usage: Some(1073741824),
+                ..Default::default()
+            }
+        ),
+    ))
+    .unwrap();
+
+    let mut config = default_test_simulation_config(None);
+    config.node_pressure.enabled = true;
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    // Pressure checks never stop, so simulation is run for a time enough for all pods to finish.
+    kube_sim.step_until_time(300.0);
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(2, metrics.pods_succeeded);
+    assert_eq!(1, metrics.pods_evicted_by_node_pressure);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "node_1",
+        persistent_storage.succeeded_pods["pod_1"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "node_2",
+        persistent_storage.succeeded_pods["pod_2"]
+            .status
+            .assigned_node
+    );
+}
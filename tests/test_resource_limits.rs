@@ -0,0 +1,113 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::{PodConditionType, PodFailureReason};
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace(cpu_usage: u32, ram_usage: u64) -> GenericWorkloadTrace {
+    serde_yaml::from_str(&format!(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_1
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+              usage_model_config:
+                cpu_config:
+                  model_name: constant
+                  config: "usage: {}"
+                ram_config:
+                  model_name: constant
+                  config: "usage: {}"
+            running_duration: 30.0
+  "#,
+        cpu_usage, ram_usage
+    ))
+    .unwrap()
+}
+
+fn run_simulation(mut workload_trace: GenericWorkloadTrace) -> KubernetriksSimulation {
+    let mut cluster_trace = get_cluster_trace();
+    let mut config = default_test_simulation_config(None);
+    config.resource_limits.enabled = true;
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    // Limits checks never stop, so simulation is run for a time enough for the pod to finish.
+    kube_sim.step_until_time(200.0);
+    kube_sim
+}
+
+#[test]
+fn test_pod_exceeding_memory_limit_is_oom_killed() {
+    let kube_sim = run_simulation(get_workload_trace(1000, 2147483648));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(1, metrics.pods_failed);
+    assert_eq!(1, metrics.pods_oom_killed);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.failed_pods["pod_1"];
+    assert_eq!(Some(PodFailureReason::OOMKilled), pod.status.failure_reason);
+    // Constant usage exceeds the limit right at the start.
+    let start_time = pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let failure_time = pod
+        .get_condition(PodConditionType::PodFailed)
+        .unwrap()
+        .last_transition_time;
+    assert!((failure_time - start_time).abs() < 1e-9);
+}
+
+#[test]
+fn test_pod_exceeding_cpu_limit_is_throttled() {
+    let kube_sim = run_simulation(get_workload_trace(2000, 1073741824));
+
+    let metrics = &kube_sim.metrics_collector.borrow().accumulated_metrics;
+    assert_eq!(1, metrics.pods_succeeded);
+
+    let persistent_storage = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage.succeeded_pods["pod_1"];
+    // Pod gets half of the cpu it needs, so it runs twice as long.
+    let start_time = pod
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time;
+    let finish_time = pod
+        .get_condition(PodConditionType::PodSucceeded)
+        .unwrap()
+        .last_transition_time;
+    assert!((finish_time - start_time - 60.0).abs() < 1e-9);
+}
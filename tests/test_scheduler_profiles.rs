@@ -0,0 +1,224 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn get_cluster_trace() -> GenericClusterTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: small_node
+            labels:
+              disk: ssd
+          status:
+            capacity:
+              cpu: 4000
+              ram: 8589934592
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: big_node
+          status:
+            capacity:
+              cpu: 32000
+              ram: 68719476736
+  "#,
+    )
+    .unwrap()
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: default_profile_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            node_selector:
+              disk: ssd
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: resources_only_profile_pod
+            labels:
+              scheduler_name: resources_only_scheduler
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            node_selector:
+              disk: ssd
+  "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_pods_are_scheduled_with_profiles_from_config() {
+    // The second profile does not enable NodeAffinity plugin, so node selector is ignored.
+    let config = default_test_simulation_config(Some(
+        r#"
+    scheduler:
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          - name: NodeAffinity
+          score:
+          - name: LeastAllocatedResources
+            weight: 1.0
+      - scheduler_name: resources_only_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          score:
+          - name: LeastAllocatedResources
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(&mut get_cluster_trace(), &mut get_workload_trace());
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    assert_eq!(
+        "small_node",
+        persistent_storage_borrowed.succeeded_pods["default_profile_pod"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "big_node",
+        persistent_storage_borrowed.succeeded_pods["resources_only_profile_pod"]
+            .status
+            .assigned_node
+    );
+}
+
+#[test]
+fn test_simulation_with_unknown_plugin_in_config_fails_to_start() {
+    let config = default_test_simulation_config(Some(
+        r#"
+    scheduler:
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          - name: NodeAfinity
+    "#,
+    ));
+    let err = KubernetriksSimulation::new(Rc::new(config)).err().unwrap();
+    assert!(err.contains("plugin \"NodeAfinity\" is not registered"));
+}
+
+#[test]
+fn test_most_allocated_plugin_packs_pods_to_one_node() {
+    let mut cluster_trace = "events:".to_string();
+    for name in ["node_1", "node_2", "node_3"] {
+        cluster_trace += &format!(
+            r#"
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: {}
+          status:
+            capacity:
+              cpu: 8000
+              ram: 17179869184
+  "#,
+            name
+        );
+    }
+    let mut workload_trace = "events:".to_string();
+    for idx in 0..4 {
+        workload_trace += &format!(
+            r#"
+  - timestamp: {}
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_{}
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 100.0
+  "#,
+            5 + idx * 10,
+            idx
+        );
+    }
+
+    let config = default_test_simulation_config(Some(
+        r#"
+    scheduler:
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          score:
+          - name: MostAllocated
+            args:
+              resources:
+                cpu: 2.0
+                ram: 1.0
+    "#,
+    ));
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(config)).unwrap();
+    kube_sim.initialize(
+        &mut serde_yaml::from_str::<GenericClusterTrace>(&cluster_trace).unwrap(),
+        &mut serde_yaml::from_str::<GenericWorkloadTrace>(&workload_trace).unwrap(),
+    );
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let first_node = &persistent_storage_borrowed.succeeded_pods["pod_0"]
+        .status
+        .assigned_node;
+    for idx in 1..4 {
+        assert_eq!(
+            first_node,
+            &persistent_storage_borrowed.succeeded_pods[&format!("pod_{}", idx)]
+                .status
+                .assigned_node
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::config::SimulationConfig;
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+fn scheduling_queue_config(pod_initial_backoff_seconds: f64) -> SimulationConfig {
+    default_test_simulation_config(Some(&format!(
+        r#"
+    scheduler:
+      pod_initial_backoff_seconds: {}
+      pod_max_backoff_seconds: 60.0
+      profiles:
+      - scheduler_name: default_scheduler
+        plugins:
+          filter:
+          - name: Fit
+          score:
+          - name: LeastAllocatedResources
+    "#,
+        pod_initial_backoff_seconds
+    )))
+}
+
+fn node_event(name: &str, cpu: u32, timestamp: f64) -> String {
+    format!(
+        r#"
+  - timestamp: {}
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: {}
+          status:
+            capacity:
+              cpu: {}
+              ram: 17179869184"#,
+        timestamp, name, cpu
+    )
+}
+
+fn get_workload_trace() -> GenericWorkloadTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_1
+          spec:
+            resources:
+              requests:
+                cpu: 8000
+                ram: 1073741824
+              limits:
+                cpu: 8000
+                ram: 1073741824
+            running_duration: 10.0
+  "#,
+    )
+    .unwrap()
+}
+
+fn pod_start_time(kube_sim: &KubernetriksSimulation, pod_name: &str) -> f64 {
+    kube_sim
+        .persistent_storage
+        .borrow()
+        .get_pod(pod_name)
+        .unwrap()
+        .get_condition(PodConditionType::PodRunning)
+        .unwrap()
+        .last_transition_time
+}
+
+#[test]
+fn test_irrelevant_cluster_events_do_not_move_pods() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        "events:{}{}{}",
+        node_event("node_1", 4000, 0.0),
+        node_event("node_2", 4000, 15.0),
+        node_event("node_3", 16000, 35.0),
+    ))
+    .unwrap();
+
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(scheduling_queue_config(1.0))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut get_workload_trace());
+
+    // The pod does not fit into the added node_2, so it stays unschedulable.
+    kube_sim.step_until_time(30.0);
+    assert_eq!(1, kube_sim.scheduler.borrow().unschedulable_pods.len());
+
+    kube_sim.step_until_time(45.0);
+    assert!(kube_sim.scheduler.borrow().unschedulable_pods.is_empty());
+    assert!(pod_start_time(&kube_sim, "pod_1") > 40.0);
+}
+
+#[test]
+fn test_pod_waits_in_backoff_queue() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(&format!(
+        "events:{}{}",
+        node_event("node_1", 4000, 0.0),
+        node_event("node_2", 16000, 12.0),
+    ))
+    .unwrap();
+
+    let mut kube_sim = KubernetriksSimulation::new(Rc::new(scheduling_queue_config(15.0))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut get_workload_trace());
+
+    // The pod fails at 10.0, so it is not retried in the cycle at 20.0 even though node_2 fits.
+    kube_sim.step_until_time(20.0);
+    assert_eq!(1, kube_sim.scheduler.borrow().backoff_queue.len());
+
+    kube_sim.step_until_time(35.0);
+    assert!(kube_sim.scheduler.borrow().backoff_queue.is_empty());
+    let start_time = pod_start_time(&kube_sim, "pod_1");
+    assert!(start_time > 30.0 && start_time < 40.0);
+}
@@ -0,0 +1,394 @@
+use std::rc::Rc;
+
+use dslab_kubernetriks::core::pod::PodConditionType;
+use dslab_kubernetriks::simulation_callbacks::RunUntilAllPodsAreFinishedCallbacks;
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+#[test]
+fn test_pods_are_not_scheduled_to_nodes_with_intolerable_taints() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: gpu_node
+          spec:
+            taints:
+            - key: dedicated
+              value: gpu
+              effect: NoSchedule
+          status:
+            capacity:
+              cpu: 16000
+              ram: 17179869184
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: common_node
+          status:
+            capacity:
+              cpu: 2000
+              ram: 4294967296
+  "#,
+    )
+    .unwrap();
+
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: common_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+  - timestamp: 5
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: gpu_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 10.0
+            tolerations:
+            - key: dedicated
+              value: gpu
+              effect: NoSchedule
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    // gpu_node has much more free resources, but common pod does not tolerate its taint
+    assert_eq!(
+        "common_node",
+        persistent_storage_borrowed.succeeded_pods["common_pod"]
+            .status
+            .assigned_node
+    );
+    assert_eq!(
+        "gpu_node",
+        persistent_storage_borrowed.succeeded_pods["gpu_pod"]
+            .status
+            .assigned_node
+    );
+}
+
+fn no_execute_cluster_trace(untaint_timestamp: Option<f64>) -> GenericClusterTrace {
+    // node_2 is created after pods are scheduled to node_1, then node_1 is tainted
+    let mut trace = r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  - timestamp: 20
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  - timestamp: 30
+    event_type:
+      !TaintNode
+        node_name: node_1
+        taint:
+          key: maintenance
+          effect: NoExecute
+  "#
+    .to_string();
+    if let Some(timestamp) = untaint_timestamp {
+        trace += &format!(
+            r#"
+  - timestamp: {}
+    event_type:
+      !UntaintNode
+        node_name: node_1
+        key: maintenance
+        effect: NoExecute
+  "#,
+            timestamp
+        );
+    }
+    serde_yaml::from_str(&trace).unwrap()
+}
+
+fn no_execute_workload_trace() -> GenericWorkloadTrace {
+    serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 1
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: intolerant_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 100.0
+  - timestamp: 1
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: tolerating_for_a_while_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 100.0
+            tolerations:
+            - key: maintenance
+              operator: Exists
+              effect: NoExecute
+              toleration_seconds: 20.0
+  - timestamp: 1
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: tolerating_pod
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 100.0
+            tolerations:
+            - key: maintenance
+              operator: Exists
+  "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_no_execute_taint_evicts_running_pods() {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(
+        &mut no_execute_cluster_trace(None),
+        &mut no_execute_workload_trace(),
+    );
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    let metrics_collector_borrowed = kube_sim.metrics_collector.borrow();
+    assert_eq!(
+        2,
+        metrics_collector_borrowed
+            .accumulated_metrics
+            .pods_evicted_by_taints
+    );
+    assert_eq!(
+        3,
+        metrics_collector_borrowed
+            .accumulated_metrics
+            .pods_succeeded
+    );
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let intolerant_pod = &persistent_storage_borrowed.succeeded_pods["intolerant_pod"];
+    let tolerating_for_a_while_pod =
+        &persistent_storage_borrowed.succeeded_pods["tolerating_for_a_while_pod"];
+    let tolerating_pod = &persistent_storage_borrowed.succeeded_pods["tolerating_pod"];
+
+    assert_eq!("node_2", intolerant_pod.status.assigned_node);
+    assert_eq!("node_2", tolerating_for_a_while_pod.status.assigned_node);
+    assert_eq!("node_1", tolerating_pod.status.assigned_node);
+    assert!(tolerating_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .is_none());
+
+    let intolerant_pod_eviction_time = intolerant_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .unwrap()
+        .last_transition_time;
+    let tolerating_for_a_while_pod_eviction_time = tolerating_for_a_while_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .unwrap()
+        .last_transition_time;
+    // taint reaches node component shortly after 30.0
+    assert!(intolerant_pod_eviction_time > 30.0 && intolerant_pod_eviction_time < 31.0);
+    assert_eq!(
+        20.0,
+        tolerating_for_a_while_pod_eviction_time - intolerant_pod_eviction_time
+    );
+}
+
+#[test]
+fn test_untaint_cancels_delayed_eviction() {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(
+        &mut no_execute_cluster_trace(Some(40.0)),
+        &mut no_execute_workload_trace(),
+    );
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    assert_eq!(
+        1,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_evicted_by_taints
+    );
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let tolerating_for_a_while_pod =
+        &persistent_storage_borrowed.succeeded_pods["tolerating_for_a_while_pod"];
+    assert_eq!("node_1", tolerating_for_a_while_pod.status.assigned_node);
+    assert!(tolerating_for_a_while_pod
+        .get_condition(PodConditionType::PodEvicted)
+        .is_none());
+}
+
+#[test]
+fn test_new_taint_with_shorter_toleration_brings_eviction_forward() {
+    let mut cluster_trace: GenericClusterTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_1
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  - timestamp: 20
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: node_2
+          status:
+            capacity:
+              cpu: 4000
+              ram: 17179869184
+  - timestamp: 30
+    event_type:
+      !TaintNode
+        node_name: node_1
+        taint:
+          key: maintenance
+          effect: NoExecute
+  - timestamp: 40
+    event_type:
+      !TaintNode
+        node_name: node_1
+        taint:
+          key: outage
+          effect: NoExecute
+  "#,
+    )
+    .unwrap();
+    let mut workload_trace: GenericWorkloadTrace = serde_yaml::from_str(
+        r#"
+  events:
+  - timestamp: 1
+    event_type:
+      !CreatePod
+        pod:
+          metadata:
+            name: pod_1
+          spec:
+            resources:
+              requests:
+                cpu: 1000
+                ram: 1073741824
+              limits:
+                cpu: 1000
+                ram: 1073741824
+            running_duration: 100.0
+            tolerations:
+            - key: maintenance
+              operator: Exists
+              effect: NoExecute
+              toleration_seconds: 60.0
+            - key: outage
+              operator: Exists
+              effect: NoExecute
+              toleration_seconds: 20.0
+  "#,
+    )
+    .unwrap();
+
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(&mut cluster_trace, &mut workload_trace);
+    kube_sim.run_with_callbacks(Box::new(RunUntilAllPodsAreFinishedCallbacks {}));
+
+    assert_eq!(
+        1,
+        kube_sim
+            .metrics_collector
+            .borrow()
+            .accumulated_metrics
+            .pods_evicted_by_taints
+    );
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let pod = &persistent_storage_borrowed.succeeded_pods["pod_1"];
+    assert_eq!("node_2", pod.status.assigned_node);
+    // The eviction is counted from the first taint with the shorter toleration of the second one.
+    let eviction_time = pod
+        .get_condition(PodConditionType::PodEvicted)
+        .unwrap()
+        .last_transition_time;
+    assert!(eviction_time > 50.0 && eviction_time < 51.0);
+}
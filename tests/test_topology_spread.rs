@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_kubernetriks::simulator::KubernetriksSimulation;
+use dslab_kubernetriks::trace::generic::{GenericClusterTrace, GenericWorkloadTrace};
+
+use dslab_kubernetriks::test_util::helpers::default_test_simulation_config;
+
+// Zone zone_a has much bigger nodes, so without spread constraints LeastAllocatedResources plugin
+// would place all pods there.
+fn get_cluster_trace() -> GenericClusterTrace {
+    let mut trace = "events:".to_string();
+    for (name, zone, cpu) in [
+        ("node_1", "zone_a", 64000),
+        ("node_2", "zone_a", 64000),
+        ("node_3", "zone_b", 8000),
+        ("node_4", "zone_c", 8000),
+    ] {
+        trace += &format!(
+            r#"
+  - timestamp: 0
+    event_type:
+      !CreateNode
+        node:
+          metadata:
+            name: {}
+            labels:
+              zone: {}
+          status:
+            capacity:
+              cpu: {}
+              ram: 17179869184
+  "#,
+            name, zone, cpu
+        );
+    }
+    serde_yaml::from_str(&trace).unwrap()
+}
+
+fn get_workload_trace(when_unsatisfiable: &str) -> GenericWorkloadTrace {
+    serde_yaml::from_str(&format!(
+        r#"
+  events:
+  - timestamp: 5
+    event_type:
+      !CreatePodGroup
+        pod_group:
+          name: pod_group_1
+          initial_pod_count: 6
+          max_pod_count: 6
+          pod_template:
+            metadata:
+              name: pod_group_1
+            spec:
+              resources:
+                requests:
+                  cpu: 1000
+                  ram: 1073741824
+                limits:
+                  cpu: 1000
+                  ram: 1073741824
+              topology_spread_constraints:
+              - max_skew: 1
+                topology_key: zone
+                when_unsatisfiable: {}
+                label_selector:
+                  match_labels:
+                    pod_group: pod_group_1
+          target_resources_usage:
+            cpu_utilization: 0.6
+          resources_usage_model_config:
+            cpu_config:
+              model_name: constant
+              config: |
+                usage: 0.5
+  "#,
+        when_unsatisfiable
+    ))
+    .unwrap()
+}
+
+fn pods_per_zone(when_unsatisfiable: &str) -> HashMap<String, usize> {
+    let mut kube_sim =
+        KubernetriksSimulation::new(Rc::new(default_test_simulation_config(None))).unwrap();
+    kube_sim.initialize(
+        &mut get_cluster_trace(),
+        &mut get_workload_trace(when_unsatisfiable),
+    );
+    kube_sim.step_until_time(100.0);
+
+    let persistent_storage_borrowed = kube_sim.persistent_storage.borrow();
+    let mut pods_per_zone: HashMap<String, usize> = Default::default();
+    for idx in 0..6 {
+        let pod = persistent_storage_borrowed
+            .get_pod(&format!("pod_group_1_{}", idx))
+            .unwrap();
+        let zone = match pod.status.assigned_node.as_str() {
+            "node_1" | "node_2" => "zone_a",
+            "node_3" => "zone_b",
+            "node_4" => "zone_c",
+            other => panic!("pod is assigned to unexpected node {:?}", other),
+        };
+        *pods_per_zone.entry(zone.to_string()).or_default() += 1;
+    }
+    pods_per_zone
+}
+
+#[test]
+fn test_pod_group_is_spread_evenly_with_do_not_schedule_constraint() {
+    let pods_per_zone = pods_per_zone("DoNotSchedule");
+    assert_eq!(3, pods_per_zone.len());
+    assert!(pods_per_zone.values().all(|&count| count == 2));
+}
+
+#[test]
+fn test_pod_group_is_spread_with_schedule_anyway_constraint() {
+    let pods_per_zone = pods_per_zone("ScheduleAnyway");
+    assert_eq!(3, pods_per_zone.len());
+    let max_count = *pods_per_zone.values().max().unwrap();
+    let min_count = *pods_per_zone.values().min().unwrap();
+    assert!(max_count - min_count <= 1);
+}